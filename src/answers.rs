@@ -0,0 +1,213 @@
+use crate::error::ParseError;
+use crate::runner::{PartResult, Status};
+use crate::solution::Answer;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Expected answers, keyed by input file, day and part.
+///
+/// The file has one answer per line, `<input file> <day> <part> <answer>`. Line breaks in
+/// multi-line answers are written as `\n`. Empty lines and lines starting with `#` are ignored.
+#[derive(Debug, Default)]
+pub struct Answers {
+    expected: HashMap<(String, u8, u8), String>,
+}
+
+pub fn default_path() -> PathBuf {
+    PathBuf::from("answers.txt")
+}
+
+/// Encodes an answer the way it is written in the answers file.
+pub fn escape(answer: &Answer) -> String {
+    answer.to_string().replace('\n', "\\n")
+}
+
+impl Answers {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let error = |token: &str, expected: &str| {
+            ParseError::at(text.as_bytes(), token.as_bytes(), expected)
+        };
+        let mut entries = HashMap::new();
+
+        for line in text.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(4, ' ');
+            let mut field = |expected: &str| {
+                fields
+                    .next()
+                    .filter(|field| !field.is_empty())
+                    .ok_or_else(|| ParseError::end_of(text.as_bytes(), line.as_bytes(), expected))
+            };
+            let input = field("an input file")?;
+            let day = field("a day")?;
+            let part = field("a part")?;
+            let answer = field("an answer")?;
+
+            let day = match day.parse() {
+                Ok(day) if (1..=crate::NUM_DAYS).contains(&day) => day,
+                _ => return Err(error(day, "a day from 1 to 25")),
+            };
+            let part = match part {
+                "1" => 1,
+                "2" => 2,
+                _ => return Err(error(part, "a part, 1 or 2")),
+            };
+
+            entries.insert(
+                (input.to_string(), day, part),
+                answer.trim_end().to_string(),
+            );
+        }
+
+        Ok(Answers { expected: entries })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+        Self::parse(&text)
+            .map_err(|err| format!("invalid answers file {}: {}", path.display(), err))
+    }
+
+    pub fn get(&self, input: &str, day: u8, part: u8) -> Option<&str> {
+        self.expected
+            .get(&(input.to_string(), day, part))
+            .map(String::as_str)
+    }
+
+    /// Compares a result with the expected answer, or returns `None` for parts that do not exist.
+    pub fn check(&self, input: &str, result: &PartResult) -> Option<Verdict> {
+        if result.status == Status::Ok && result.answer == Answer::NoPart {
+            return None;
+        }
+
+        Some(match self.get(input, result.day, result.part) {
+            None => Verdict::Missing,
+            Some(expected) if result.status == Status::Ok && escape(&result.answer) == expected => {
+                Verdict::Pass
+            }
+            Some(expected) => Verdict::Fail(expected.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    /// Contains the expected answer.
+    Fail(String),
+    Missing,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Summary {
+    pub passed: usize,
+    pub failed: usize,
+    pub missing: usize,
+}
+
+impl Summary {
+    pub fn add(&mut self, verdict: &Verdict) {
+        match verdict {
+            Verdict::Pass => self.passed += 1,
+            Verdict::Fail(_) => self.failed += 1,
+            Verdict::Missing => self.missing += 1,
+        }
+    }
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} passed, {} failed, {} missing",
+            self.passed, self.failed, self.missing
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const ANSWERS: &str = "\
+# input day part answer
+inputs/day05.txt 5 1 CMZ
+inputs/day10.txt 10 2 #.\\n.#
+
+other.txt 5 1 MCD
+";
+
+    fn result(day: u8, part: u8, answer: Answer, status: Status) -> PartResult {
+        PartResult {
+            day,
+            part,
+            answer,
+            status,
+            parse_time: Duration::ZERO,
+            solve_time: Duration::ZERO,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(answers.get("inputs/day05.txt", 5, 1), Some("CMZ"));
+        assert_eq!(answers.get("other.txt", 5, 1), Some("MCD"));
+        assert_eq!(answers.get("inputs/day10.txt", 10, 2), Some("#.\\n.#"));
+        assert_eq!(answers.get("inputs/day05.txt", 5, 2), None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = Answers::parse("a.txt 1 1 5\na.txt 26 1 5\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 7, "26")
+        );
+        let error = Answers::parse("a.txt 1 2\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 10));
+    }
+
+    #[test]
+    fn test_check() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        let text = |text: &str| Answer::Text(String::from(text));
+
+        let pass = result(5, 1, text("CMZ"), Status::Ok);
+        assert_eq!(
+            answers.check("inputs/day05.txt", &pass),
+            Some(Verdict::Pass)
+        );
+        assert_eq!(
+            answers.check("other.txt", &pass),
+            Some(Verdict::Fail(String::from("MCD")))
+        );
+        assert_eq!(answers.check("mine.txt", &pass), Some(Verdict::Missing));
+
+        let art = result(10, 2, Answer::Art(String::from("#.\n.#\n")), Status::Ok);
+        assert_eq!(answers.check("inputs/day10.txt", &art), Some(Verdict::Pass));
+
+        let timeout = result(5, 1, Answer::None, Status::Timeout);
+        assert_eq!(
+            answers.check("inputs/day05.txt", &timeout),
+            Some(Verdict::Fail(String::from("CMZ")))
+        );
+
+        let nonexistent = result(25, 2, Answer::NoPart, Status::Ok);
+        assert_eq!(answers.check("inputs/day25.txt", &nonexistent), None);
+
+        // A part whose input has no answer is still compared with the expected one.
+        let none = result(5, 1, Answer::None, Status::Ok);
+        assert_eq!(
+            answers.check("inputs/day05.txt", &none),
+            Some(Verdict::Fail(String::from("CMZ")))
+        );
+        assert_eq!(answers.check("mine.txt", &none), Some(Verdict::Missing));
+    }
+}
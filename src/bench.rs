@@ -0,0 +1,200 @@
+use crate::error::ParseError;
+use crate::report::format_duration;
+use crate::solution::{Day, DayParams};
+use std::fmt;
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Config {
+    /// How long to run the function before measuring, to warm up caches and the branch predictor.
+    pub warmup: Duration,
+    /// Rough time budget for the measurement; slow functions take fewer samples.
+    pub measurement: Duration,
+    pub samples: usize,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            warmup: Duration::from_millis(200),
+            measurement: Duration::from_secs(2),
+            samples: 50,
+        }
+    }
+}
+
+/// Minimum number of samples, even if the function is slower than the time budget.
+const MIN_SAMPLES: usize = 5;
+
+#[derive(Debug, Clone)]
+pub struct Summary {
+    pub name: String,
+    pub samples: usize,
+    /// Number of calls per sample; the times below are per call.
+    pub iterations: u64,
+    pub median: Duration,
+    /// Median absolute deviation from the median.
+    pub spread: Duration,
+    pub min: Duration,
+    pub max: Duration,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let relative = if self.median.is_zero() {
+            0.0
+        } else {
+            100.0 * self.spread.as_secs_f64() / self.median.as_secs_f64()
+        };
+        write!(
+            f,
+            "{:<32} {:>10} ± {:>9} ({:>4.1}%)  [{} .. {}]  {} x {}",
+            self.name,
+            format_duration(self.median),
+            format_duration(self.spread),
+            relative,
+            format_duration(self.min),
+            format_duration(self.max),
+            self.samples,
+            self.iterations,
+        )
+    }
+}
+
+fn median(sorted: &[Duration]) -> Duration {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[mid - 1] + sorted[mid]) / 2
+    } else {
+        sorted[mid]
+    }
+}
+
+fn summarize(name: &str, iterations: u64, mut times: Vec<Duration>) -> Summary {
+    times.sort_unstable();
+    let median = median(&times);
+
+    let mut deviations: Vec<Duration> = times.iter().map(|time| time.abs_diff(median)).collect();
+    deviations.sort_unstable();
+
+    Summary {
+        name: name.to_string(),
+        samples: times.len(),
+        iterations,
+        median,
+        spread: self::median(&deviations),
+        min: times[0],
+        max: times[times.len() - 1],
+    }
+}
+
+/// Measures `f` with the given configuration.
+pub fn measure<T>(name: &str, config: &Config, mut f: impl FnMut() -> T) -> Summary {
+    let start = Instant::now();
+    let mut calls = 0u64;
+    while calls == 0 || start.elapsed() < config.warmup {
+        black_box(f());
+        calls += 1;
+    }
+    let estimate = start.elapsed() / calls as u32;
+
+    let sample_time = config.measurement / config.samples.max(1) as u32;
+    let iterations = if estimate.is_zero() {
+        1000
+    } else {
+        (sample_time.as_nanos() / estimate.as_nanos()).clamp(1, 1_000_000) as u64
+    };
+    let samples = if iterations > 1 {
+        config.samples
+    } else {
+        let affordable = config.measurement.as_nanos() / estimate.as_nanos().max(1);
+        (affordable as usize).clamp(MIN_SAMPLES, config.samples.max(MIN_SAMPLES))
+    };
+
+    let times = (0..samples)
+        .map(|_| {
+            let start = Instant::now();
+            for _ in 0..iterations {
+                black_box(f());
+            }
+            start.elapsed() / iterations as u32
+        })
+        .collect();
+
+    summarize(name, iterations, times)
+}
+
+/// Measures `f` with the default configuration and prints the summary.
+///
+/// This is used by the `bench_*` tests, which are ignored by default:
+/// `cargo test --release -- --ignored --test-threads=1 --nocapture bench_`
+pub fn bench<T>(name: &str, f: impl FnMut() -> T) {
+    println!("{}", measure(name, &Config::default(), f));
+}
+
+/// Measures parsing and each of the given parts of a single day.
+pub fn bench_day(
+    day: &Day,
+    input: &[u8],
+    params: &DayParams,
+    parts: &[u8],
+    config: &Config,
+) -> Result<Vec<Summary>, ParseError> {
+    let mut summaries = vec![measure(
+        &format!("day{:02}::parse", day.number),
+        config,
+        || day.parse(input),
+    )];
+
+    let parsed = day.parse(input)?;
+    for &part in parts {
+        summaries.push(measure(
+            &format!("day{:02}::part{}", day.number, part),
+            config,
+            || day.solve(&parsed, params, part),
+        ));
+    }
+
+    Ok(summaries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summarize() {
+        let times = [5, 1, 4, 2, 3, 100].map(Duration::from_micros).to_vec();
+        let summary = summarize("sum", 10, times);
+        assert_eq!(summary.samples, 6);
+        assert_eq!(
+            summary.median,
+            Duration::from_micros(3) + Duration::from_nanos(500)
+        );
+        assert_eq!(
+            summary.spread,
+            Duration::from_micros(1) + Duration::from_nanos(500)
+        );
+        assert_eq!(summary.min, Duration::from_micros(1));
+        assert_eq!(summary.max, Duration::from_micros(100));
+    }
+
+    #[test]
+    fn test_measure() {
+        let config = Config {
+            warmup: Duration::from_millis(1),
+            measurement: Duration::from_millis(10),
+            samples: 10,
+        };
+        let mut calls = 0;
+        let summary = measure("sleep", &config, || {
+            calls += 1;
+            std::thread::sleep(Duration::from_millis(2))
+        });
+        assert_eq!(summary.iterations, 1);
+        assert_eq!(summary.samples, MIN_SAMPLES);
+        assert!(summary.min >= Duration::from_millis(2));
+        assert!(calls > MIN_SAMPLES);
+    }
+}
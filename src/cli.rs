@@ -0,0 +1,720 @@
+use crate::client::Remote;
+use crate::input::Source;
+use crate::params::{self, Overrides};
+use crate::report::Format;
+use crate::runner::{self, Options};
+use crate::NUM_DAYS;
+use crate::{answers, bench, fetch, submit};
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "\
+Usage: aoc-y2022 [COMMAND] [OPTIONS]
+
+Commands:
+  run     Solve puzzles (default)
+  verify  Check answers against an answers file
+  bench   Benchmark parsing and solving
+  fetch   Download puzzle inputs
+  submit  Solve a part and submit the answer
+  new     Create the module for a new day
+  gen     Print a random input for a day
+  list    List all days
+  help    Print this message
+
+Options for run:
+  -d, --day <DAYS>    Days to solve, e.g. `17`, `1-10` or `1,3,5-7` (default: all)
+  -p, --part <PART>   Part to solve, `1` or `2` (default: both)
+  -i, --input <PATH>  Read the input from PATH, or from stdin if PATH is `-`
+                      (default: inputs/dayNN.txt; requires a single day)
+  -e, --example       Read the example from the puzzle description, examples/dayNN.txt
+  -f, --format <FMT>  Output format: `text`, `json` or `csv` (default: text)
+  -t, --timeout <S>   Give up on a day after S seconds
+  -j, --jobs <N>      Solve up to N days at the same time, or one per CPU if N
+                      is `0` (default: 1)
+      --timings       Print a table of parse and solve times, slowest day first
+      --param <NAME=VALUE>  Change a puzzle parameter of the selected days, e.g.
+                      `--day 15 --param row=10`; can be repeated
+      --params <PATH>  Read puzzle parameters per input from PATH (default: params.txt)
+
+Options for verify:
+  -d, --day, -p, --part, -i, --input, -e, --example, -t, --timeout, -j, --jobs,
+  --param and --params as for run
+  -a, --answers <PATH>  Read the expected answers from PATH (default: answers.txt)
+
+Options for bench:
+  -d, --day, -p, --part, -i, --input, -e, --example, --param and --params as for run
+  -s, --samples <N>   Number of samples per measurement (default: 50)
+
+Options for fetch:
+  -d, --day <DAYS>    Days to download, as for run (required)
+      --force         Overwrite existing inputs and download again instead of using the cache
+      --url <URL>     Base URL of the puzzle server (default: https://adventofcode.com)
+      --session <PATH>  Read the session cookie from PATH (default: .session)
+      --cache <DIR>   Keep downloaded inputs in DIR (default: .cache)
+
+Options for submit:
+  -d, --day <DAY>     Day to submit (required)
+  -p, --part <PART>   Part to submit (required)
+      --answer <VALUE>  Submit VALUE instead of solving the part
+      --url, --session  as for fetch
+      --submissions <PATH>  Record submitted answers in PATH (default: submissions.txt)
+
+Options for new:
+  -d, --day <DAY>     Day to create (required)
+      --title <TITLE> Title of the puzzle (default: read from the puzzle description)
+      --url, --session, --cache  as for fetch, to download the title and input if
+                      the session file exists
+
+Options for gen:
+  -d, --day <DAY>     Day to generate an input for (required)
+      --seed <S>      Seed of the random numbers, the same seed gives the same input
+                      (default: 0)
+      --size <N>      Size of the input, e.g. the valves with a flow rate on day 16
+                      (default: about the size of the puzzle inputs)";
+
+#[derive(Debug, Eq, PartialEq)]
+pub enum Command {
+    Run {
+        days: Vec<u8>,
+        parts: Vec<u8>,
+        source: Source,
+        format: Format,
+        /// The parameters given on the command line are in `options.params`.
+        options: Options,
+        params: PathBuf,
+        timings: bool,
+    },
+    Verify {
+        days: Vec<u8>,
+        parts: Vec<u8>,
+        source: Source,
+        answers: PathBuf,
+        options: Options,
+        params: PathBuf,
+    },
+    Bench {
+        days: Vec<u8>,
+        parts: Vec<u8>,
+        source: Source,
+        config: bench::Config,
+        /// Given on the command line.
+        overrides: Overrides,
+        params: PathBuf,
+    },
+    Fetch {
+        days: Vec<u8>,
+        remote: Remote,
+        cache: PathBuf,
+        force: bool,
+    },
+    Submit {
+        day: u8,
+        part: u8,
+        /// Submitted instead of the computed answer, e.g. for answers drawn as art.
+        answer: Option<String>,
+        remote: Remote,
+        submissions: PathBuf,
+    },
+    New {
+        day: u8,
+        /// Read from the puzzle description if not given.
+        title: Option<String>,
+        remote: Remote,
+        cache: PathBuf,
+    },
+    Gen {
+        day: u8,
+        seed: u64,
+        /// The default size of the day if not given.
+        size: Option<usize>,
+    },
+    List,
+    Help,
+}
+
+fn parse_day(spec: &str) -> Result<u8, String> {
+    match spec.trim().parse::<u8>() {
+        Ok(day) if (1..=NUM_DAYS).contains(&day) => Ok(day),
+        _ => Err(format!(
+            "invalid day `{}`, expected a number from 1 to {}",
+            spec, NUM_DAYS
+        )),
+    }
+}
+
+fn parse_days(spec: &str) -> Result<Vec<u8>, String> {
+    let mut days = Vec::new();
+
+    for range in spec.split(',') {
+        if let Some((first, last)) = range.split_once('-') {
+            let (first, last) = (parse_day(first)?, parse_day(last)?);
+            if first > last {
+                return Err(format!("invalid day range `{}`", range));
+            }
+            days.extend(first..=last);
+        } else {
+            days.push(parse_day(range)?);
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+
+    Ok(days)
+}
+
+fn parse_format(spec: &str) -> Result<Format, String> {
+    Format::from_arg(spec)
+        .ok_or_else(|| format!("invalid format `{}`, expected text, json or csv", spec))
+}
+
+fn parse_timeout(spec: &str) -> Result<Duration, String> {
+    match spec.parse::<f64>().map(Duration::try_from_secs_f64) {
+        Ok(Ok(timeout)) if !timeout.is_zero() => Ok(timeout),
+        _ => Err(format!(
+            "invalid timeout `{}`, expected a positive number of seconds",
+            spec
+        )),
+    }
+}
+
+fn parse_jobs(spec: &str) -> Result<usize, String> {
+    match spec.parse::<usize>() {
+        Ok(0) => Ok(runner::available_jobs()),
+        Ok(jobs) => Ok(jobs),
+        _ => Err(format!(
+            "invalid number of jobs `{}`, expected a number",
+            spec
+        )),
+    }
+}
+
+fn parse_samples(spec: &str) -> Result<usize, String> {
+    match spec.parse::<usize>() {
+        Ok(samples) if samples > 0 => Ok(samples),
+        _ => Err(format!(
+            "invalid number of samples `{}`, expected a positive number",
+            spec
+        )),
+    }
+}
+
+fn parse_seed(spec: &str) -> Result<u64, String> {
+    spec.parse()
+        .map_err(|_| format!("invalid seed `{}`, expected a number", spec))
+}
+
+fn parse_size(spec: &str) -> Result<usize, String> {
+    spec.parse()
+        .map_err(|_| format!("invalid size `{}`, expected a number", spec))
+}
+
+fn parse_param(spec: &str) -> Result<(String, String), String> {
+    match spec.split_once('=') {
+        Some((name, value)) if !name.is_empty() => Ok((name.to_string(), value.to_string())),
+        _ => Err(format!("invalid parameter `{}`, expected NAME=VALUE", spec)),
+    }
+}
+
+fn parse_part(spec: &str) -> Result<Vec<u8>, String> {
+    match spec {
+        "1" => Ok(vec![1]),
+        "2" => Ok(vec![2]),
+        _ => Err(format!("invalid part `{}`, expected 1 or 2", spec)),
+    }
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, String> {
+    let mut args = args.into_iter().peekable();
+
+    let command = match args.peek().map(String::as_str) {
+        Some("run") => {
+            args.next();
+            "run"
+        }
+        Some("verify") => {
+            args.next();
+            "verify"
+        }
+        Some("bench") => {
+            args.next();
+            "bench"
+        }
+        Some("list") => {
+            args.next();
+            "list"
+        }
+        Some("fetch") => {
+            args.next();
+            "fetch"
+        }
+        Some("submit") => {
+            args.next();
+            "submit"
+        }
+        Some("new") => {
+            args.next();
+            "new"
+        }
+        Some("gen") => {
+            args.next();
+            "gen"
+        }
+        Some("help" | "-h" | "--help") => return Ok(Command::Help),
+        _ => "run",
+    };
+
+    let mut days: Option<Vec<u8>> = None;
+    let mut parts: Option<Vec<u8>> = None;
+    let mut source = Source::Default;
+    let mut format = Format::Text;
+    let mut options = Options::default();
+    let mut timings = false;
+    let mut config = bench::Config::default();
+    let mut answers = answers::default_path();
+    let mut params = params::default_path();
+    let mut remote = Remote::default();
+    let mut cache = fetch::default_cache_dir();
+    let mut force = false;
+    let mut answer = None;
+    let mut submissions = submit::default_path();
+    let mut title = None;
+    let mut seed = 0;
+    let mut size = None;
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) => (flag.to_string(), Some(value.to_string())),
+            None => (arg, None),
+        };
+        let mut value = || {
+            inline_value
+                .clone()
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("missing value for `{}`", flag))
+        };
+
+        match (command, flag.as_str()) {
+            (_, "-h" | "--help") => return Ok(Command::Help),
+            ("run" | "verify" | "bench" | "fetch" | "submit" | "new" | "gen", "-d" | "--day") => {
+                days = Some(parse_days(&value()?)?)
+            }
+            ("run" | "verify" | "bench" | "submit", "-p" | "--part") => {
+                parts = Some(parse_part(&value()?)?)
+            }
+            ("run" | "verify" | "bench", "-i" | "--input") => source = Source::from_arg(&value()?),
+            ("run" | "verify" | "bench", "-e" | "--example") if inline_value.is_none() => {
+                source = Source::Example
+            }
+            ("run", "-f" | "--format") => format = parse_format(&value()?)?,
+            ("run" | "verify", "-t" | "--timeout") => {
+                options.timeout = Some(parse_timeout(&value()?)?)
+            }
+            ("run" | "verify", "-j" | "--jobs") => options.jobs = parse_jobs(&value()?)?,
+            ("verify", "-a" | "--answers") => answers = PathBuf::from(value()?),
+            ("run" | "verify" | "bench", "--param") => {
+                let (name, value) = parse_param(&value()?)?;
+                options.params.set(&name, &value)
+            }
+            ("run" | "verify" | "bench", "--params") => params = PathBuf::from(value()?),
+            ("run", "--timings") if inline_value.is_none() => timings = true,
+            ("bench", "-s" | "--samples") => config.samples = parse_samples(&value()?)?,
+            ("fetch" | "submit" | "new", "--url") => remote.url = value()?,
+            ("fetch" | "submit" | "new", "--session") => remote.session = PathBuf::from(value()?),
+            ("new", "--title") => title = Some(value()?),
+            ("submit", "--answer") => answer = Some(value()?),
+            ("submit", "--submissions") => submissions = PathBuf::from(value()?),
+            ("fetch" | "new", "--cache") => cache = PathBuf::from(value()?),
+            ("fetch", "--force") if inline_value.is_none() => force = true,
+            ("gen", "--seed") => seed = parse_seed(&value()?)?,
+            ("gen", "--size") => size = Some(parse_size(&value()?)?),
+            _ => return Err(format!("unexpected argument `{}`", flag)),
+        }
+    }
+
+    if command == "fetch" {
+        let days = days.ok_or_else(|| String::from("`fetch` requires `--day`"))?;
+        return Ok(Command::Fetch {
+            days,
+            remote,
+            cache,
+            force,
+        });
+    }
+
+    if command == "submit" {
+        let (Some([day]), Some([part])) = (days.as_deref(), parts.as_deref()) else {
+            return Err(String::from(
+                "`submit` requires a single `--day` and `--part`",
+            ));
+        };
+        return Ok(Command::Submit {
+            day: *day,
+            part: *part,
+            answer,
+            remote,
+            submissions,
+        });
+    }
+
+    if command == "new" {
+        let Some([day]) = days.as_deref() else {
+            return Err(String::from("`new` requires a single `--day`"));
+        };
+        return Ok(Command::New {
+            day: *day,
+            title,
+            remote,
+            cache,
+        });
+    }
+
+    if command == "gen" {
+        let Some([day]) = days.as_deref() else {
+            return Err(String::from("`gen` requires a single `--day`"));
+        };
+        return Ok(Command::Gen {
+            day: *day,
+            seed,
+            size,
+        });
+    }
+
+    let days = days.unwrap_or_else(|| (1..=NUM_DAYS).collect());
+    let parts = parts.unwrap_or_else(|| vec![1, 2]);
+    if matches!(source, Source::File(_) | Source::Stdin) && days.len() != 1 {
+        return Err(String::from("`--input` requires exactly one day"));
+    }
+
+    match command {
+        "verify" => Ok(Command::Verify {
+            days,
+            parts,
+            source,
+            answers,
+            options,
+            params,
+        }),
+        "bench" => Ok(Command::Bench {
+            days,
+            parts,
+            source,
+            config,
+            overrides: options.params,
+            params,
+        }),
+        "list" => Ok(Command::List),
+        _ => Ok(Command::Run {
+            days,
+            parts,
+            source,
+            format,
+            options,
+            params,
+            timings,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_defaults_to_all_days() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Command::Run {
+                days: (1..=25).collect(),
+                parts: vec![1, 2],
+                source: Source::Default,
+                format: Format::Text,
+                options: Options::default(),
+                params: params::default_path(),
+                timings: false
+            })
+        )
+    }
+
+    #[test]
+    fn test_run_single_part() {
+        assert_eq!(
+            parse(&["run", "--day", "17", "--part", "2"]),
+            Ok(Command::Run {
+                days: vec![17],
+                parts: vec![2],
+                source: Source::Default,
+                format: Format::Text,
+                options: Options::default(),
+                params: params::default_path(),
+                timings: false
+            })
+        )
+    }
+
+    #[test]
+    fn test_run_day_ranges() {
+        assert_eq!(
+            parse(&["run", "-d=8-10,1,3", "-p", "1"]),
+            Ok(Command::Run {
+                days: vec![1, 3, 8, 9, 10],
+                parts: vec![1],
+                source: Source::Default,
+                format: Format::Text,
+                options: Options::default(),
+                params: params::default_path(),
+                timings: false
+            })
+        )
+    }
+
+    #[test]
+    fn test_run_with_input() {
+        assert_eq!(
+            parse(&["run", "--day", "5", "--input", "-"]),
+            Ok(Command::Run {
+                days: vec![5],
+                parts: vec![1, 2],
+                source: Source::Stdin,
+                format: Format::Text,
+                options: Options::default(),
+                params: params::default_path(),
+                timings: false
+            })
+        );
+        assert!(parse(&["run", "--day", "1-2", "--input", "other.txt"]).is_err());
+
+        let Ok(Command::Verify { days, source, .. }) = parse(&["verify", "--example"]) else {
+            panic!("expected a verify command")
+        };
+        assert_eq!((days.len(), source), (25, Source::Example));
+    }
+
+    #[test]
+    fn test_run_with_format_and_timeout() {
+        assert_eq!(
+            parse(&["run", "--format", "json", "--timeout", "2.5"]),
+            Ok(Command::Run {
+                days: (1..=25).collect(),
+                parts: vec![1, 2],
+                source: Source::Default,
+                format: Format::Json,
+                options: Options {
+                    timeout: Some(Duration::from_millis(2500)),
+                    ..Options::default()
+                },
+                params: params::default_path(),
+                timings: false
+            })
+        );
+        assert!(parse(&["run", "--format", "xml"]).is_err());
+        assert!(parse(&["run", "--timeout", "-1"]).is_err());
+        assert!(parse(&["run", "--timeout", "1e300"]).is_err());
+        assert!(parse(&["run", "--timeout", "inf"]).is_err());
+    }
+
+    #[test]
+    fn test_run_with_jobs() {
+        let Ok(Command::Verify { options, .. }) = parse(&["verify", "-j", "4"]) else {
+            panic!("expected a verify command")
+        };
+        assert_eq!(options.jobs, 4);
+        let Ok(Command::Run { options, .. }) = parse(&["--jobs=0"]) else {
+            panic!("expected a run command")
+        };
+        assert_eq!(options.jobs, runner::available_jobs());
+        assert!(parse(&["run", "--jobs", "many"]).is_err());
+        assert!(parse(&["bench", "--jobs", "2"]).is_err());
+    }
+
+    #[test]
+    fn test_run_with_timings() {
+        assert_eq!(
+            parse(&["--timings", "-d", "3"]),
+            Ok(Command::Run {
+                days: vec![3],
+                parts: vec![1, 2],
+                source: Source::Default,
+                format: Format::Text,
+                options: Options::default(),
+                params: params::default_path(),
+                timings: true
+            })
+        );
+        assert!(parse(&["--timings=yes"]).is_err());
+    }
+
+    #[test]
+    fn test_params() {
+        let Ok(Command::Run {
+            options, params, ..
+        }) = parse(&[
+            "-d",
+            "15",
+            "--param",
+            "row=10",
+            "--param=max_coordinate=20",
+            "--params",
+            "mine.txt",
+        ])
+        else {
+            panic!("expected a run command")
+        };
+        let mut expected = Overrides::default();
+        expected.set("row", "10");
+        expected.set("max_coordinate", "20");
+        assert_eq!(
+            (options.params, params),
+            (expected, PathBuf::from("mine.txt"))
+        );
+
+        let Ok(Command::Bench { overrides, .. }) = parse(&["bench", "--param", "rounds=5"]) else {
+            panic!("expected a bench command")
+        };
+        assert_eq!(overrides.get("inputs/day11.txt", 11).len(), 1);
+        assert!(parse(&["run", "--param", "row"]).is_err());
+        assert!(parse(&["run", "--param", "=10"]).is_err());
+        assert!(parse(&["fetch", "-d", "1", "--param", "row=10"]).is_err());
+    }
+
+    #[test]
+    fn test_verify() {
+        assert_eq!(
+            parse(&[
+                "verify",
+                "-d",
+                "5",
+                "-i",
+                "mine.txt",
+                "--answers",
+                "mine-answers.txt"
+            ]),
+            Ok(Command::Verify {
+                days: vec![5],
+                parts: vec![1, 2],
+                source: Source::File(PathBuf::from("mine.txt")),
+                answers: PathBuf::from("mine-answers.txt"),
+                options: Options::default(),
+                params: params::default_path()
+            })
+        );
+        assert!(parse(&["verify", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_bench() {
+        assert_eq!(
+            parse(&["bench", "-d", "16", "--samples", "10"]),
+            Ok(Command::Bench {
+                days: vec![16],
+                parts: vec![1, 2],
+                source: Source::Default,
+                config: bench::Config {
+                    samples: 10,
+                    ..bench::Config::default()
+                },
+                overrides: Overrides::default(),
+                params: params::default_path()
+            })
+        );
+        assert!(parse(&["bench", "--samples", "0"]).is_err());
+        assert!(parse(&["bench", "--format", "json"]).is_err());
+    }
+
+    #[test]
+    fn test_fetch() {
+        assert_eq!(
+            parse(&[
+                "fetch",
+                "-d",
+                "3-4",
+                "--force",
+                "--url=http://localhost:8080"
+            ]),
+            Ok(Command::Fetch {
+                days: vec![3, 4],
+                remote: Remote {
+                    url: String::from("http://localhost:8080"),
+                    ..Remote::default()
+                },
+                cache: PathBuf::from(".cache"),
+                force: true
+            })
+        );
+        assert!(parse(&["fetch"]).is_err());
+        assert!(parse(&["fetch", "-d", "1", "--part", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_submit() {
+        assert_eq!(
+            parse(&["submit", "-d", "10", "-p", "2", "--answer", "RUAKHBEK"]),
+            Ok(Command::Submit {
+                day: 10,
+                part: 2,
+                answer: Some(String::from("RUAKHBEK")),
+                remote: Remote::default(),
+                submissions: PathBuf::from("submissions.txt")
+            })
+        );
+        assert!(parse(&["submit", "-d", "10"]).is_err());
+        assert!(parse(&["submit", "-d", "1-2", "-p", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_new() {
+        assert_eq!(
+            parse(&["new", "--day", "7", "--title", "No Space Left On Device"]),
+            Ok(Command::New {
+                day: 7,
+                title: Some(String::from("No Space Left On Device")),
+                remote: Remote::default(),
+                cache: PathBuf::from(".cache")
+            })
+        );
+        assert!(parse(&["new"]).is_err());
+    }
+
+    #[test]
+    fn test_gen() {
+        assert_eq!(
+            parse(&["gen", "--day", "16", "--seed", "42", "--size=30"]),
+            Ok(Command::Gen {
+                day: 16,
+                seed: 42,
+                size: Some(30)
+            })
+        );
+        assert_eq!(
+            parse(&["gen", "-d", "7"]),
+            Ok(Command::Gen {
+                day: 7,
+                seed: 0,
+                size: None
+            })
+        );
+        assert!(parse(&["gen"]).is_err());
+        assert!(parse(&["gen", "-d", "1-2"]).is_err());
+        assert!(parse(&["gen", "-d", "1", "--seed", "-1"]).is_err());
+        assert!(parse(&["run", "--seed", "1"]).is_err());
+    }
+
+    #[test]
+    fn test_list() {
+        assert_eq!(parse(&["list"]), Ok(Command::List))
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        assert!(parse(&["run", "--day", "26"]).is_err());
+        assert!(parse(&["run", "--day", "10-1"]).is_err());
+        assert!(parse(&["run", "--part", "3"]).is_err());
+        assert!(parse(&["run", "--day"]).is_err());
+        assert!(parse(&["list", "--day", "1"]).is_err());
+    }
+}
@@ -0,0 +1,335 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub const YEAR: u16 = 2022;
+pub const DEFAULT_URL: &str = "https://adventofcode.com";
+
+/// Where to reach the puzzle server and how to log in.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Remote {
+    /// Base URL without trailing slash, e.g. `https://adventofcode.com`.
+    pub url: String,
+    /// File with the session cookie, see [`Session::parse`].
+    pub session: PathBuf,
+}
+
+impl Default for Remote {
+    fn default() -> Self {
+        Remote {
+            url: String::from(DEFAULT_URL),
+            session: PathBuf::from(".session"),
+        }
+    }
+}
+
+/// The value of the `session` cookie of a logged-in browser.
+#[derive(Clone, Eq, PartialEq)]
+pub struct Session(String);
+
+impl std::fmt::Debug for Session {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("Session(..)")
+    }
+}
+
+impl Session {
+    /// Accepts either the bare cookie value or a `SESSION=<value>` line.
+    pub fn parse(text: &str) -> Result<Self, String> {
+        let value = text
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default();
+        let value = value.strip_prefix("SESSION=").unwrap_or(value);
+        let value = value.trim_matches(|c| c == '"' || c == '\'');
+
+        if value.is_empty() {
+            return Err(String::from("the session cookie is empty"));
+        }
+        if !value.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(String::from(
+                "the session cookie must consist of hexadecimal digits",
+            ));
+        }
+        Ok(Session(value.to_string()))
+    }
+
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read session from {}: {}", path.display(), err))?;
+        Self::parse(&text).map_err(|err| format!("invalid session in {}: {}", path.display(), err))
+    }
+}
+
+/// A blocking HTTP client for the puzzle server.
+pub struct Client {
+    url: String,
+    session: Session,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    /// Creates a client for `remote`, reading its session file.
+    pub fn connect(remote: &Remote) -> Result<Self, String> {
+        Ok(Self::new(&remote.url, Session::load(&remote.session)?))
+    }
+
+    pub fn new(url: &str, session: Session) -> Self {
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .user_agent(concat!(
+                env!("CARGO_PKG_NAME"),
+                "/",
+                env!("CARGO_PKG_VERSION")
+            ))
+            .build();
+        Client {
+            url: url.trim_end_matches('/').to_string(),
+            session,
+            agent,
+        }
+    }
+
+    pub fn day_url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}", self.url, YEAR, day)
+    }
+
+    /// Downloads the puzzle input of `day`.
+    pub fn input(&self, day: u8) -> Result<Vec<u8>, String> {
+        let url = format!("{}/input", self.day_url(day));
+        let request = self.agent.get(&url);
+        let (content_type, body) = self.send(&url, request, None)?;
+
+        if is_web_page(&content_type, &body) {
+            return Err(format!("{} returned a web page instead of an input", url));
+        }
+        if body.trim_ascii().is_empty() {
+            return Err(format!("{} returned an empty input", url));
+        }
+        Ok(body)
+    }
+
+    /// Reads the title of `day` from its puzzle description.
+    pub fn title(&self, day: u8) -> Result<String, String> {
+        let url = self.day_url(day);
+        let request = self.agent.get(&url);
+        let (_, page) = self.send(&url, request, None)?;
+        let page = String::from_utf8_lossy(&page).into_owned();
+
+        let prefix = format!("--- Day {}: ", day);
+        page.split_once(&prefix)
+            .and_then(|(_, rest)| rest.split_once(" ---"))
+            .map(|(title, _)| title.to_string())
+            .ok_or_else(|| format!("cannot find the title of day {} in {}", day, url))
+    }
+
+    /// Posts a form to `url` and returns the body of the response.
+    pub fn post_form(&self, url: &str, form: &[(&str, &str)]) -> Result<Vec<u8>, String> {
+        let request = self.agent.post(url);
+        self.send(url, request, Some(form)).map(|(_, body)| body)
+    }
+
+    /// Sends `request` and returns the content type and body of the response.
+    fn send(
+        &self,
+        url: &str,
+        request: ureq::Request,
+        form: Option<&[(&str, &str)]>,
+    ) -> Result<(String, Vec<u8>), String> {
+        let request = request.set("Cookie", &format!("session={}", self.session.0));
+        let response = match form {
+            Some(form) => request.send_form(form),
+            None => request.call(),
+        };
+
+        match response {
+            Ok(response) => {
+                let content_type = response.content_type().to_ascii_lowercase();
+                let mut body = Vec::new();
+                response
+                    .into_reader()
+                    .read_to_end(&mut body)
+                    .map_err(|err| format!("cannot read response from {}: {}", url, err))?;
+                Ok((content_type, body))
+            }
+            Err(ureq::Error::Status(status, response)) => {
+                let body = response.into_string().unwrap_or_default();
+                Err(status_error(url, status, &body))
+            }
+            Err(ureq::Error::Transport(err)) => Err(format!("cannot reach {}: {}", url, err)),
+        }
+    }
+}
+
+/// Whether a response is an HTML page, e.g. a login page, judged by its content type or its
+/// start. A leading `<` alone is not enough, as day 17 inputs start with one half of the time.
+fn is_web_page(content_type: &str, body: &[u8]) -> bool {
+    let start = body.trim_ascii_start();
+    let start = start[..start.len().min(9)].to_ascii_lowercase();
+    content_type == "text/html" || start.starts_with(b"<!doctype") || start.starts_with(b"<html")
+}
+
+fn status_error(url: &str, status: u16, body: &str) -> String {
+    let reason = match status {
+        400 | 401 | 403 | 500 => "the session is invalid or has expired",
+        404 if body.contains("before it unlocks") => "the puzzle has not been unlocked yet",
+        404 => "not found",
+        429 => "too many requests",
+        _ => "unexpected response",
+    };
+    let detail = body.lines().next().unwrap_or_default().trim();
+    if detail.is_empty() || detail.starts_with('<') {
+        format!("{} returned {}: {}", url, status, reason)
+    } else {
+        format!("{} returned {}: {} ({})", url, status, reason, detail)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread::{self, JoinHandle};
+
+    pub(crate) const SESSION: &str = "53616c7465645f5fabcdef";
+
+    /// Serves one canned `(status, body)` response per connection and returns the base URL and
+    /// a handle yielding the received requests, each as request line followed by the body.
+    pub(crate) fn serve(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+        serve_typed(
+            responses
+                .into_iter()
+                .map(|(status, body)| (status, "text/plain", body))
+                .collect(),
+        )
+    }
+
+    /// Like [`serve`], with the content type of each response.
+    pub(crate) fn serve_typed(
+        responses: Vec<(u16, &'static str, &'static str)>,
+    ) -> (String, JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, content_type, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut request = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line.trim_end().is_empty() {
+                        break;
+                    }
+                    let lowercase = line.to_ascii_lowercase();
+                    if let Some(length) = lowercase.strip_prefix("content-length:") {
+                        content_length = length.trim().parse().unwrap();
+                    }
+                    if lowercase.starts_with("cookie:") {
+                        assert_eq!(line.trim_end(), format!("Cookie: session={}", SESSION));
+                    }
+                    if request.is_empty() {
+                        request = line.trim_end().to_string();
+                    }
+                }
+                let mut content = vec![0; content_length];
+                reader.read_exact(&mut content).unwrap();
+                request.push('\n');
+                request.push_str(&String::from_utf8_lossy(&content));
+                requests.push(request);
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} X\r\nContent-Type: {}\r\nContent-Length: {}\r\n\
+                     Connection: close\r\n\r\n{}",
+                    status,
+                    content_type,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    /// Returns a fresh, empty directory below the system temp directory.
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "aoc-y2022-{}-{}-{}",
+            name,
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::{serve, serve_typed, SESSION};
+    use super::*;
+
+    #[test]
+    fn test_session() {
+        let session = Session::parse(&format!("SESSION={}\n", SESSION)).unwrap();
+        assert_eq!(session, Session::parse(SESSION).unwrap());
+        assert!(Session::parse("\n").is_err());
+        assert!(Session::parse("SESSION=<your cookie>").is_err());
+    }
+
+    #[test]
+    fn test_input() {
+        let (url, server) = serve(vec![
+            (200, "1\n2\n"),
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+            (200, "<!DOCTYPE html>\n<html>"),
+            (200, "<article><h2>--- Day 2: Rock Paper Scissors ---</h2>"),
+        ]);
+        let client = Client::new(&url, Session::parse(SESSION).unwrap());
+
+        assert_eq!(client.input(1), Ok(b"1\n2\n".to_vec()));
+        assert!(client.input(1).unwrap_err().contains("session is invalid"));
+        assert!(client.input(25).unwrap_err().contains("not been unlocked"));
+        assert!(client.input(1).unwrap_err().contains("web page"));
+        assert_eq!(client.title(2).as_deref(), Ok("Rock Paper Scissors"));
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests[0], "GET /2022/day/1/input HTTP/1.1\n");
+        assert_eq!(requests[2], "GET /2022/day/25/input HTTP/1.1\n");
+    }
+
+    #[test]
+    fn test_web_page() {
+        let (url, server) = serve_typed(vec![
+            (200, "text/plain", "<<><>>\n"),
+            (200, "text/html; charset=utf-8", "<<><>>\n"),
+            (200, "text/plain", "\n<HTML><body>"),
+        ]);
+        let client = Client::new(&url, Session::parse(SESSION).unwrap());
+
+        assert_eq!(client.input(17), Ok(b"<<><>>\n".to_vec()));
+        assert!(client.input(17).unwrap_err().contains("web page"));
+        assert!(client.input(17).unwrap_err().contains("web page"));
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,292 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::ops::Sub;
+
+/// Values measured on the states of a cycle, which are extrapolated with checked arithmetic so
+/// that a value too large for the type is noticed rather than wrapped.
+pub trait Value: Copy + Sub<Output = Self> + TryFrom<usize> {
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! impl_value {
+    ($($t:ty),*) => {$(
+        impl Value for $t {
+            #[inline]
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            #[inline]
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+}
+
+impl_value!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+/// A sequence of states that repeats every `period` steps from step `start` on, together with a
+/// value measured on each state, e.g. the height of a tower, that grows by `delta` per period.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cycle<V> {
+    pub start: usize,
+    pub period: usize,
+    pub delta: V,
+    /// The values of the states before the cycle and of one full period.
+    values: Vec<V>,
+}
+
+impl<V> Cycle<V>
+where
+    V: Value,
+{
+    fn new(start: usize, period: usize, mut values: Vec<V>) -> Self {
+        values.truncate(start + period + 1);
+        let delta = values[start + period] - values[start];
+        Cycle {
+            start,
+            period,
+            delta,
+            values,
+        }
+    }
+
+    /// The value after `steps` steps, extrapolated from the recorded ones, or `None` if it does
+    /// not fit into the value type.
+    pub fn value_at(&self, steps: usize) -> Option<V> {
+        if steps < self.values.len() {
+            return Some(self.values[steps]);
+        }
+        let periods = (steps - self.start) / self.period;
+        let offset = (steps - self.start) % self.period;
+        let periods = V::try_from(periods).ok()?;
+        self.delta
+            .checked_mul(periods)?
+            .checked_add(self.values[self.start + offset])
+    }
+}
+
+/// Finds a cycle by remembering the `key` of every state, which only has to contain what
+/// determines the following states. This works for states that are too large or too expensive
+/// to compare, such as a growing tower whose top rows are all that matters.
+///
+/// `step` advances the state by one step. Returns `None` if no key repeats within `limit` steps.
+pub fn find_by_key<S, K, V>(
+    mut state: S,
+    mut step: impl FnMut(&mut S),
+    mut key: impl FnMut(&S) -> K,
+    mut value: impl FnMut(&S) -> V,
+    limit: usize,
+) -> Option<Cycle<V>>
+where
+    K: Eq + Hash,
+    V: Value,
+{
+    let mut seen = HashMap::new();
+    let mut values = Vec::new();
+    for index in 0..=limit {
+        values.push(value(&state));
+        if let Some(start) = seen.insert(key(&state), index) {
+            return Some(Cycle::new(start, index - start, values));
+        }
+        step(&mut state);
+    }
+    None
+}
+
+/// Finds a cycle with Brent's algorithm, which compares whole states but keeps only two of them.
+///
+/// `step` returns the state following its argument. Returns `None` if no state repeats within
+/// `limit` steps.
+pub fn brent<S, V>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    value: impl FnMut(&S) -> V,
+    limit: usize,
+) -> Option<Cycle<V>>
+where
+    S: Clone + Eq,
+    V: Value,
+{
+    // Find the period by moving the hare ahead until it meets the tortoise, which jumps to the
+    // hare at every power of two.
+    let (mut power, mut period) = (1, 1);
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    let mut steps = 1;
+    while tortoise != hare {
+        if steps > limit {
+            return None;
+        }
+        if power == period {
+            tortoise = hare.clone();
+            power *= 2;
+            period = 0;
+        }
+        hare = step(&hare);
+        period += 1;
+        steps += 1;
+    }
+
+    // With the hare one period ahead, both meet at the start of the cycle.
+    let hare = (0..period).fold(initial.clone(), |state, _| step(&state));
+    let start = meet(initial.clone(), hare, &mut step);
+    Some(measure(initial, step, value, start, period))
+}
+
+/// Finds a cycle with Floyd's algorithm, which like [`brent`] keeps only two states but needs
+/// more steps.
+pub fn floyd<S, V>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    value: impl FnMut(&S) -> V,
+    limit: usize,
+) -> Option<Cycle<V>>
+where
+    S: Clone + Eq,
+    V: Value,
+{
+    // The hare moves twice as fast, so they meet after a multiple of the period.
+    let mut tortoise = step(&initial);
+    let mut hare = step(&tortoise);
+    let mut steps = 1;
+    while tortoise != hare {
+        if steps > limit {
+            return None;
+        }
+        tortoise = step(&tortoise);
+        let skipped = step(&hare);
+        hare = step(&skipped);
+        steps += 1;
+    }
+
+    let start = meet(initial.clone(), hare, &mut step);
+
+    let mut period = 1;
+    let first = (0..start).fold(initial.clone(), |state, _| step(&state));
+    let mut state = step(&first);
+    while state != first {
+        state = step(&state);
+        period += 1;
+    }
+
+    Some(measure(initial, step, value, start, period))
+}
+
+/// Moves both states until they are equal and returns the number of steps taken.
+fn meet<S: Eq>(mut tortoise: S, mut hare: S, step: &mut impl FnMut(&S) -> S) -> usize {
+    let mut steps = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        steps += 1;
+    }
+    steps
+}
+
+/// Replays the sequence to record the values up to the end of the first period.
+fn measure<S, V>(
+    initial: S,
+    mut step: impl FnMut(&S) -> S,
+    mut value: impl FnMut(&S) -> V,
+    start: usize,
+    period: usize,
+) -> Cycle<V>
+where
+    V: Value,
+{
+    let mut state = initial;
+    let mut values = vec![value(&state)];
+    for _ in 0..start + period {
+        state = step(&state);
+        values.push(value(&state));
+    }
+    Cycle::new(start, period, values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Enters a cycle of length 6 after 4 steps: 0, 1, 2, 3, then 4..=9 repeating.
+    fn next(state: &u32) -> u32 {
+        if *state < 9 {
+            state + 1
+        } else {
+            4
+        }
+    }
+
+    /// The sum of the first `limit` states, which grows by 39 per period.
+    fn total(limit: usize) -> u64 {
+        let mut state = 0;
+        let mut sum = 0;
+        for _ in 0..limit {
+            sum += state as u64;
+            state = next(&state);
+        }
+        sum
+    }
+
+    #[test]
+    fn test_brent() {
+        let cycle = brent(0, next, |state| *state as u64, 1000).unwrap();
+        assert_eq!((cycle.start, cycle.period), (4, 6));
+        assert_eq!(cycle.delta, 0);
+        assert_eq!(cycle.value_at(3), Some(3));
+        assert_eq!(cycle.value_at(100), Some(4));
+        assert_eq!(brent(0, next, |state| *state as u64, 5), None);
+    }
+
+    #[test]
+    fn test_floyd() {
+        let cycle = floyd(0, next, |state| *state as u64, 1000).unwrap();
+        assert_eq!(cycle, brent(0, next, |state| *state as u64, 1000).unwrap());
+        assert_eq!(floyd(0, next, |state| *state as u64, 3), None);
+    }
+
+    #[test]
+    fn test_find_by_key() {
+        // The running sum never repeats, so only the sequence element is used as the key.
+        let cycle = find_by_key(
+            (0, 0_u64),
+            |(state, sum)| {
+                *sum += *state as u64;
+                *state = next(state);
+            },
+            |(state, _)| *state,
+            |(_, sum)| *sum,
+            1000,
+        )
+        .unwrap();
+        assert_eq!((cycle.start, cycle.period, cycle.delta), (4, 6, 39));
+        for steps in [0, 3, 4, 9, 10, 11, 100, 1001] {
+            assert_eq!(cycle.value_at(steps), Some(total(steps)));
+        }
+        let steps = 1_000_000_000_001;
+        assert_eq!(
+            cycle.value_at(steps),
+            Some((steps - 4) as u64 / 6 * 39 + total(4 + (steps - 4) % 6))
+        );
+        assert_eq!(cycle.value_at(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_no_prefix() {
+        let cycle = brent(0_u8, |state| (state + 1) % 3, |state| *state as i32, 10).unwrap();
+        assert_eq!((cycle.start, cycle.period), (0, 3));
+        let cycle = find_by_key(5, |_| {}, |state| *state, |_| 1_usize, 10).unwrap();
+        assert_eq!((cycle.start, cycle.period, cycle.delta), (0, 1, 0));
+    }
+
+    #[test]
+    fn test_overflow() {
+        // A value growing by 1 per step only fits into a `u8` for 255 steps.
+        let cycle = Cycle::new(0, 1, vec![0_u8, 1]);
+        assert_eq!(cycle.value_at(255), Some(255));
+        assert_eq!(cycle.value_at(256), None);
+        assert_eq!(cycle.value_at(1000), None);
+    }
+}
@@ -1,81 +1,136 @@
-extern crate test;
-
-pub const INPUT: &[u8] = include_bytes!("../inputs/day01.txt");
-
-const POWERS_OF_TEN: [u32; 10] = [
-    1,
-    10,
-    100,
-    1000,
-    10_000,
-    100_000,
-    1_000_000,
-    10_000_000,
-    100_000_000,
-    1_000_000_000,
-];
-
-fn u32_from_bytes(bytes: &[u8]) -> u32 {
-    bytes.iter().rev().enumerate().fold(0, |acc, (ix, x)| {
-        acc + (x - b'0') as u32 * POWERS_OF_TEN[ix]
-    })
+use crate::error::ParseError;
+use crate::parse::integer;
+use crate::solution::{Answer, Solution};
+
+pub fn parse(input: &[u8]) -> Result<Vec<u64>, ParseError> {
+    let mut elves = vec![0_u64];
+
+    for bytes in input.trim_ascii_end().split(|byte| *byte == b'\n') {
+        if bytes.is_empty() {
+            elves.push(0);
+        } else {
+            let calories = integer::<u64>(bytes)
+                .ok_or_else(|| ParseError::at(input, bytes, "a number of calories"))?;
+            let elf = elves.last_mut().unwrap();
+            *elf = elf.checked_add(calories).ok_or_else(|| {
+                ParseError::at(input, bytes, "calories that add up to at most 2^64 - 1")
+            })?;
+        }
+    }
+
+    Ok(elves)
 }
 
-pub(crate) fn part1(input: &[u8]) -> u32 {
-    input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .fold((0, 0), |(max_cals, cur_cals), bytes| {
-            if bytes.is_empty() {
-                (std::cmp::max(max_cals, cur_cals), 0)
-            } else {
-                (max_cals, cur_cals + u32_from_bytes(bytes))
-            }
-        })
-        .0
+pub fn part1(elves: &[u64]) -> u64 {
+    elves.iter().copied().max().unwrap_or(0)
 }
 
-pub(crate) fn part2(input: &[u8]) -> u32 {
-    let mut top_three_plus_one = [0_u32; 4];
-
-    input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .fold(0, |cur_cals, bytes| {
-            if bytes.is_empty() {
-                top_three_plus_one[0] = cur_cals;
-                top_three_plus_one.sort_unstable();
-                0
-            } else {
-                cur_cals + u32_from_bytes(bytes)
-            }
-        });
-
-    top_three_plus_one.iter().skip(1).sum()
+/// The calories of the three elves carrying the most, or `None` if they add up to more than
+/// `u64::MAX`.
+pub fn part2(elves: &[u64]) -> Option<u64> {
+    let mut top_three_plus_one = [0_u64; 4];
+
+    for cals in elves {
+        top_three_plus_one[0] = *cals;
+        top_three_plus_one.sort_unstable();
+    }
+
+    top_three_plus_one
+        .iter()
+        .skip(1)
+        .try_fold(0_u64, |sum, calories| sum.checked_add(*calories))
+}
+
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Parsed = Vec<u64>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(elves: &Self::Parsed, _: &()) -> Answer {
+        part1(elves).into()
+    }
+
+    fn part2(elves: &Self::Parsed, _: &()) -> Answer {
+        part2(elves).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day01.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day01.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 69912)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 69912)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 208180)
+        assert_eq!(part2(&parse(INPUT).unwrap()), Some(208180))
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_example() {
+        let elves = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&elves), 24000);
+        assert_eq!(part2(&elves), Some(45000));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"1000\n\n20x0\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (3, 1, "20x0")
+        );
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Totals beyond `u32::MAX`, with the elves in increasing order.
+        let input = (0..2000_u64)
+            .map(|elf| format!("{}\n", 1_000_000_000 + elf).repeat(50))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let elves = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&elves), 50 * 1_000_001_999);
+        assert_eq!(part2(&elves), Some(50 * (3 * 1_000_001_999 - 3)));
+
+        let error = parse(format!("{}\n1\n", u64::MAX).as_bytes()).unwrap_err();
+        assert_eq!((error.line, error.text.as_str()), (2, "1"));
+
+        // Elves that each carry no more than `u64::MAX`, but more than that together.
+        let elves = parse(format!("{0}\n\n{0}\n\n1\n", u64::MAX / 2 + 1).as_bytes()).unwrap();
+        assert_eq!(part1(&elves), u64::MAX / 2 + 1);
+        assert_eq!(part2(&elves), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day01::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let elves = parse(INPUT).unwrap();
+        bench("day01::part1", || part1(&elves))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let elves = parse(INPUT).unwrap();
+        bench("day01::part2", || part2(&elves))
     }
 }
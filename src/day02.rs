@@ -1,52 +1,116 @@
-pub const INPUT: &[u8] = include_bytes!("../inputs/day02.txt");
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-pub(crate) fn part1(input: &[u8]) -> u32 {
+fn parse_round(input: &[u8], bytes: &[u8]) -> Result<(u8, u8), ParseError> {
+    match bytes {
+        [a @ b'A'..=b'C', b' ', b @ b'X'..=b'Z'] => Ok((a - b'A', b - b'X')),
+        _ => Err(ParseError::at(
+            input,
+            bytes,
+            "`A`, `B` or `C`, a space and `X`, `Y` or `Z`",
+        )),
+    }
+}
+
+pub fn parse(input: &[u8]) -> Result<Vec<(u8, u8)>, ParseError> {
     input
         .trim_ascii_end()
         .split(|byte| *byte == b'\n')
-        .map(|bytes| {
-            let a = bytes[0] - b'A';
-            let b = bytes[2] - b'X';
-            (1 + b + ((4 + b - a) % 3) * 3) as u32 // add 4 instead of 1 because 4 + b - a is always > 0
+        .map(|bytes| parse_round(input, bytes))
+        .collect()
+}
+
+pub fn part1(rounds: &[(u8, u8)]) -> u64 {
+    rounds
+        .iter()
+        .map(|(a, b)| {
+            (1 + b + ((4 + b - a) % 3) * 3) as u64 // add 4 instead of 1 because 4 + b - a is always > 0
         })
         .sum()
 }
 
-pub(crate) fn part2(input: &[u8]) -> u32 {
-    input
-        .split_inclusive(|byte| *byte == b'\n')
-        .map(|bytes| {
-            let a = bytes[0] - b'A';
-            let b = bytes[2] - b'X';
-            (1 + 3 * b + (a + b + 2) % 3) as u32
-        })
+pub fn part2(rounds: &[(u8, u8)]) -> u64 {
+    rounds
+        .iter()
+        .map(|(a, b)| (1 + 3 * b + (a + b + 2) % 3) as u64)
         .sum()
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Parsed = Vec<(u8, u8)>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(rounds: &Self::Parsed, _: &()) -> Answer {
+        part1(rounds).into()
+    }
+
+    fn part2(rounds: &Self::Parsed, _: &()) -> Answer {
+        part2(rounds).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day02.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day02.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 15572)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 15572)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 16098)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 16098)
+    }
+
+    #[test]
+    fn test_example() {
+        let rounds = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&rounds), 15);
+        assert_eq!(part2(&rounds), 12);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"A Y\nB\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 1, "B"));
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_large_input() {
+        // A million times the three rounds of the example, 300 times the rounds of the puzzle.
+        let rounds = parse("A Y\nB X\nC Z\n".repeat(1_000_000).as_bytes()).unwrap();
+        assert_eq!(part1(&rounds), 15 * 1_000_000);
+        assert_eq!(part2(&rounds), 12 * 1_000_000);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day02::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let rounds = parse(INPUT).unwrap();
+        bench("day02::part1", || part1(&rounds))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let rounds = parse(INPUT).unwrap();
+        bench("day02::part2", || part2(&rounds))
     }
 }
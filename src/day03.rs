@@ -1,81 +1,188 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 use itertools::Itertools;
 
-pub const INPUT: &[u8] = include_bytes!("../inputs/day03.txt");
 pub const NEWLINE: u8 = 10;
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    input
+fn parse_rucksack(input: &[u8], bytes: &[u8]) -> Result<Vec<u8>, ParseError> {
+    if !bytes.len().is_multiple_of(2) {
+        return Err(ParseError::at(input, bytes, "an even number of items"));
+    }
+    match bytes.iter().position(|byte| !byte.is_ascii_alphabetic()) {
+        Some(index) => Err(ParseError::at(input, &bytes[index..=index], "a letter")),
+        None => Ok(Vec::from(bytes)),
+    }
+}
+
+/// Parses the rucksacks, each with an item in both compartments and in groups of three that share
+/// an item.
+pub fn parse(input: &[u8]) -> Result<Vec<Vec<u8>>, ParseError> {
+    let lines: Vec<&[u8]> = input
         .trim_ascii_end()
         .split(|byte| *byte == NEWLINE)
-        .map(|bytes: &[u8]| {
-            let num_bytes = bytes.len();
-
-            for item1 in bytes.iter().take(num_bytes / 2) {
-                for item2 in bytes.iter().skip(num_bytes / 2) {
-                    if *item1 == *item2 {
-                        return *item1;
-                    }
-                }
-            }
-            panic!()
-        })
-        .map(|common| match common {
-            0..=96 => (common - 38) as usize,
-            _ => (common - 96) as usize,
+        .collect();
+    let rucksacks: Vec<Vec<u8>> = lines
+        .iter()
+        .map(|bytes| parse_rucksack(input, bytes))
+        .collect::<Result<_, _>>()?;
+
+    for (bytes, rucksack) in lines.iter().zip(&rucksacks) {
+        let (first, second) = rucksack.split_at(rucksack.len() / 2);
+        if items(first) & items(second) == 0 {
+            let expected = "a rucksack with an item in both compartments";
+            return Err(ParseError::at(input, bytes, expected));
+        }
+    }
+    for (group, bytes) in rucksacks.chunks(3).zip(lines.chunks(3)) {
+        let last = bytes[bytes.len() - 1];
+        if group.len() < 3 {
+            return Err(ParseError::end_of(
+                input,
+                last,
+                "a group of three rucksacks",
+            ));
+        }
+        if items(&group[0]) & items(&group[1]) & items(&group[2]) == 0 {
+            let expected = "the last rucksack of a group with an item that all three carry";
+            return Err(ParseError::at(input, last, expected));
+        }
+    }
+    Ok(rucksacks)
+}
+
+fn priority(item: u8) -> usize {
+    match item {
+        0..=96 => (item - 38) as usize,
+        _ => (item - 96) as usize,
+    }
+}
+
+/// The set of items as a bit per priority.
+fn items(bytes: &[u8]) -> u64 {
+    bytes
+        .iter()
+        .fold(0, |items, item| items | 1 << priority(*item))
+}
+
+pub fn part1(rucksacks: &[Vec<u8>]) -> usize {
+    rucksacks
+        .iter()
+        .map(|bytes| {
+            let (first, second) = bytes.split_at(bytes.len() / 2);
+            let second = items(second);
+
+            first
+                .iter()
+                .map(|item| priority(*item))
+                .find(|priority| second & 1 << priority != 0)
+                .expect("the parser rejects rucksacks without an item in both compartments")
         })
         .sum()
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let lines = input.trim_ascii_end().split(|byte| *byte == NEWLINE);
-
-    lines
+pub fn part2(rucksacks: &[Vec<u8>]) -> usize {
+    rucksacks
+        .iter()
         .tuples()
         .map(|(line1, line2, line3)| {
-            for item1 in line1 {
-                for item2 in line2 {
-                    for item3 in line3 {
-                        if *item1 == *item2 && *item2 == *item3 {
-                            return *item1;
-                        }
-                    }
-                }
-            }
-            panic!()
-        })
-        .map(|common| match common {
-            0..=96 => (common - 38) as usize,
-            _ => (common - 96) as usize,
+            let others = items(line2) & items(line3);
+
+            line1
+                .iter()
+                .map(|item| priority(*item))
+                .find(|priority| others & 1 << priority != 0)
+                .expect("the parser rejects groups without an item that all three carry")
         })
         .sum()
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Parsed = Vec<Vec<u8>>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(rucksacks: &Self::Parsed, _: &()) -> Answer {
+        part1(rucksacks).into()
+    }
+
+    fn part2(rucksacks: &Self::Parsed, _: &()) -> Answer {
+        part2(rucksacks).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day03.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day03.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 7691)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 7691)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2508)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 2508)
+    }
+
+    #[test]
+    fn test_example() {
+        let rucksacks = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&rucksacks), 157);
+        assert_eq!(part2(&rucksacks), 70);
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"abAB\nab-d\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "-"));
+        let error = parse(b"abAc\n").err().unwrap();
+        assert_eq!(
+            error.expected,
+            "a rucksack with an item in both compartments"
+        );
+        let error = parse(b"aa\nbb\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 3));
+        let error = parse(b"aa\nbb\ncc\n").err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (3, "cc"));
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Groups of rucksacks with 100 times the items of the puzzle, sharing `b`, `c` and `d`
+        // between their compartments and `Z` as badge, which is at the end of the first ones.
+        let rucksack = |item: &str| format!("{}Z{}\n", item.repeat(2499), item.repeat(2500));
+        let group = format!("{}{}{}", rucksack("b"), rucksack("c"), rucksack("d"));
+        let rucksacks = parse(group.repeat(1000).as_bytes()).unwrap();
+        assert_eq!(part1(&rucksacks), (2 + 3 + 4) * 1000);
+        assert_eq!(part2(&rucksacks), 52 * 1000);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day03::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let rucksacks = parse(INPUT).unwrap();
+        bench("day03::part1", || part1(&rucksacks))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let rucksacks = parse(INPUT).unwrap();
+        bench("day03::part2", || part2(&rucksacks))
     }
 }
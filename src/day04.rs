@@ -1,70 +1,142 @@
-extern crate test;
+use crate::error::ParseError;
+use crate::parse::integer;
+use crate::solution::{Answer, Solution};
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day04.txt");
-
-const POWERS_OF_TEN: [u8; 3] = [1, 10, 100];
-
-fn u8_from_bytes(bytes: &[u8]) -> u8 {
-    bytes
-        .iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (ix, x)| acc + (x - b'0') * POWERS_OF_TEN[ix])
-}
-
-fn numbers_from_line(bytes: &[u8]) -> (u8, u8, u8, u8) {
+fn numbers_from_line(input: &[u8], bytes: &[u8]) -> Result<(u32, u32, u32, u32), ParseError> {
     let mut numbers = bytes.split(|byte| *byte == b',' || *byte == b'-');
+    let mut next = || {
+        let number = numbers
+            .next()
+            .ok_or_else(|| ParseError::end_of(input, bytes, "a section number"))?;
+        integer::<u32>(number).ok_or_else(|| ParseError::at(input, number, "a section number"))
+    };
 
-    (
-        u8_from_bytes(numbers.next().unwrap()),
-        u8_from_bytes(numbers.next().unwrap()),
-        u8_from_bytes(numbers.next().unwrap()),
-        u8_from_bytes(numbers.next().unwrap()),
-    )
+    let pair = (next()?, next()?, next()?, next()?);
+    match numbers.next() {
+        Some(rest) => Err(ParseError::at(input, rest, "end of line")),
+        None => Ok(pair),
+    }
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
+pub fn parse(input: &[u8]) -> Result<Vec<(u32, u32, u32, u32)>, ParseError> {
     input
         .trim_ascii_end()
         .split(|byte| *byte == b'\n')
-        .map(numbers_from_line)
+        .map(|bytes| numbers_from_line(input, bytes))
+        .collect()
+}
+
+pub fn part1(pairs: &[(u32, u32, u32, u32)]) -> usize {
+    pairs
+        .iter()
         .filter(|(a, b, c, d)| a >= c && b <= d || c >= a && d <= b)
         .count()
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .map(numbers_from_line)
+pub fn part2(pairs: &[(u32, u32, u32, u32)]) -> usize {
+    pairs
+        .iter()
         .filter(|(a, b, c, d)| !(a < c && b < c || c < a && d < a))
         .count()
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Parsed = Vec<(u32, u32, u32, u32)>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(pairs: &Self::Parsed, _: &()) -> Answer {
+        part1(pairs).into()
+    }
+
+    fn part2(pairs: &Self::Parsed, _: &()) -> Answer {
+        part2(pairs).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day04.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day04.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 571)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 571)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 917)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 917)
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_example() {
+        let pairs = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&pairs), 2);
+        assert_eq!(part2(&pairs), 4);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"2-4,6-8\n2-3,4\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 6, ""));
+        assert!(parse(b"2-4,6-8000000000").is_err());
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Sections beyond `u16::MAX`, alternating between contained and disjoint ranges.
+        let input: String = (0..10_000_u32)
+            .map(|pair| {
+                let start = 100_000 * pair;
+                match pair % 2 {
+                    0 => format!(
+                        "{}-{},{}-{}\n",
+                        start,
+                        start + 900,
+                        start + 100,
+                        start + 200
+                    ),
+                    _ => format!(
+                        "{}-{},{}-{}\n",
+                        start,
+                        start + 100,
+                        start + 200,
+                        start + 900
+                    ),
+                }
+            })
+            .collect();
+        let pairs = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&pairs), 5000);
+        assert_eq!(part2(&pairs), 5000);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day04::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let pairs = parse(INPUT).unwrap();
+        bench("day04::part1", || part1(&pairs))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let pairs = parse(INPUT).unwrap();
+        bench("day04::part2", || part2(&pairs))
     }
 }
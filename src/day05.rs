@@ -1,72 +1,126 @@
-extern crate test;
-
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day05.txt");
-const POWERS_OF_TEN: [u8; 3] = [1, 10, 100];
-
-fn bytes_to_u8(bytes: &[u8]) -> u8 {
-    bytes
-        .iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (ix, x)| acc + (x - b'0') * POWERS_OF_TEN[ix])
-}
+use crate::error::ParseError;
+use crate::parse::integer;
+use crate::solution::{Answer, Solution};
+
+fn parse_stacks(input: &[u8], drawing: &[u8]) -> Result<Vec<Vec<u8>>, ParseError> {
+    let mut lines: Vec<&[u8]> = drawing.split(|byte| *byte == b'\n').collect();
+    let labels = lines.pop().unwrap_or_default();
+    let num_stacks = labels
+        .split(|byte| *byte == b' ')
+        .filter(|label| !label.is_empty())
+        .count();
+    if num_stacks == 0 {
+        return Err(ParseError::at(input, labels, "a line of stack numbers"));
+    }
 
-fn parse_stacks(input: &[u8]) -> Vec<Vec<u8>> {
-    let mut stacks: Vec<Vec<u8>> = vec![Vec::new(); 9];
-    for bytes in input.trim_ascii_end().split(|byte| *byte == b'\n') {
-        if bytes[1] == b'1' {
-            break;
-        }
+    let mut stacks: Vec<Vec<u8>> = vec![Vec::new(); num_stacks];
+    for bytes in lines {
         for (index, byte) in bytes.trim_ascii_end().iter().skip(1).step_by(4).enumerate() {
-            if *byte != b' ' {
+            if index >= stacks.len() {
+                let expected = format!("at most {} stacks", num_stacks);
+                return Err(ParseError::at(input, &bytes[1 + 4 * index..], expected));
+            }
+            if byte.is_ascii_uppercase() {
                 stacks[index].push(*byte)
+            } else if *byte != b' ' {
+                let crate_ = &bytes[1 + 4 * index..=1 + 4 * index];
+                return Err(ParseError::at(input, crate_, "a crate like `[A]`"));
             }
         }
     }
     for stack in stacks.iter_mut() {
         stack.reverse()
     }
-    stacks
+    Ok(stacks)
+}
+
+#[derive(Clone)]
+pub struct Procedure {
+    stacks: Vec<Vec<u8>>,
+    moves: Vec<(usize, usize, usize)>,
 }
 
-pub(crate) fn part1(input: &[u8]) -> Vec<u8> {
-    let mut stacks: Vec<Vec<u8>> = parse_stacks(input);
+/// Parses a move and applies it to `heights`, the number of crates on each stack, so that no move
+/// takes more crates than its stack holds.
+fn parse_move(
+    input: &[u8],
+    bytes: &[u8],
+    heights: &mut [usize],
+) -> Result<(usize, usize, usize), ParseError> {
+    let num_stacks = heights.len();
+    let expected = "a move like `move 1 from 2 to 3`";
+    let words: Vec<&[u8]> = bytes.split(|byte| *byte == b' ').collect();
+    let [b"move", count, b"from", src, b"to", dst] = words[..] else {
+        return Err(ParseError::at(input, bytes, expected));
+    };
+
+    let count_bytes = count;
+    let count = integer::<usize>(count).ok_or_else(|| ParseError::at(input, count, "a number"))?;
+    let stack = |number: &[u8]| match integer::<usize>(number) {
+        Some(stack) if (1..=num_stacks).contains(&stack) => Ok(stack),
+        _ => {
+            let expected = format!("a stack number from 1 to {}", num_stacks);
+            Err(ParseError::at(input, number, expected))
+        }
+    };
+
+    let (src, dst) = (stack(src)?, stack(dst)?);
+    if count > heights[src - 1] {
+        let expected = format!(
+            "at most {} crates, the height of stack {}",
+            heights[src - 1],
+            src
+        );
+        return Err(ParseError::at(input, count_bytes, expected));
+    }
+    heights[src - 1] -= count;
+    heights[dst - 1] += count;
+    Ok((count, src, dst))
+}
 
-    for bytes in input.trim_ascii_end().split(|byte| *byte == b'\n').skip(10) {
-        let mut nums = bytes
-            .split(|byte| *byte == b' ')
-            .skip(1)
-            .step_by(2)
-            .map(|num| bytes_to_u8(num) as usize);
+pub fn parse(input: &[u8]) -> Result<Procedure, ParseError> {
+    let separator = input.windows(2).position(|window| window == b"\n\n");
+    let Some(separator) = separator else {
+        let end = &input[input.len()..];
+        return Err(ParseError::at(input, end, "an empty line after the stacks"));
+    };
+    let stacks = parse_stacks(input, &input[..separator])?;
+    let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    let moves = input[separator + 2..]
+        .trim_ascii_end()
+        .split(|byte| *byte == b'\n')
+        .map(|bytes| parse_move(input, bytes, &mut heights))
+        .collect::<Result<_, _>>()?;
+
+    Ok(Procedure { stacks, moves })
+}
 
-        let count = nums.next().unwrap();
-        let src = nums.next().unwrap();
-        let dst = nums.next().unwrap();
+pub fn part1(procedure: &Procedure) -> Vec<u8> {
+    let mut stacks: Vec<Vec<u8>> = procedure.stacks.clone();
 
+    for (count, src, dst) in procedure.moves.iter().copied() {
         for _ in 0..count {
             let to_move = stacks[src - 1].pop().unwrap();
             stacks[dst - 1].push(to_move)
         }
     }
 
-    stacks.iter().map(|stack| *stack.last().unwrap()).collect()
+    top_crates(&stacks)
 }
 
-pub(crate) fn part2(input: &[u8]) -> Vec<u8> {
-    let mut stacks: Vec<Vec<u8>> = parse_stacks(input);
-    let mut temp_stack: Vec<u8> = Vec::new();
-
-    for bytes in input.trim_ascii_end().split(|byte| *byte == b'\n').skip(10) {
-        let mut nums = bytes
-            .split(|byte| *byte == b' ')
-            .skip(1)
-            .step_by(2)
-            .map(|num| bytes_to_u8(num) as usize);
+/// The crate on top of each stack, skipping stacks that ended up empty.
+fn top_crates(stacks: &[Vec<u8>]) -> Vec<u8> {
+    stacks
+        .iter()
+        .filter_map(|stack| stack.last().copied())
+        .collect()
+}
 
-        let count = nums.next().unwrap();
-        let src = nums.next().unwrap();
-        let dst = nums.next().unwrap();
+pub fn part2(procedure: &Procedure) -> Vec<u8> {
+    let mut stacks: Vec<Vec<u8>> = procedure.stacks.clone();
+    let mut temp_stack: Vec<u8> = Vec::new();
 
+    for (count, src, dst) in procedure.moves.iter().copied() {
         for _ in 0..count {
             let to_move = stacks[src - 1].pop().unwrap();
             temp_stack.push(to_move);
@@ -77,33 +131,119 @@ pub(crate) fn part2(input: &[u8]) -> Vec<u8> {
         }
     }
 
-    stacks.iter().map(|stack| *stack.last().unwrap()).collect()
+    top_crates(&stacks)
+}
+
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Parsed = Procedure;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(procedure: &Self::Parsed, _: &()) -> Answer {
+        Answer::text(&part1(procedure))
+    }
+
+    fn part2(procedure: &Self::Parsed, _: &()) -> Answer {
+        Answer::text(&part2(procedure))
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day05.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day05.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), b"VCTFTJQCG")
+        assert_eq!(part1(&parse(INPUT).unwrap()), b"VCTFTJQCG")
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), b"GCFGLDNJZ")
+        assert_eq!(part2(&parse(INPUT).unwrap()), b"GCFGLDNJZ")
+    }
+
+    #[test]
+    fn test_example() {
+        let procedure = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&procedure), b"CMZ");
+        assert_eq!(part2(&procedure), b"MCD");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let mut input = INPUT.to_vec();
+        let line_start = input.len() - "move 1 from 1 to 2\n".len();
+        input[line_start + 12] = b'0';
+        let error = parse(&input).err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (13, "0"));
+
+        // The example with the third move taking a crate more than stack 2 holds by then.
+        let input = String::from_utf8(EXAMPLE.to_vec()).unwrap();
+        let error = parse(
+            input
+                .replace("move 2 from 2 to 1", "move 3 from 2 to 1")
+                .as_bytes(),
+        )
+        .err()
+        .unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (8, 6, "3"));
+        assert_eq!(error.expected, "at most 2 crates, the height of stack 2");
+    }
+
+    #[test]
+    fn test_empty_stack() {
+        let procedure = parse(b"    [B]\n[A] [C]\n 1   2 \n\nmove 1 from 1 to 2\n").unwrap();
+        assert_eq!(part1(&procedure), b"A");
+        assert_eq!(part2(&procedure), b"A");
+    }
+
+    #[test]
+    fn test_large_input() {
+        // 12 stacks and a single move of more than 255 crates. Stack 1 holds `A` and `E` at the
+        // bottom, then `B`s and `C` on top; all others hold a `D`.
+        let mut input = String::from("[C]\n");
+        input.push_str(&"[B]\n".repeat(297));
+        input.push_str("[E]\n");
+        input.push_str(&format!("[A] {}\n", ["[D]"; 11].join(" ")));
+        input.push_str(
+            &(1..=12)
+                .map(|stack| format!("{:^3}", stack))
+                .collect::<Vec<_>>()
+                .join(" "),
+        );
+        input.push_str("\n\nmove 299 from 1 to 12\n");
+
+        let procedure = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&procedure), b"ADDDDDDDDDDE");
+        assert_eq!(part2(&procedure), b"ADDDDDDDDDDC");
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day05::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let procedure = parse(INPUT).unwrap();
+        bench("day05::part1", || part1(&procedure))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let procedure = parse(INPUT).unwrap();
+        bench("day05::part2", || part2(&procedure))
     }
 }
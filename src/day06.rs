@@ -1,10 +1,24 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day06.txt");
+pub fn parse(input: &[u8]) -> Result<Vec<u8>, ParseError> {
+    let signal = input.trim_ascii_end();
+    if let Some(index) = signal.iter().position(|byte| !byte.is_ascii_lowercase()) {
+        return Err(ParseError::at(
+            input,
+            &signal[index..],
+            "a lowercase letter",
+        ));
+    }
+    if signal.len() < 14 {
+        return Err(ParseError::end_of(input, signal, "at least 14 characters"));
+    }
+    Ok(Vec::from(signal))
+}
 
-pub(crate) fn part1(input: &[u8]) -> usize {
+/// The number of characters up to the first four different ones, or `None` without them.
+pub fn part1(input: &[u8]) -> Option<usize> {
     input
         .windows(4)
         .position(|w| {
@@ -15,11 +29,11 @@ pub(crate) fn part1(input: &[u8]) -> usize {
                 && w[1] != w[3]
                 && w[2] != w[3]
         })
-        .unwrap()
-        + 4
+        .map(|start| start + 4)
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
+/// The number of characters up to the first fourteen different ones, or `None` without them.
+pub fn part2(input: &[u8]) -> Option<usize> {
     let mut counts: HashMap<u8, u8> = HashMap::new();
 
     for item in &input[0..14] {
@@ -41,7 +55,10 @@ pub(crate) fn part2(input: &[u8]) -> usize {
             break;
         }
         match counts.get_mut(&window[0]) {
-            None => panic!(),
+            None => unreachable!(
+                "`{}` left the window without being counted",
+                window[0] as char
+            ),
             Some(c) => {
                 if *c > 1 {
                     *c -= 1;
@@ -63,33 +80,93 @@ pub(crate) fn part2(input: &[u8]) -> usize {
         result += 1
     }
 
-    result
+    (diff_count == 14).then_some(result)
+}
+
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Parsed = Vec<u8>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(signal: &Self::Parsed, _: &()) -> Answer {
+        part1(signal).into()
+    }
+
+    fn part2(signal: &Self::Parsed, _: &()) -> Answer {
+        part2(signal).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day06.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day06.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 1953)
+        assert_eq!(part1(&parse(INPUT).unwrap()), Some(1953))
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2301)
+        assert_eq!(part2(&parse(INPUT).unwrap()), Some(2301))
+    }
+
+    #[test]
+    fn test_example() {
+        let signal = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&signal), Some(7));
+        assert_eq!(part2(&signal), Some(19));
+    }
+
+    #[test]
+    fn test_no_marker() {
+        let signal = parse(b"abcabcabcabcabcabc\n").unwrap();
+        assert_eq!((part1(&signal), part2(&signal)), (None, None));
+        let signal = parse(b"abcdabcdabcdabcd\n").unwrap();
+        assert_eq!((part1(&signal), part2(&signal)), (Some(4), None));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse(b"abc\ndef").err().unwrap().column, 4);
+        assert_eq!(parse(b"abcdef\n").err().unwrap().column, 7);
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_large_input() {
+        // A signal 100 times as long as the puzzle's, with both markers at its end.
+        let signal = format!("{}abcdefghijklmn\n", "ab".repeat(200_000));
+        let signal = parse(signal.as_bytes()).unwrap();
+        assert_eq!(part1(&signal), Some(400_000 + 4));
+        assert_eq!(part2(&signal), Some(400_000 + 14));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day06::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let signal = parse(INPUT).unwrap();
+        bench("day06::part1", || part1(&signal))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let signal = parse(INPUT).unwrap();
+        bench("day06::part2", || part2(&signal))
     }
 }
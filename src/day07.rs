@@ -1,8 +1,18 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::solution::{params, Answer, Solution};
 use std::collections::HashMap;
 
-pub(crate) const INPUT: &str = include_str!("../inputs/day07.txt");
+params! {
+    pub struct Params {
+        /// Largest size of the directories summed up in part 1.
+        pub max_size: usize = 100_000,
+        /// Total size of the disk in part 2.
+        pub disk_size: usize = 70_000_000,
+        /// Free space needed for the update.
+        pub required_space: usize = 30_000_000,
+    }
+}
 
 fn total_size(
     dir_name: &str,
@@ -14,7 +24,9 @@ fn total_size(
     if let Some(subdirs) = dir_map.get(dir_name) {
         for subdir in subdirs {
             let subsize = total_size(subdir, size_map, dir_map);
-            result += subsize;
+            result = result
+                .checked_add(subsize)
+                .expect("the files of a directory add up to more than 2^64 - 1");
         }
     }
 
@@ -22,123 +34,228 @@ fn total_size(
 }
 
 fn dir_name(vec: &[String]) -> String {
-    let mut result = String::new();
-    for str in vec.iter().intersperse(&String::from("/")) {
-        result.push_str(str);
-    }
-    result
+    vec.join("/")
 }
 
-struct DirectoryTree {
+pub struct DirectoryTree {
     all_dirs: Vec<String>,
     dir_map: HashMap<String, Vec<String>>,
     size_map: HashMap<String, usize>,
 }
 
 impl DirectoryTree {
-    fn parse(input: &str) -> Self {
-        let mut size_map: HashMap<String, usize> = HashMap::new();
-        let mut dir_map: HashMap<String, Vec<String>> = HashMap::new();
-        let mut cur_dir: Vec<String> = Vec::new();
-        let mut all_dirs: Vec<String> = Vec::new();
-
-        for line in input.lines() {
-            if line == "$ cd .." {
-                cur_dir.pop();
-            } else if let Some(stripped) = line.strip_prefix("$ cd ") {
-                cur_dir.push(String::from(stripped));
-                let cur_dir_name = dir_name(&cur_dir);
-                all_dirs.push(cur_dir_name)
-            } else if let Some(stripped) = line.strip_prefix("dir ") {
-                let cur_dir_name = dir_name(&cur_dir);
-                cur_dir.push(String::from(stripped));
-                let new_dir_name = dir_name(&cur_dir);
-                cur_dir.pop();
-                if let Some(list) = dir_map.get_mut(&cur_dir_name) {
-                    list.push(new_dir_name);
-                } else {
-                    dir_map.insert(cur_dir_name, vec![new_dir_name]);
-                }
-            } else if line.as_bytes()[0].is_ascii_digit() {
-                let space_pos = line.find(' ').unwrap();
-                let cur_dir_name = dir_name(&cur_dir);
-                let size = &line[0..space_pos].parse::<usize>().unwrap();
-                if let Some(total_size) = size_map.get_mut(&cur_dir_name) {
-                    *total_size += *size;
-                } else {
-                    size_map.insert(cur_dir_name, *size);
-                }
-            }
-        }
+    /// The paths of all directories: their names from the root joined with `/`, so that the
+    /// subdirectory `a` of the root `/` is `//a`.
+    pub fn directories(&self) -> impl Iterator<Item = &str> {
+        self.all_dirs.iter().map(String::as_str)
+    }
 
-        Self {
-            all_dirs,
-            dir_map,
-            size_map,
-        }
+    /// The size of all files in `dir` and its subdirectories.
+    pub fn total_size(&self, dir: &str) -> usize {
+        total_size(dir, &self.size_map, &self.dir_map)
     }
 }
 
-pub(crate) fn part1(input: &str) -> usize {
-    let directory_tree = DirectoryTree::parse(input);
+pub fn parse(input: &str) -> Result<DirectoryTree, ParseError> {
+    let mut size_map: HashMap<String, usize> = HashMap::new();
+    let mut dir_map: HashMap<String, Vec<String>> = HashMap::new();
+    let mut cur_dir: Vec<String> = Vec::new();
+    let mut all_dirs: Vec<String> = Vec::new();
 
-    let mut result = 0;
-
-    for dir in directory_tree.all_dirs {
-        let size = total_size(&dir, &directory_tree.size_map, &directory_tree.dir_map);
-        if size <= 100000 {
-            result += size;
+    for line in input.lines() {
+        if line == "$ cd .." {
+            cur_dir.pop();
+        } else if let Some(stripped) = line.strip_prefix("$ cd ") {
+            cur_dir.push(String::from(stripped));
+            let cur_dir_name = dir_name(&cur_dir);
+            all_dirs.push(cur_dir_name)
+        } else if let Some(stripped) = line.strip_prefix("dir ") {
+            let cur_dir_name = dir_name(&cur_dir);
+            cur_dir.push(String::from(stripped));
+            let new_dir_name = dir_name(&cur_dir);
+            cur_dir.pop();
+            if let Some(list) = dir_map.get_mut(&cur_dir_name) {
+                list.push(new_dir_name);
+            } else {
+                dir_map.insert(cur_dir_name, vec![new_dir_name]);
+            }
+        } else if line.starts_with(|c: char| c.is_ascii_digit()) {
+            let Some((size, _)) = line.split_once(' ') else {
+                return Err(ParseError::end_of(
+                    input.as_bytes(),
+                    line.as_bytes(),
+                    "a file name",
+                ));
+            };
+            let cur_dir_name = dir_name(&cur_dir);
+            let size: usize = integer_at(input.as_bytes(), size.as_bytes())?;
+            if let Some(total_size) = size_map.get_mut(&cur_dir_name) {
+                *total_size = total_size.checked_add(size).ok_or_else(|| {
+                    ParseError::at(
+                        input.as_bytes(),
+                        line.as_bytes(),
+                        "files that add up to at most 2^64 - 1",
+                    )
+                })?;
+            } else {
+                size_map.insert(cur_dir_name, size);
+            }
+        } else if line != "$ ls" {
+            return Err(ParseError::at(
+                input.as_bytes(),
+                line.as_bytes(),
+                "`$ cd`, `$ ls`, `dir` or a file size",
+            ));
         }
     }
 
-    result
+    Ok(DirectoryTree {
+        all_dirs,
+        dir_map,
+        size_map,
+    })
 }
 
-pub(crate) fn part2(input: &str) -> usize {
-    let directory_tree = DirectoryTree::parse(input);
+pub fn part1(directory_tree: &DirectoryTree, max_size: usize) -> usize {
+    directory_tree
+        .directories()
+        .map(|dir| directory_tree.total_size(dir))
+        .filter(|size| *size <= max_size)
+        .try_fold(0_usize, |sum, size| sum.checked_add(size))
+        .expect("the directories of part 1 add up to more than 2^64 - 1")
+}
 
-    let free_space =
-        70000000 - total_size("/", &directory_tree.size_map, &directory_tree.dir_map) as isize;
-    let to_free = 30000000 - free_space;
+/// The size of the smallest directory to delete to make room for the update, or `None` if
+/// deleting any one directory is not enough.
+pub fn part2(
+    directory_tree: &DirectoryTree,
+    disk_size: usize,
+    required_space: usize,
+) -> Option<usize> {
+    let used_space = directory_tree.total_size("/");
+    let to_free = match disk_size.checked_sub(required_space) {
+        Some(available) => used_space.saturating_sub(available),
+        None => (required_space - disk_size).checked_add(used_space)?,
+    };
 
     directory_tree
-        .all_dirs
-        .iter()
-        .map(|dir| {
-            let dir_size =
-                total_size(dir, &directory_tree.size_map, &directory_tree.dir_map) as isize;
-            (dir_size - to_free, dir_size)
-        })
-        .filter(|(diff, _)| *diff >= 0)
-        .min_by(|(diff1, _), (diff2, _)| diff1.cmp(diff2))
-        .unwrap()
-        .1 as usize
+        .directories()
+        .map(|dir| directory_tree.total_size(dir))
+        .filter(|size| *size >= to_free)
+        .min()
+}
+
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Parsed = DirectoryTree;
+    type Params = Params;
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(&String::from_utf8_lossy(input))
+    }
+
+    fn part1(directory_tree: &Self::Parsed, params: &Params) -> Answer {
+        part1(directory_tree, params.max_size).into()
+    }
+
+    fn part2(directory_tree: &Self::Parsed, params: &Params) -> Answer {
+        part2(directory_tree, params.disk_size, params.required_space).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &str = include_str!("../inputs/day07.txt");
+    const EXAMPLE: &str = include_str!("../examples/day07.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 919137)
+        assert_eq!(part1(&parse(INPUT).unwrap(), 100000), 919137)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2877389)
+        assert_eq!(
+            part2(&parse(INPUT).unwrap(), 70000000, 30000000),
+            Some(2877389)
+        )
+    }
+
+    #[test]
+    fn test_example() {
+        let directory_tree = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&directory_tree, 100000), 95437);
+        assert_eq!(part2(&directory_tree, 70000000, 30000000), Some(24933642));
+        assert_eq!(part2(&directory_tree, 1, 30000000), None);
+        assert_eq!(part2(&directory_tree, 70000000, usize::MAX), None);
+        assert_eq!(
+            part2(&directory_tree, usize::MAX, usize::MAX),
+            Some(48381165)
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("$ cd /\n$ ls\n12a b.txt\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (3, 1, "12a")
+        );
+        let error = parse("$ cd /\n$ mv a b\n").err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (2, "$ mv a b"));
+        let input = format!("$ cd /\n$ ls\n{0} a\n{0} b\n", u64::MAX / 2 + 1);
+        assert_eq!(parse(&input).err().unwrap().line, 4);
+    }
+
+    #[test]
+    fn test_large_input() {
+        // 10,000 directories, 100 times as many as in the puzzle, with files beyond `u32::MAX`.
+        let size = |dir: usize| 1_000_000_000_000 + dir;
+        let mut input = String::from("$ cd /\n$ ls\n");
+        for dir in 0..10_000 {
+            input.push_str(&format!("dir d{}\n", dir));
+        }
+        for dir in 0..10_000 {
+            input.push_str(&format!("$ cd d{}\n$ ls\n{} f\n$ cd ..\n", dir, size(dir)));
+        }
+        let directory_tree = parse(&input).unwrap();
+        let total: usize = (0..10_000).map(size).sum();
+        assert_eq!(directory_tree.total_size("/"), total);
+
+        assert_eq!(
+            part1(&directory_tree, size(4999)),
+            (0..5000).map(size).sum()
+        );
+        let disk_size = 2 * total;
+        assert_eq!(
+            part2(&directory_tree, disk_size, disk_size - total + size(7000)),
+            Some(size(7000))
+        );
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day07::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let directory_tree = parse(INPUT).unwrap();
+        bench("day07::part1", || part1(&directory_tree, 100000))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let directory_tree = parse(INPUT).unwrap();
+        bench("day07::part2", || {
+            part2(&directory_tree, 70000000, 30000000)
+        })
     }
 }
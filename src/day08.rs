@@ -1,156 +1,153 @@
-extern crate test;
-
-use std::cmp::max;
-use std::collections::HashSet;
-
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day08.txt");
-
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let grid: Vec<&[u8]> = input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .collect();
-
-    let mut visible: HashSet<(usize, usize)> = HashSet::new();
-
-    for i in 1..grid[0].len() - 1 {
-        let mut cur_height = grid[0][i];
+use crate::error::ParseError;
+use crate::grid::{Grid2D, NEIGHBOURS_4};
+use crate::solution::{Answer, Solution};
+
+pub fn parse(input: &[u8]) -> Result<Grid2D<u8>, ParseError> {
+    Grid2D::parse(input, "trees", "a tree height from 0 to 9", |byte| {
+        byte.is_ascii_digit().then_some(byte)
+    })
+}
 
-        for (j, _) in grid.iter().enumerate().skip(1) {
-            let new_height = grid[j][i];
-            if new_height > cur_height {
-                cur_height = max(cur_height, new_height);
-                visible.insert((j, i));
-            }
+/// Marks the trees along `line` that are taller than all trees before them.
+fn mark_visible(
+    grid: &Grid2D<u8>,
+    visible: &mut Grid2D<bool>,
+    line: impl Iterator<Item = (usize, usize)>,
+) {
+    let mut tallest = None;
+    for position in line {
+        if tallest < Some(grid[position]) {
+            tallest = Some(grid[position]);
+            visible[position] = true;
         }
+    }
+}
 
-        cur_height = grid[grid.len() - 1][i];
+pub fn part1(grid: &Grid2D<u8>) -> usize {
+    let mut visible = grid.map(|_| false);
+    let (width, height) = (grid.width(), grid.height());
 
-        for (j, _) in grid.iter().enumerate().rev().skip(1) {
-            let new_height = grid[j][i];
-            if new_height > cur_height {
-                cur_height = max(cur_height, new_height);
-                visible.insert((j, i));
-            }
-        }
+    for y in 0..height {
+        mark_visible(grid, &mut visible, (0..width).map(|x| (x, y)));
+        mark_visible(grid, &mut visible, (0..width).rev().map(|x| (x, y)));
     }
-
-    for i in 0..grid.len() {
-        visible.insert((i, 0));
-        visible.insert((i, grid.len() - 1));
-        visible.insert((0, i));
-        visible.insert((grid.len() - 1, i));
+    for x in 0..width {
+        mark_visible(grid, &mut visible, (0..height).map(|y| (x, y)));
+        mark_visible(grid, &mut visible, (0..height).rev().map(|y| (x, y)));
     }
 
-    for i in 1..grid.len() - 1 {
-        let mut cur_height = grid[i][0];
-
-        for (j, _) in grid[i].iter().enumerate().skip(1) {
-            let new_height = grid[i][j];
-            if new_height > cur_height {
-                cur_height = max(cur_height, new_height);
-                visible.insert((i, j));
-            }
-        }
-
-        cur_height = grid[i][grid.len() - 1];
+    visible.iter().filter(|visible| **visible).count()
+}
 
-        for (j, _) in grid[i].iter().enumerate().rev().skip(1) {
-            let new_height = grid[i][j];
-            if new_height > cur_height {
-                cur_height = max(cur_height, new_height);
-                visible.insert((i, j));
-            }
+/// The number of `trees` seen from a tree of `height`, up to the first one that is as tall.
+fn viewing_distance<'a>(height: u8, trees: impl Iterator<Item = &'a u8>) -> usize {
+    let mut distance = 0;
+    for tree in trees {
+        distance += 1;
+        if *tree >= height {
+            break;
         }
     }
+    distance
+}
 
-    visible.len()
+pub fn part2(grid: &Grid2D<u8>) -> usize {
+    grid.positions()
+        .map(|position| {
+            let height = grid[position];
+            NEIGHBOURS_4
+                .into_iter()
+                .map(|direction| viewing_distance(height, grid.ray_cells(position, direction)))
+                .try_fold(1_usize, |score, distance| score.checked_mul(distance))
+                .expect("a scenic score beyond 2^64 - 1")
+        })
+        .max()
+        .unwrap_or(0)
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let grid: Vec<&[u8]> = input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .collect();
-
-    let mut result = 0;
-
-    for i in 1..grid.len() - 1 {
-        for j in 1..grid[i].len() - 1 {
-            let mut score = 1;
-            let height = grid[i][j];
-
-            for k in 1..grid[i].len() {
-                if j < k {
-                    score *= k - 1;
-                    break;
-                } else if grid[i][j - k] >= height {
-                    score *= k;
-                    break;
-                }
-            }
-
-            for k in 1..grid[i].len() {
-                if j + k > grid[i].len() - 1 {
-                    score *= k - 1;
-                    break;
-                } else if grid[i][j + k] >= height {
-                    score *= k;
-                    break;
-                }
-            }
-
-            for k in 1..grid.len() {
-                if i < k {
-                    score *= k - 1;
-                    break;
-                } else if grid[i - k][j] >= height {
-                    score *= k;
-                    break;
-                }
-            }
-
-            for k in 1..grid.len() {
-                if i + k > grid.len() - 1 {
-                    score *= k - 1;
-                    break;
-                } else if grid[i + k][j] >= height {
-                    score *= k;
-                    break;
-                }
-            }
-
-            result = max(result, score)
-        }
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Parsed = Grid2D<u8>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
     }
 
-    result
+    fn part1(grid: &Self::Parsed, _: &()) -> Answer {
+        part1(grid).into()
+    }
+
+    fn part2(grid: &Self::Parsed, _: &()) -> Answer {
+        part2(grid).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day08.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day08.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 1763)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 1763)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 671160)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 671160)
+    }
+
+    #[test]
+    fn test_example() {
+        let grid = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&grid), 21);
+        assert_eq!(part2(&grid), 8);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"123\n45\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "a row of 3 trees")
+        );
+        let error = parse(b"123\n4.6\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 2));
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_large_input() {
+        // A forest of 1000 x 1000 trees, 100 times as many as in the puzzle, of height 0 with a
+        // single tree of height 9 that sees all the way to the edges.
+        let mut input = format!("{}\n", "0".repeat(1000)).repeat(1000).into_bytes();
+        input[500 * 1001 + 500] = b'9';
+        let grid = parse(&input).unwrap();
+        assert_eq!(part1(&grid), 4 * 999 + 1);
+        assert_eq!(part2(&grid), 500 * 499 * 500 * 499);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day08::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let grid = parse(INPUT).unwrap();
+        bench("day08::part1", || part1(&grid))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let grid = parse(INPUT).unwrap();
+        bench("day08::part2", || part2(&grid))
     }
 }
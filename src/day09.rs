@@ -1,109 +1,148 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer;
+use crate::point::{Direction, Point2};
+use crate::solution::{Answer, Solution};
 use std::collections::HashSet;
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day09.txt");
-
-const POWERS_OF_TEN: [u8; 3] = [1, 10, 100];
+fn parse_motion(input: &[u8], bytes: &[u8]) -> Result<(Direction, u32), ParseError> {
+    match bytes {
+        [direction @ (b'U' | b'D' | b'L' | b'R'), b' ', steps @ ..] => {
+            let direction = match direction {
+                b'U' => Direction::Up,
+                b'D' => Direction::Down,
+                b'L' => Direction::Left,
+                _ => Direction::Right,
+            };
+            let steps = integer::<u32>(steps)
+                .ok_or_else(|| ParseError::at(input, steps, "a number of steps"))?;
+            Ok((direction, steps))
+        }
+        _ => Err(ParseError::at(
+            input,
+            bytes,
+            "`U`, `D`, `L` or `R` followed by a number of steps",
+        )),
+    }
+}
 
-fn u8_from_bytes(bytes: &[u8]) -> u8 {
-    bytes
-        .iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (ix, x)| acc + (x - b'0') * POWERS_OF_TEN[ix])
+pub fn parse(input: &[u8]) -> Result<Vec<(Direction, u32)>, ParseError> {
+    input
+        .trim_ascii_end()
+        .split(|byte| *byte == b'\n')
+        .map(|bytes| parse_motion(input, bytes))
+        .collect()
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let mut head: (i16, i16) = (0, 0);
-    let mut tail: (i16, i16) = (0, 0);
-    let mut visited: HashSet<(i16, i16)> = HashSet::new();
-    visited.insert(tail);
-
-    for bytes in input.trim_ascii_end().split(|byte| *byte == b'\n') {
-        let (dx, dy): (i16, i16) = match bytes[0] {
-            b'U' => (-1, 0),
-            b'D' => (1, 0),
-            b'L' => (0, -1),
-            b'R' => (0, 1),
-            _ => panic!(),
-        };
-        let steps = u8_from_bytes(&bytes[2..]);
+/// Moves the head of a rope of `N` knots and returns the number of positions the tail visits.
+fn simulate<const N: usize>(motions: &[(Direction, u32)]) -> usize {
+    let mut rope = [Point2::<i64>::ORIGIN; N];
+    let mut visited = HashSet::from([rope[N - 1]]);
 
+    for (direction, steps) in motions.iter().copied() {
         for _ in 0..steps {
-            head.0 += dx;
-            head.1 += dy;
-            let (diff_x, diff_y) = (head.0 - tail.0, head.1 - tail.1);
-            if diff_x.abs() > 1 || diff_y.abs() > 1 {
-                tail.0 += diff_x.signum();
-                tail.1 += diff_y.signum();
-                visited.insert(tail);
+            rope[0] += direction.offset();
+            for knot in 1..N {
+                if rope[knot].chebyshev(rope[knot - 1]) > 1 {
+                    let step = (rope[knot - 1] - rope[knot]).signum();
+                    rope[knot] += step;
+                }
             }
+            visited.insert(rope[N - 1]);
         }
     }
 
     visited.len()
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let mut rope: [i32; 20] = [0; 20];
-    let mut visited: HashSet<u64> = HashSet::new();
-    visited.insert((((rope[18] as u32) as u64) << 32) | ((rope[19] as u32) as u64));
-
-    for bytes in input.trim_ascii_end().split(|byte| *byte == b'\n') {
-        let (dx, dy): (i32, i32) = match bytes[0] {
-            b'U' => (-1, 0),
-            b'D' => (1, 0),
-            b'L' => (0, -1),
-            b'R' => (0, 1),
-            _ => panic!(),
-        };
-        let steps = u8_from_bytes(&bytes[2..]);
+pub fn part1(motions: &[(Direction, u32)]) -> usize {
+    simulate::<2>(motions)
+}
 
-        for _ in 0..steps {
-            rope[0] += dx;
-            rope[1] += dy;
-            for segment in 0..9 {
-                let (diff_x, diff_y): (i32, i32) = (
-                    rope[2 * segment] - rope[2 * segment + 2],
-                    rope[2 * segment + 1] - rope[2 * segment + 3],
-                );
-                if diff_x.abs() > 1 || diff_y.abs() > 1 {
-                    rope[2 * segment + 2] += diff_x.signum();
-                    rope[2 * segment + 3] += diff_y.signum();
-                }
-            }
-            visited.insert((((rope[18] as u32) as u64) << 32) | ((rope[19] as u32) as u64));
-        }
+pub fn part2(motions: &[(Direction, u32)]) -> usize {
+    simulate::<10>(motions)
+}
+
+pub struct Day09;
+
+impl Solution for Day09 {
+    type Parsed = Vec<(Direction, u32)>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
     }
 
-    visited.len()
+    fn part1(motions: &Self::Parsed, _: &()) -> Answer {
+        part1(motions).into()
+    }
+
+    fn part2(motions: &Self::Parsed, _: &()) -> Answer {
+        part2(motions).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day09.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day09.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 6311)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 6311)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2482)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 2482)
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_example() {
+        let motions = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&motions), 13);
+        assert_eq!(part2(&motions), 1);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"R 4\nX 2\n").err().unwrap();
+        assert_eq!((error.line, error.text.as_str()), (2, "X 2"));
+        let error = parse(b"R 4\nU 4294967296\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 3, "4294967296")
+        );
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Motions of more than 255 steps, moving the head far beyond `i16::MAX`.
+        let input = "R 300\n".repeat(500);
+        let motions = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&motions), 150_000);
+        assert_eq!(part2(&motions), 149_992);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day09::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let motions = parse(INPUT).unwrap();
+        bench("day09::part1", || part1(&motions))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let motions = parse(INPUT).unwrap();
+        bench("day09::part2", || part2(&motions))
     }
 }
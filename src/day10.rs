@@ -1,79 +1,94 @@
-extern crate test;
+use crate::error::ParseError;
+use crate::parse::integer;
+use crate::solution::{Answer, Solution};
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day10.txt");
-
-const POWERS_OF_TEN: [i8; 3] = [1, 10, 100];
+#[derive(Copy, Clone)]
+pub enum Instruction {
+    Noop,
+    Addx(i32),
+}
 
-fn i8_from_bytes(bytes: &[u8]) -> i8 {
-    if bytes[0] == b'-' {
-        bytes
-            .iter()
-            .skip(1)
-            .rev()
-            .enumerate()
-            .fold(0, |acc, (ix, x)| acc + (48 - *x as i8) * POWERS_OF_TEN[ix])
-    } else {
-        bytes
-            .iter()
-            .rev()
-            .enumerate()
-            .fold(0, |acc, (ix, x)| acc + (*x as i8 - 48) * POWERS_OF_TEN[ix])
+fn parse_instruction(input: &[u8], bytes: &[u8]) -> Result<Instruction, ParseError> {
+    match bytes {
+        b"noop" => Ok(Instruction::Noop),
+        [b'a', b'd', b'd', b'x', b' ', value @ ..] => integer::<i32>(value)
+            .map(Instruction::Addx)
+            .ok_or_else(|| ParseError::at(input, value, "a 32-bit number")),
+        _ => Err(ParseError::at(input, bytes, "`noop` or `addx <value>`")),
     }
 }
 
-pub(crate) fn part1(input: &[u8]) -> isize {
+pub fn parse(input: &[u8]) -> Result<Vec<Instruction>, ParseError> {
     input
         .trim_ascii_end()
         .split(|byte| *byte == b'\n')
+        .map(|bytes| parse_instruction(input, bytes))
+        .collect()
+}
+
+/// Adds the signal strength during `cycle` to `signal` if it is one of the cycles to check.
+fn add_signal(signal: isize, cycle: isize, register: isize) -> isize {
+    if (cycle + 20) % 40 == 0 {
+        cycle
+            .checked_mul(register)
+            .and_then(|strength| signal.checked_add(strength))
+            .expect("the signal strength overflows")
+    } else {
+        signal
+    }
+}
+
+pub fn part1(instructions: &[Instruction]) -> isize {
+    instructions
+        .iter()
         .fold(
             (1_isize, 1_isize, 0_isize),
             |(cycle, register, signal), instruction| {
-                let (mut new_cycle, mut new_signal) = (cycle, signal);
-                if (new_cycle + 20) % 40 == 0 {
-                    new_signal += new_cycle * register;
-                }
-                new_cycle += 1;
-                if instruction[0] == b'n' {
-                    (new_cycle, register, new_signal)
-                } else {
-                    if (new_cycle + 20) % 40 == 0 {
-                        new_signal += new_cycle * register;
+                let signal = add_signal(signal, cycle, register);
+                match instruction {
+                    Instruction::Noop => (cycle + 1, register, signal),
+                    Instruction::Addx(value) => {
+                        let signal = add_signal(signal, cycle + 1, register);
+                        let register = register
+                            .checked_add(*value as isize)
+                            .expect("the register overflows");
+                        (cycle + 2, register, signal)
                     }
-                    (
-                        new_cycle + 1,
-                        register + i8_from_bytes(&instruction[5..]) as isize,
-                        new_signal,
-                    )
                 }
             },
         )
         .2
 }
 
-pub(crate) fn part2(input: &[u8]) -> String {
+pub fn part2(instructions: &[Instruction]) -> String {
     let mut register: isize = 1;
     let mut cycle: usize = 0;
-    let mut crt_row: usize;
-    let mut crt_col: usize;
     let mut result = vec![vec![false; 40]; 6];
 
-    for instruction in input.trim_ascii_end().split(|byte| *byte == b'\n') {
-        if instruction[0] == b'n' {
-            cycle = (cycle + 1) % 240;
-            crt_row = cycle / 40;
-            crt_col = cycle - crt_row * 40;
-            result[crt_row][crt_col] = ((crt_col as isize) - register).abs() < 2;
-        } else {
-            cycle = (cycle + 1) % 240;
-            crt_row = cycle / 40;
-            crt_col = cycle - crt_row * 40;
-            result[crt_row][crt_col] = ((crt_col as isize) - register).abs() < 2;
-            cycle = (cycle + 1) % 240;
-            crt_row = cycle / 40;
-            crt_col = cycle - crt_row * 40;
-            register += i8_from_bytes(&instruction[5..]) as isize;
+    let mut draw = |cycle: usize, register: isize| {
+        if cycle < 240 {
+            let (crt_row, crt_col) = (cycle / 40, cycle % 40);
             result[crt_row][crt_col] = ((crt_col as isize) - register).abs() < 2;
         }
+    };
+
+    // Only the first 240 cycles are drawn, so the register can't overflow.
+    for instruction in instructions {
+        if cycle >= 240 {
+            break;
+        }
+        match instruction {
+            Instruction::Noop => {
+                draw(cycle, register);
+                cycle += 1;
+            }
+            Instruction::Addx(value) => {
+                draw(cycle, register);
+                draw(cycle + 1, register);
+                cycle += 2;
+                register += *value as isize;
+            }
+        }
     }
 
     let mut out = String::new();
@@ -86,24 +101,44 @@ pub(crate) fn part2(input: &[u8]) -> String {
     out
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Parsed = Vec<Instruction>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(instructions: &Self::Parsed, _: &()) -> Answer {
+        part1(instructions).into()
+    }
+
+    fn part2(instructions: &Self::Parsed, _: &()) -> Answer {
+        Answer::Art(part2(instructions))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day10.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day10.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 13220)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 13220)
     }
 
     #[test]
     fn test_part2() {
         assert_eq!(
-            part2(INPUT),
+            part2(&parse(INPUT).unwrap()),
             r"
-.##..#..#..##..#..#.#..#.###..####.#..#.
+###..#..#..##..#..#.#..#.###..####.#..#.
 #..#.#..#.#..#.#.#..#..#.#..#.#....#.#..
 #..#.#..#.#..#.##...####.###..###..##...
 ###..#..#.####.#.#..#..#.#..#.#....#.#..
@@ -114,13 +149,70 @@ mod tests {
         )
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_example() {
+        let instructions = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&instructions), 13140);
+        assert_eq!(
+            part2(&instructions),
+            "\
+##..##..##..##..##..##..##..##..##..##..
+###...###...###...###...###...###...###.
+####....####....####....####....####....
+#####.....#####.....#####.....#####.....
+######......######......######......####
+#######.......#######.......#######.....
+"
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(parse(b"noop\naddx -128\n").unwrap().len(), 2);
+        let error = parse(b"noop\naddx 1x\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 6, "1x")
+        );
+        let error = parse(b"noop\nmulx 2\n").err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Operands beyond `i8::MAX` and signal strengths beyond `i32::MAX`. The `k`th `addx`
+        // takes cycles `2k + 1` and `2k + 2`, during which the register is `1 + 100000 k`.
+        let instructions = parse("addx 100000\n".repeat(10_000).as_bytes()).unwrap();
+        let expected = (20..=20_000)
+            .step_by(40)
+            .map(|cycle| cycle * (1 + 100_000 * ((cycle - 1) / 2)))
+            .sum::<isize>();
+        assert_eq!(part1(&instructions), expected);
+        // Only the first two pixels are drawn before the sprite moves off the screen.
+        let dark_row = format!("{}\n", ".".repeat(40));
+        assert_eq!(
+            part2(&instructions),
+            format!("##{}", &dark_row[2..]) + &dark_row.repeat(5)
+        );
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day10::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let instructions = parse(INPUT).unwrap();
+        bench("day10::part1", || part1(&instructions))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let instructions = parse(INPUT).unwrap();
+        bench("day10::part2", || part2(&instructions))
     }
 }
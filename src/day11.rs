@@ -1,101 +1,272 @@
-extern crate test;
+use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::solution::{Answer, Solution};
 
-const MAX_ITEMS_PER_MONKEY: usize = 32;
-const NUM_MONKEYS: usize = 8;
-const MODULUS: usize = 2 * 3 * 5 * 7 * 11 * 13 * 17 * 19;
+#[derive(Copy, Clone)]
+enum Operation {
+    Add(usize),
+    Multiply(usize),
+    Square,
+}
+
+#[derive(Clone)]
+pub struct Monkey {
+    items: Vec<usize>,
+    operation: Operation,
+    divisor: usize,
+    next_true: usize,
+    next_false: usize,
+}
+
+fn parse_monkey(input: &str, block: &str, num_monkeys: usize) -> Result<Monkey, ParseError> {
+    let error =
+        |token: &str, expected: &str| ParseError::at(input.as_bytes(), token.as_bytes(), expected);
+    let number = |token: &str| integer_at::<usize>(input.as_bytes(), token.as_bytes());
+
+    let mut lines = block.lines().map(str::trim);
+    let mut line = |prefix: &str| {
+        let line = lines.next().unwrap_or(&block[block.len()..]);
+        line.strip_prefix(prefix)
+            .ok_or_else(|| error(line, &format!("a line starting with `{}`", prefix)))
+    };
+    let monkey = |token: &str| match number(token) {
+        Ok(monkey) if monkey < num_monkeys => Ok(monkey),
+        _ => Err(error(token, "the number of another monkey")),
+    };
+
+    line("Monkey ")?;
+
+    let items = line("Starting items:")?
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(number)
+        .collect::<Result<_, _>>()?;
+
+    let operation = line("Operation: new = old ")?;
+    let operation = match operation.split_once(' ') {
+        Some(("*", "old")) => Operation::Square,
+        Some(("*", factor)) => Operation::Multiply(number(factor)?),
+        Some(("+", summand)) => Operation::Add(number(summand)?),
+        _ => return Err(error(operation, "`* <number>`, `* old` or `+ <number>`")),
+    };
+
+    let divisor = line("Test: divisible by ")?;
+    let divisor = match number(divisor) {
+        Ok(0) | Err(_) => return Err(error(divisor, "a positive number")),
+        Ok(divisor) => divisor,
+    };
+
+    Ok(Monkey {
+        items,
+        operation,
+        divisor,
+        next_true: monkey(line("If true: throw to monkey ")?)?,
+        next_false: monkey(line("If false: throw to monkey ")?)?,
+    })
+}
+
+pub fn parse(input: &[u8]) -> Result<Vec<Monkey>, ParseError> {
+    let input = String::from_utf8_lossy(input);
+    let blocks: Vec<&str> = input.trim_end().split("\n\n").collect();
+
+    blocks
+        .iter()
+        .map(|block| parse_monkey(&input, block, blocks.len()))
+        .collect()
+}
 
-const fn item_index(monkey: usize, item: usize) -> usize {
-    monkey * MAX_ITEMS_PER_MONKEY + item
+const fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+impl Operation {
+    /// The new worry level, or `None` if it does not fit into 64 bits.
+    #[inline]
+    fn apply(self, worry_level: usize) -> Option<usize> {
+        match self {
+            Operation::Add(summand) => worry_level.checked_add(summand),
+            Operation::Multiply(factor) => worry_level.checked_mul(factor),
+            Operation::Square => worry_level.checked_mul(worry_level),
+        }
+    }
+
+    /// The new worry level in 128 bits, where it can't overflow.
+    #[cold]
+    fn apply_wide(self, worry_level: usize) -> u128 {
+        let worry_level = worry_level as u128;
+        match self {
+            Operation::Add(summand) => worry_level + summand as u128,
+            Operation::Multiply(factor) => worry_level * factor as u128,
+            Operation::Square => worry_level * worry_level,
+        }
+    }
+}
+
+const fn item_index(monkey: usize, item: usize, max_items_per_monkey: usize) -> usize {
+    monkey * max_items_per_monkey + item
 }
 
-fn solution(rounds: usize, calm_down_factor: usize) -> usize {
-    let mut nums: [usize; 8] = [6, 7, 4, 5, 3, 1, 8, 2];
-    let factors: [usize; 8] = [3, 0, 13, 0, 0, 0, 0, 0];
-    let summands: [usize; 8] = [0, 1, 0, 0, 7, 8, 4, 5];
-    let divisors: [usize; 8] = [13, 3, 7, 2, 19, 5, 11, 17];
-    let next_true: [usize; 8] = [6, 7, 1, 6, 5, 3, 1, 3];
-    let next_false: [usize; 8] = [2, 4, 4, 0, 7, 0, 2, 5];
-    let mut num_inspections: [usize; 8] = [0; 8];
-
-    let mut items: [usize; NUM_MONKEYS * MAX_ITEMS_PER_MONKEY] = [
-        89, 73, 66, 57, 64, 80, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 83, 78, 81, 55, 81, 59, 69, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 76, 91, 58, 85, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 71, 72, 74, 76, 68, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 98, 85, 84, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 78, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 86, 70, 60, 88, 88, 78, 74, 83, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 81, 58, 0, 0, 0, 0, 0,
-        0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
-    ];
+fn solution(monkeys: &[Monkey], rounds: usize, calm_down_factor: usize) -> usize {
+    let num_monkeys = monkeys.len();
+    let max_items_per_monkey: usize = monkeys.iter().map(|monkey| monkey.items.len()).sum();
+    // Keeping the worry levels below a common multiple of all divisors keeps the tests intact.
+    let modulus: usize = monkeys
+        .iter()
+        .try_fold(1_usize, |modulus, monkey| {
+            (modulus / gcd(modulus, monkey.divisor)).checked_mul(monkey.divisor)
+        })
+        .expect("the divisors have no common multiple within 64 bits");
+
+    let mut nums: Vec<usize> = monkeys.iter().map(|monkey| monkey.items.len()).collect();
+    let mut num_inspections: Vec<usize> = vec![0; num_monkeys];
+    let mut items: Vec<usize> = vec![0; num_monkeys * max_items_per_monkey];
+
+    for (monkey, Monkey { items: initial, .. }) in monkeys.iter().enumerate() {
+        for (item, worry_level) in initial.iter().enumerate() {
+            items[item_index(monkey, item, max_items_per_monkey)] = *worry_level;
+        }
+    }
 
     for _ in 0..rounds {
-        for monkey in 0..NUM_MONKEYS {
+        for (monkey, params) in monkeys.iter().enumerate() {
             for item in 0..nums[monkey] {
                 num_inspections[monkey] += 1;
-                let mut worry_level = items[item_index(monkey, item)];
-                let factor = factors[monkey];
-                let summand = summands[monkey];
-                let divisor = divisors[monkey];
-                if summand != 0 {
-                    worry_level += summand;
-                } else if factor != 0 {
-                    worry_level *= factor;
+                let old = items[item_index(monkey, item, max_items_per_monkey)];
+                let worry_level = if calm_down_factor == 1 {
+                    match params.operation.apply(old) {
+                        Some(worry_level) => worry_level % modulus,
+                        None => (params.operation.apply_wide(old) % modulus as u128) as usize,
+                    }
                 } else {
-                    worry_level = worry_level * worry_level;
-                }
-                worry_level %= MODULUS;
-                worry_level /= calm_down_factor;
-                let next_monkey = if worry_level % divisor == 0 {
-                    next_true[monkey]
+                    let worry_level = params.operation.apply_wide(old) / calm_down_factor as u128;
+                    usize::try_from(worry_level).expect("the worry level overflows")
+                };
+                let next_monkey = if worry_level.is_multiple_of(params.divisor) {
+                    params.next_true
                 } else {
-                    next_false[monkey]
+                    params.next_false
                 };
                 let next_monkey_num_items = nums[next_monkey];
-                items[item_index(next_monkey, next_monkey_num_items)] = worry_level;
+                items[item_index(next_monkey, next_monkey_num_items, max_items_per_monkey)] =
+                    worry_level;
                 nums[next_monkey] += 1;
             }
             nums[monkey] = 0;
         }
     }
 
-    num_inspections.sort();
+    num_inspections.sort_unstable();
+
+    num_inspections.iter().rev().take(2).product()
+}
 
-    num_inspections[6] * num_inspections[7]
+pub fn part1(monkeys: &[Monkey]) -> usize {
+    solution(monkeys, 20, 3)
 }
 
-pub(crate) fn part1() -> usize {
-    solution(20, 3)
+pub fn part2(monkeys: &[Monkey]) -> usize {
+    solution(monkeys, 10000, 1)
 }
 
-pub(crate) fn part2() -> usize {
-    solution(10000, 1)
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Parsed = Vec<Monkey>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(monkeys: &Self::Parsed, _: &()) -> Answer {
+        part1(monkeys).into()
+    }
+
+    fn part2(monkeys: &Self::Parsed, _: &()) -> Answer {
+        part2(monkeys).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day11.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day11.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(), 119715)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 119715)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(), 18085004878)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 18085004878)
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1())
+    #[test]
+    fn test_example() {
+        let monkeys = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&monkeys), 10605);
+        assert_eq!(part2(&monkeys), 2713310158);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2())
+    #[test]
+    fn test_parse_error() {
+        let input = String::from_utf8_lossy(INPUT).replacen("divisible by", "divisible through", 1);
+        let error = parse(input.as_bytes()).err().unwrap();
+        assert_eq!((error.line, error.column), (4, 3));
+        let input = String::from_utf8_lossy(INPUT).replacen("monkey 2", "monkey 12", 1);
+        let error = parse(input.as_bytes()).err().unwrap();
+        assert_eq!(error.text, "12");
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Worry levels beyond `u32::MAX` and a common multiple of the divisors beyond `u32::MAX`,
+        // so that squares overflow 64 bits. The monkeys throw every item to each other.
+        let input = "\
+Monkey 0:
+  Starting items: 4000000000
+  Operation: new = old * old
+  Test: divisible by 1000003
+    If true: throw to monkey 1
+    If false: throw to monkey 1
+
+Monkey 1:
+  Starting items: 5000000000
+  Operation: new = old * 4000000000
+  Test: divisible by 1000033
+    If true: throw to monkey 0
+    If false: throw to monkey 0
+";
+        let monkeys = parse(input.as_bytes()).unwrap();
+        assert_eq!(part2(&monkeys), (1 + 2 * 9999) * 2 * 10000);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day11::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let monkeys = parse(INPUT).unwrap();
+        bench("day11::part1", || part1(&monkeys))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let monkeys = parse(INPUT).unwrap();
+        bench("day11::part2", || part2(&monkeys))
     }
 }
@@ -1,8 +1,7 @@
-extern crate test;
-
-use std::collections::VecDeque;
-
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day12.txt");
+use crate::error::ParseError;
+use crate::grid::Grid2D;
+use crate::search::Search;
+use crate::solution::{Answer, Solution};
 
 #[derive(Debug, Eq, PartialEq, Copy, Clone)]
 enum Direction {
@@ -10,126 +9,161 @@ enum Direction {
     Down,
 }
 
-#[derive(Debug, Copy, Clone)]
-struct State {
-    cost: usize,
-    position: (usize, usize),
-}
-
-fn solve(input: &[u8], start: u8, end: u8, direction: Direction) -> usize {
-    let grid: Vec<&[u8]> = input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .collect();
-
-    let (x_size, y_size) = (grid[0].len(), grid.len());
-    let (mut x0, mut y0) = (0, 0);
-
-    'outer: for (y, row) in grid.iter().enumerate() {
-        for (x, cell) in row.iter().enumerate() {
-            if *cell == start {
-                (x0, y0) = (x, y);
-                break 'outer;
-            }
-        }
-    }
-
-    let mut visited: Vec<bool> = vec![false; x_size * y_size];
-    let mut queue: VecDeque<State> = VecDeque::new();
-    visited[y0 * x_size + x0] = true;
-    queue.push_back(State {
-        cost: 0,
-        position: (x0, y0),
-    });
-
-    while let Some(State { cost, position }) = queue.pop_front() {
-        if grid[position.1][position.0] == end {
-            return cost;
-        }
-        for (next_x, next_y) in next_cells(position.0, position.1, x_size, y_size, direction, &grid)
-        {
-            if visited[next_y * x_size + next_x] {
-                continue;
-            }
-            let next = State {
-                cost: cost + 1,
-                position: (next_x, next_y),
-            };
-            queue.push_back(next);
-            visited[next_y * x_size + next_x] = true;
+pub fn parse(input: &[u8]) -> Result<Grid2D<u8>, ParseError> {
+    let grid = Grid2D::parse(
+        input,
+        "squares",
+        "an elevation from `a` to `z`, `S` or `E`",
+        |byte| matches!(byte, b'a'..=b'z' | b'S' | b'E').then_some(byte),
+    )?;
+    for marker in [b'S', b'E'] {
+        if grid.position(|square| *square == marker).is_none() {
+            let last = input.trim_ascii_end().rsplit(|byte| *byte == b'\n').next();
+            let expected = format!("a square marked `{}`", marker as char);
+            return Err(ParseError::end_of(input, last.unwrap_or(input), expected));
         }
     }
 
-    panic!()
+    Ok(grid)
 }
 
-fn next_cells(
-    x: usize,
-    y: usize,
-    x_size: usize,
-    y_size: usize,
-    direction: Direction,
-    grid: &[&[u8]],
-) -> Vec<(usize, usize)> {
-    let (x_, y_, x_size_, y_size_) = (x as isize, y as isize, x_size as isize, y_size as isize);
-
-    let current_elevation = match grid[y][x] {
+fn elevation(square: u8) -> u8 {
+    match square {
         b'S' => b'a',
         b'E' => b'z',
         h => h,
+    }
+}
+
+/// The fewest steps from `start` to a square marked `end`, or `None` if none can be reached.
+fn solve(grid: &Grid2D<u8>, start: u8, end: u8, direction: Direction) -> Option<usize> {
+    let start = grid.position(|square| *square == start).unwrap();
+    let reachable = |position: &(usize, usize)| {
+        let current = elevation(grid[*position]);
+        grid.neighbours_4(*position)
+            .filter(move |next| match direction {
+                Direction::Up => current + 1 >= elevation(grid[*next]),
+                Direction::Down => elevation(grid[*next]) + 1 >= current,
+            })
     };
 
-    [(-1, 0), (0, -1), (1, 0), (0, 1)]
-        .iter()
-        .map(|(dx, dy)| (x_ + dx, y_ + dy))
-        .filter(|(x_new, y_new)| *x_new >= 0 && *y_new >= 0 && *x_new < x_size_ && *y_new < y_size_)
-        .map(|(x_new, y_new)| (x_new as usize, y_new as usize))
-        .filter(|(x_new, y_new)| {
-            let new_elevation = match grid[*y_new][*x_new] {
-                b'S' => b'a',
-                b'E' => b'z',
-                h => h,
-            };
-            match direction {
-                Direction::Up => current_elevation + 1 >= new_elevation,
-                Direction::Down => new_elevation + 1 >= current_elevation,
-            }
-        })
-        .collect::<Vec<(usize, usize)>>()
+    Search::with_visited(grid.map(|_| false))
+        .bfs([start], reachable, |position, _| grid[*position] == end)
+        .map(|(_, steps)| steps)
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    solve(input, b'S', b'E', Direction::Up)
+pub fn part1(grid: &Grid2D<u8>) -> Option<usize> {
+    solve(grid, b'S', b'E', Direction::Up)
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    solve(input, b'E', b'a', Direction::Down)
+pub fn part2(grid: &Grid2D<u8>) -> Option<usize> {
+    solve(grid, b'E', b'a', Direction::Down)
+}
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Parsed = Grid2D<u8>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(grid: &Self::Parsed, _: &()) -> Answer {
+        part1(grid).into()
+    }
+
+    fn part2(grid: &Self::Parsed, _: &()) -> Answer {
+        part2(grid).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day12.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day12.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 394)
+        assert_eq!(part1(&parse(INPUT).unwrap()), Some(394))
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 388)
+        assert_eq!(part2(&parse(INPUT).unwrap()), Some(388))
+    }
+
+    #[test]
+    fn test_example() {
+        let grid = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&grid), Some(31));
+        assert_eq!(part2(&grid), Some(29));
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_no_path() {
+        let grid = parse(b"SzE\n").unwrap();
+        assert_eq!((part1(&grid), part2(&grid)), (None, None));
+        assert_eq!(Day12::part1(&grid, &()), Answer::None);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"Sab\nc1E\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "1"));
+        let error = parse(b"Sab\ncde\n").err().unwrap();
+        assert_eq!(error.expected, "a square marked `E`");
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    fn test_large_input() {
+        // 100 times the squares of the puzzle: rows of `a` between walls of `z` with a gap at
+        // alternating ends, and a slope up to `E` at the end of the last row.
+        let (width, rows) = (1000, 201);
+        let mut input = String::new();
+        for row in 0..rows {
+            if row > 0 {
+                let wall = "z".repeat(width - 1);
+                match row % 2 {
+                    1 => input.push_str(&format!("{}a\n", wall)),
+                    _ => input.push_str(&format!("a{}\n", wall)),
+                }
+            }
+            match row {
+                0 => input.push_str(&format!("S{}\n", "a".repeat(width - 1))),
+                _ if row == rows - 1 => input.push_str(&format!(
+                    "{}bcdefghijklmnopqrstuvwxyE\n",
+                    "a".repeat(width - 25)
+                )),
+                _ => input.push_str(&format!("{}\n", "a".repeat(width))),
+            }
+        }
+        let grid = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&grid), Some(rows * (width - 1) + (rows - 1) * 2));
+        assert_eq!(part2(&grid), Some(25));
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day12::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let grid = parse(INPUT).unwrap();
+        bench("day12::part1", || part1(&grid))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let grid = parse(INPUT).unwrap();
+        bench("day12::part2", || part2(&grid))
     }
 }
@@ -1,41 +1,33 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::solution::{params, Answer, Solution};
 use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day13.txt");
+params! {
+    pub struct Params {
+        /// Divider packets added to the packets in part 2.
+        pub first_divider: Value = divider(2),
+        pub second_divider: Value = divider(6),
+    }
+}
 
 #[derive(Eq, PartialEq, Clone, Debug)]
-enum Value {
-    Integer(u8),
+pub enum Value {
+    Integer(u32),
     List(Vec<Value>),
 }
 
 impl Ord for Value {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self {
-            Value::Integer(left) => match other {
-                Value::Integer(right) => left.cmp(right),
-                Value::List(_) => Value::List(vec![self.clone()]).cmp(other),
-            },
-            Value::List(left) => match other {
-                Value::Integer(_) => self.cmp(&Value::List(vec![other.clone()])),
-                Value::List(right) => {
-                    if left.is_empty() && right.is_empty() {
-                        Ordering::Equal
-                    } else if left.is_empty() {
-                        Ordering::Less
-                    } else if right.is_empty() {
-                        Ordering::Greater
-                    } else {
-                        match left[0].cmp(&right[0]) {
-                            Ordering::Less => Ordering::Less,
-                            Ordering::Greater => Ordering::Greater,
-                            Ordering::Equal => Value::List(Vec::from(&left[1..]))
-                                .cmp(&Value::List(Vec::from(&right[1..]))),
-                        }
-                    }
-                }
-            },
+        match (self, other) {
+            (Value::Integer(left), Value::Integer(right)) => left.cmp(right),
+            (Value::Integer(_), Value::List(right)) => std::slice::from_ref(self).cmp(right),
+            (Value::List(left), Value::Integer(_)) => {
+                left.as_slice().cmp(std::slice::from_ref(other))
+            }
+            (Value::List(left), Value::List(right)) => left.cmp(right),
         }
     }
 }
@@ -46,68 +38,103 @@ impl PartialOrd for Value {
     }
 }
 
-fn find_closing(bytes: &[u8]) -> usize {
-    let mut bracket_count = 0;
-    let mut skipped = 0;
-
-    for byte in bytes {
-        skipped += 1;
-        match byte {
-            b'[' => {
-                bracket_count += 1;
-            }
-            b']' => {
-                bracket_count -= 1;
-                if bracket_count == 0 {
-                    return skipped;
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Value::Integer(value) => write!(f, "{}", value),
+            Value::List(values) => {
+                write!(f, "[")?;
+                for (i, value) in values.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
                 }
+                write!(f, "]")
             }
-            _ => {}
         }
     }
-    panic!();
 }
 
-fn parse_values(bytes: &[u8]) -> Vec<Value> {
-    let mut values = Vec::new();
-    let mut next_pos = 1;
+impl FromStr for Value {
+    type Err = ParseError;
 
-    while next_pos < bytes.len() {
-        match bytes[next_pos] {
-            b']' => {
-                break;
-            }
-            b'[' => {
-                let to_skip = find_closing(&bytes[next_pos..]);
-                let inner_values = parse_values(&bytes[next_pos..next_pos + to_skip]);
-                values.push(Value::List(inner_values));
-                next_pos += to_skip;
-            }
-            b',' => {
-                next_pos += 1;
+    fn from_str(packet: &str) -> Result<Self, Self::Err> {
+        parse_packet(packet.as_bytes(), packet.as_bytes())
+    }
+}
+
+fn unexpected(input: &[u8], line: &[u8], pos: usize, expected: &str) -> ParseError {
+    ParseError::at(input, &line[pos..(pos + 1).min(line.len())], expected)
+}
+
+fn parse_value(input: &[u8], line: &[u8], pos: &mut usize) -> Result<Value, ParseError> {
+    match line.get(*pos) {
+        Some(b'[') => parse_list(input, line, pos).map(Value::List),
+        Some(b'0'..=b'9') => {
+            let start = *pos;
+            while line.get(*pos).is_some_and(u8::is_ascii_digit) {
+                *pos += 1;
             }
-            c => {
-                values.push(Value::Integer(c));
-                next_pos += 1;
+            integer_at(input, &line[start..*pos]).map(Value::Integer)
+        }
+        // The stored puzzle input writes 10 as `a`, so that every integer is a single byte.
+        Some(b'a') => {
+            *pos += 1;
+            Ok(Value::Integer(10))
+        }
+        _ => Err(unexpected(input, line, *pos, "a number, `a` or `[`")),
+    }
+}
+
+fn parse_list(input: &[u8], line: &[u8], pos: &mut usize) -> Result<Vec<Value>, ParseError> {
+    let mut values = Vec::new();
+
+    *pos += 1;
+    if line.get(*pos) == Some(&b']') {
+        *pos += 1;
+        return Ok(values);
+    }
+
+    loop {
+        values.push(parse_value(input, line, pos)?);
+        match line.get(*pos) {
+            Some(b',') => *pos += 1,
+            Some(b']') => {
+                *pos += 1;
+                return Ok(values);
             }
+            _ => return Err(unexpected(input, line, *pos, "`,` or `]`")),
         }
     }
+}
 
-    values
+fn parse_packet(input: &[u8], line: &[u8]) -> Result<Value, ParseError> {
+    let mut pos = 0;
+    if line.first() != Some(&b'[') {
+        return Err(unexpected(input, line, pos, "a packet starting with `[`"));
+    }
+    let values = parse_list(input, line, &mut pos)?;
+    if pos < line.len() {
+        return Err(ParseError::at(input, &line[pos..], "end of line"));
+    }
+    Ok(Value::List(values))
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let values: Vec<Vec<Value>> = input
+pub fn parse(input: &[u8]) -> Result<Vec<Value>, ParseError> {
+    input
         .trim_ascii_end()
         .split(|byte| *byte == b'\n')
         .filter(|line| !line.is_empty())
-        .map(parse_values)
-        .collect();
+        .map(|line| parse_packet(input, line))
+        .collect()
+}
 
+pub fn part1(packets: &[Value]) -> usize {
     let mut result = 0;
 
-    for i in 0..values.len() / 2 {
-        if values[2 * i] < values[2 * i + 1] {
+    for i in 0..packets.len() / 2 {
+        if packets[2 * i] < packets[2 * i + 1] {
             result += i + 1
         }
     }
@@ -115,50 +142,131 @@ pub(crate) fn part1(input: &[u8]) -> usize {
     result
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let mut values: Vec<Value> = input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .filter(|line| !line.is_empty())
-        .map(|line| Value::List(parse_values(line)))
-        .collect();
+/// The packet `[[value]]`.
+fn divider(value: u32) -> Value {
+    Value::List(vec![Value::List(vec![Value::Integer(value)])])
+}
 
-    let divider_1 = Value::List(parse_values(b"[[2]]"));
-    let divider_2 = Value::List(parse_values(b"[[6]]"));
+pub fn part2(packets: &[Value], divider_1: &Value, divider_2: &Value) -> usize {
+    let mut values: Vec<Value> = packets.to_vec();
 
     values.push(divider_1.clone());
     values.push(divider_2.clone());
 
     values.sort_unstable();
 
-    (values.iter().position(|value| *value == divider_1).unwrap() + 1)
-        * (values.iter().position(|value| *value == divider_2).unwrap() + 1)
+    (values.iter().position(|value| value == divider_1).unwrap() + 1)
+        * (values.iter().position(|value| value == divider_2).unwrap() + 1)
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Parsed = Vec<Value>;
+    type Params = Params;
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(packets: &Self::Parsed, _: &Params) -> Answer {
+        part1(packets).into()
+    }
+
+    fn part2(packets: &Self::Parsed, params: &Params) -> Answer {
+        part2(packets, &params.first_divider, &params.second_divider).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day13.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day13.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 5330)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 5330)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 27648)
+        assert_eq!(
+            part2(&parse(INPUT).unwrap(), &divider(2), &divider(6)),
+            27648
+        )
+    }
+
+    #[test]
+    fn test_example() {
+        let packets = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&packets), 13);
+        assert_eq!(part2(&packets, &divider(2), &divider(6)), 140);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            parse(b"[10,[]]\n").unwrap(),
+            vec![Value::List(vec![Value::Integer(10), Value::List(vec![])])]
+        );
+        assert_eq!(parse(b"[a,[]]\n").unwrap(), parse(b"[10,[]]\n").unwrap());
+        assert!(parse(b"[ab]\n").is_err());
+        let error = parse(b"[1,[2]\n").err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (7, ""));
+        let error = parse(b"[1,2]\n\n[1,,2]\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (3, 4, ","));
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Integers beyond `u8::MAX` and long lists. The first integers only differ within the odd
+        // pairs, where the left packet is smaller, and only the first pair sorts before `[[2]]`.
+        let list = (256..556)
+            .map(|value: u32| value.to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let input: String = (0..1000_u32)
+            .map(|pair| {
+                let (left, right) = (1_000_000 * pair, 1_000_000 * pair + pair % 2);
+                format!("[{},[{}]]\n[{},[{}]]\n\n", left, list, right, list)
+            })
+            .collect();
+        let packets = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&packets), (2..=1000).step_by(2).sum());
+        assert_eq!(part2(&packets, &divider(2), &divider(6)), 3 * 4);
+    }
+
+    #[test]
+    fn test_params() {
+        assert_eq!("[[2]]".parse::<Value>().unwrap(), divider(2));
+        assert_eq!(divider(6).to_string(), "[[6]]");
+        assert!("".parse::<Value>().is_err());
+
+        let mut params = Params::default();
+        crate::solution::Params::set(&mut params, "second_divider", "[[10],[]]").unwrap();
+        assert_eq!(params.second_divider.to_string(), "[[10],[]]");
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day13::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let packets = parse(INPUT).unwrap();
+        bench("day13::part1", || part1(&packets))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let packets = parse(INPUT).unwrap();
+        bench("day13::part2", || part2(&packets, &divider(2), &divider(6)))
     }
 }
@@ -1,162 +1,200 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::grid::Grid2D;
+use crate::parse::integer_at;
+use crate::solution::{Answer, Solution};
 use std::cmp::max;
 use std::collections::{HashSet, VecDeque};
 
-pub(crate) const INPUT: &str = include_str!("../inputs/day14.txt");
-
 #[derive(Copy, Clone, Eq, PartialEq)]
-enum Cell {
+pub enum Cell {
     Air,
     Rock,
     Sand,
 }
 
-struct Grid {
-    x_size: usize,
-    y_size: usize,
-    cells: Vec<Cell>,
+pub type Grid = Grid2D<Cell>;
+
+/// Where the sand comes from.
+const SOURCE: (i32, i32) = (500, 0);
+
+/// The largest coordinate of a rock, which keeps the grid of the scan below 100 MB.
+const MAX_COORDINATE: i32 = 10_000;
+
+/// The scanned rocks. Sand can come to rest left of the leftmost rock, so the grid starts one
+/// column before it, at column `offset` of the scan.
+#[derive(Clone)]
+pub struct Cave {
+    grid: Grid,
+    offset: isize,
 }
 
-impl Grid {
-    fn get(&self, x: usize, y: usize) -> Cell {
-        if x >= self.x_size || y >= self.y_size {
-            Cell::Air
-        } else {
-            self.cells[y * self.x_size + x]
-        }
+impl Cave {
+    /// The cell at `(x, y)` of the scan, with air all around the scanned area.
+    fn cell(&self, x: isize, y: isize) -> Cell {
+        // Negative coordinates wrap around to indices beyond the grid.
+        self.grid
+            .get((x - self.offset) as usize, y as usize)
+            .copied()
+            .unwrap_or(Cell::Air)
     }
 
-    fn set(&mut self, x: usize, y: usize, cell: Cell) {
-        self.cells[y * self.x_size + x] = cell
+    fn height(&self) -> isize {
+        self.grid.height() as isize
     }
+}
 
-    fn from_lines(lines: &Vec<Line>) -> Self {
-        let (x_max, y_max) = lines.iter().fold((0, 0), |(x_max, y_max), line| {
+fn cave_from_lines(lines: &[Line]) -> Cave {
+    let (x_min, x_max, y_max) = lines.iter().fold(
+        (SOURCE.0, SOURCE.0, SOURCE.1),
+        |(x_min, x_max, y_max), line| {
             (
-                max(max(x_max, line.start.0), line.end.0),
-                max(max(y_max, line.start.1), line.end.1),
+                x_min.min(line.start.0).min(line.end.0),
+                x_max.max(line.start.0).max(line.end.0),
+                y_max.max(line.start.1).max(line.end.1),
             )
-        });
-        let x_size = 2 * (x_max + 1) as usize;
-        let y_size = (y_max + 1) as usize;
-
-        let mut cells = vec![Cell::Air; x_size * y_size];
-
-        for line in lines {
-            let (dx, dy) = (line.end.0 - line.start.0, line.end.1 - line.start.1);
-
-            let steps = max(dx.abs(), dy.abs());
-            let (dx_, dy_) = if dx == 0 && dy == 0 {
-                (0, 0)
-            } else {
-                (dx / steps, dy / steps)
-            };
-
-            for i in 0..=steps {
-                let (x, y) = (
-                    (line.start.0 + dx_ * i) as usize,
-                    (line.start.1 + dy_ * i) as usize,
-                );
-                cells[y * x_size + x] = Cell::Rock;
-            }
+        },
+    );
+    let offset = x_min - 1;
+    let mut grid = Grid2D::new(
+        (x_max - offset + 2) as usize,
+        (y_max + 1) as usize,
+        Cell::Air,
+    );
+
+    for line in lines {
+        let (dx, dy) = (line.end.0 - line.start.0, line.end.1 - line.start.1);
+
+        let steps = max(dx.abs(), dy.abs());
+        let (dx_, dy_) = if dx == 0 && dy == 0 {
+            (0, 0)
+        } else {
+            (dx / steps, dy / steps)
+        };
+
+        for i in 0..=steps {
+            let (x, y) = (
+                (line.start.0 - offset + dx_ * i) as usize,
+                (line.start.1 + dy_ * i) as usize,
+            );
+            grid[(x, y)] = Cell::Rock;
         }
+    }
 
-        Grid {
-            x_size,
-            y_size,
-            cells,
-        }
+    Cave {
+        grid,
+        offset: offset as isize,
     }
 }
 
 struct Line {
-    start: (i16, i16),
-    end: (i16, i16),
+    start: (i32, i32),
+    end: (i32, i32),
 }
 
-fn parse_node(input: &str) -> (i16, i16) {
-    let mut numbers = input.split(',');
-    (
-        numbers.next().unwrap().parse().unwrap(),
-        numbers.next().unwrap().parse().unwrap(),
-    )
+fn parse_node(input: &str, node: &str) -> Result<(i32, i32), ParseError> {
+    let coordinate = |token: &str| match integer_at::<i32>(input.as_bytes(), token.as_bytes()) {
+        Ok(value) if (0..=MAX_COORDINATE).contains(&value) => Ok(value),
+        _ => Err(ParseError::at(
+            input.as_bytes(),
+            token.as_bytes(),
+            format!("a coordinate from 0 to {}", MAX_COORDINATE),
+        )),
+    };
+
+    match node.split_once(',') {
+        Some((x, y)) => Ok((coordinate(x)?, coordinate(y)?)),
+        None => Err(ParseError::at(
+            input.as_bytes(),
+            node.as_bytes(),
+            "a point like `498,4`",
+        )),
+    }
 }
 
-fn parse_lines(input: &str) -> Vec<Line> {
+fn parse_lines(input: &str) -> Result<Vec<Line>, ParseError> {
     let mut lines = Vec::new();
 
     for line in input.lines() {
         let mut nodes_it = line.split(" -> ");
-        let mut current_node = nodes_it.next().unwrap();
-        for next_node in nodes_it {
+        let mut current_node = parse_node(input, nodes_it.next().unwrap_or(line))?;
+        for token in nodes_it {
+            let next_node = parse_node(input, token)?;
+            if current_node.0 != next_node.0 && current_node.1 != next_node.1 {
+                return Err(ParseError::at(
+                    input.as_bytes(),
+                    token.as_bytes(),
+                    "a point in the same row or column as the previous one",
+                ));
+            }
             lines.push(Line {
-                start: parse_node(current_node),
-                end: parse_node(next_node),
+                start: current_node,
+                end: next_node,
             });
             current_node = next_node
         }
     }
 
-    lines
+    Ok(lines)
 }
 
-fn next_all(sx: usize, sy: usize, grid: &Grid) -> Vec<(usize, usize)> {
+fn next_all(sx: isize, sy: isize, cave: &Cave) -> Vec<(isize, isize)> {
     [(sx, sy + 1), (sx - 1, sy + 1), (sx + 1, sy + 1)]
         .into_iter()
-        .filter(|(x, y)| grid.get(*x, *y) == Cell::Air)
+        .filter(|(x, y)| cave.cell(*x, *y) == Cell::Air)
         .collect()
 }
 
-fn next(sx: usize, sy: usize, grid: &Grid) -> Option<(usize, usize)> {
-    if grid.get(sx, sy + 1) == Cell::Air {
+fn next(sx: isize, sy: isize, cave: &Cave) -> Option<(isize, isize)> {
+    if cave.cell(sx, sy + 1) == Cell::Air {
         Some((sx, sy + 1))
-    } else if grid.get(sx - 1, sy + 1) == Cell::Air {
+    } else if cave.cell(sx - 1, sy + 1) == Cell::Air {
         Some((sx - 1, sy + 1))
-    } else if grid.get(sx + 1, sy + 1) == Cell::Air {
+    } else if cave.cell(sx + 1, sy + 1) == Cell::Air {
         Some((sx + 1, sy + 1))
     } else {
         None
     }
 }
 
-pub(crate) fn part1(input: &str) -> usize {
-    let lines = parse_lines(input);
-    let mut grid = Grid::from_lines(&lines);
+pub fn parse(input: &str) -> Result<Cave, ParseError> {
+    parse_lines(input).map(|lines| cave_from_lines(&lines))
+}
+
+pub fn part1(cave: &Cave) -> usize {
+    let mut cave = cave.clone();
 
     let mut result = 0;
 
     'outer: loop {
-        let (mut sx, mut sy) = (500, 0);
+        let (mut sx, mut sy) = (SOURCE.0 as isize, SOURCE.1 as isize);
 
-        while let Some((sx_, sy_)) = next(sx, sy, &grid) {
-            if sy >= grid.y_size {
+        while let Some((sx_, sy_)) = next(sx, sy, &cave) {
+            if sy >= cave.height() {
                 break 'outer;
             }
             (sx, sy) = (sx_, sy_);
         }
 
-        grid.set(sx, sy, Cell::Sand);
+        // Sand only comes to rest next to rock or sand, which is within the grid.
+        cave.grid[((sx - cave.offset) as usize, sy as usize)] = Cell::Sand;
         result += 1;
     }
 
     result
 }
 
-pub(crate) fn part2(input: &str) -> usize {
-    let lines = parse_lines(input);
-    let grid = Grid::from_lines(&lines);
-
-    let mut queue: VecDeque<(usize, usize)> = VecDeque::new();
-    let mut visited: HashSet<(usize, usize)> = HashSet::new();
+pub fn part2(cave: &Cave) -> usize {
+    let source = (SOURCE.0 as isize, SOURCE.1 as isize);
+    let mut queue: VecDeque<(isize, isize)> = VecDeque::new();
+    let mut visited: HashSet<(isize, isize)> = HashSet::new();
     let mut result = 1;
 
-    queue.push_back((500, 0));
-    visited.insert((500, 0));
+    queue.push_back(source);
+    visited.insert(source);
 
     while let Some((x, y)) = queue.pop_front() {
-        for (next_x, next_y) in next_all(x, y, &grid) {
-            if y + 1 > grid.y_size {
+        for (next_x, next_y) in next_all(x, y, cave) {
+            if y + 1 > cave.height() {
                 continue;
             }
             if visited.contains(&(next_x, next_y)) {
@@ -171,41 +209,100 @@ pub(crate) fn part2(input: &str) -> usize {
     result
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Parsed = Cave;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(&String::from_utf8_lossy(input))
+    }
+
+    fn part1(cave: &Self::Parsed, _: &()) -> Answer {
+        part1(cave).into()
+    }
+
+    fn part2(cave: &Self::Parsed, _: &()) -> Answer {
+        part2(cave).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &str = include_str!("../inputs/day14.txt");
+    const EXAMPLE: &str = include_str!("../examples/day14.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 757)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 757)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 24943)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 24943)
+    }
+
+    #[test]
+    fn test_example() {
+        let cave = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&cave), 24);
+        assert_eq!(part2(&cave), 93);
     }
 
-    #[bench]
-    fn bench_parser(b: &mut Bencher) {
-        b.iter(|| parse_lines(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let error = parse_lines("498,4 -> 498,6\n503,4 -> 502;4\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 10, "502;4")
+        );
+        let error = parse_lines("498,4 -> 498,-6\n").err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (14, "-6"));
+        let error = parse_lines("498,4 -> 498,10001\n").err().unwrap();
+        assert_eq!(error.expected, "a coordinate from 0 to 10000");
+        let error = parse_lines("498,4 -> 500,6\n").err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (10, "500,6"));
+    }
+
+    #[test]
+    fn test_large_input() {
+        // A rock at the largest coordinate, and one so deep that the sand of part 2 spreads past
+        // `x = 0`. The short rock holds one unit of sand in part 1 and shadows one cell in part 2.
+        let cave = parse("499,502 -> 501,502\n9990,10 -> 10000,10\n").unwrap();
+        assert_eq!(part1(&cave), 1);
+        assert_eq!(part2(&cave), 504 * 504 - 3 - 1);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parser() {
+        bench("day14::parser", || parse_lines(INPUT))
     }
 
-    #[bench]
-    fn bench_grid(b: &mut Bencher) {
-        let lines = parse_lines(INPUT);
-        b.iter(|| Grid::from_lines(&lines))
+    #[test]
+    #[ignore]
+    fn bench_grid() {
+        let lines = parse_lines(INPUT).unwrap();
+        bench("day14::grid", || cave_from_lines(&lines))
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let cave = parse(INPUT).unwrap();
+        bench("day14::part1", || part1(&cave))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let cave = parse(INPUT).unwrap();
+        bench("day14::part2", || part2(&cave))
     }
 }
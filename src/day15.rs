@@ -1,144 +1,321 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integers;
+use crate::point::Point2;
+use crate::solution::{params, Answer, Solution};
 use regex::Regex;
 
-pub(crate) const INPUT: &str = include_str!("../inputs/day15.txt");
+params! {
+    /// The published example uses a row of 10 and coordinates up to 20, see `params.txt`.
+    pub struct Params {
+        /// Row checked in part 1.
+        pub row: isize = 2_000_000,
+        /// Largest coordinate searched in part 2.
+        pub max_coordinate: isize = 4_000_000,
+    }
+}
 
 #[derive(Debug)]
-struct Sensor {
-    position: (isize, isize),
-    nearest_beacon_position: (isize, isize),
+pub struct Sensor {
+    position: Point2<isize>,
+    nearest_beacon_position: Point2<isize>,
     nearest_beacon_distance: isize,
 }
 
 impl Sensor {
-    fn contains(&self, x: isize, y: isize) -> bool {
-        dist(self.position.0, self.position.1, x, y) <= self.nearest_beacon_distance
+    pub fn position(&self) -> Point2<isize> {
+        self.position
     }
 
-    fn left_most(&self) -> isize {
-        self.position.0 - self.nearest_beacon_distance + 1
+    pub fn nearest_beacon_position(&self) -> Point2<isize> {
+        self.nearest_beacon_position
     }
 
-    fn right_most(&self) -> isize {
-        self.position.0 + self.nearest_beacon_distance - 1
+    fn contains(&self, point: Point2<isize>) -> bool {
+        // The distance to a far away point may not fit into 64 bits, and then it is not covered.
+        self.position
+            .x
+            .abs_diff(point.x)
+            .checked_add(self.position.y.abs_diff(point.y))
+            .is_some_and(|distance| distance <= self.nearest_beacon_distance as usize)
     }
 }
 
-fn dist(x1: isize, y1: isize, x2: isize, y2: isize) -> isize {
-    (x1 - x2).abs() + (y1 - y2).abs()
-}
-
-fn parse_sensors(input: &str) -> Vec<Sensor> {
-    let re = Regex::new(r"x=(-?\d+), y=(-?\d+).+x=(-?\d+), y=(-?\d+)$").unwrap();
+pub fn parse(input: &str) -> Result<Vec<Sensor>, ParseError> {
+    let re =
+        Regex::new(r"^Sensor at x=-?\d+, y=-?\d+: closest beacon is at x=-?\d+, y=-?\d+$").unwrap();
 
     input
         .lines()
         .map(|line| {
-            let mut captures_it = re.captures_iter(line);
-            let capture = captures_it.next().unwrap();
-            let position: (isize, isize) =
-                (capture[1].parse().unwrap(), capture[2].parse().unwrap());
-            let nearest_beacon_position: (isize, isize) =
-                (capture[3].parse().unwrap(), capture[4].parse().unwrap());
-            let nearest_beacon_distance = (nearest_beacon_position.0 - position.0).abs()
-                + (nearest_beacon_position.1 - position.1).abs();
-            Sensor {
+            if !re.is_match(line) {
+                return Err(ParseError::at(
+                    input.as_bytes(),
+                    line.as_bytes(),
+                    "`Sensor at x=<x>, y=<y>: closest beacon is at x=<x>, y=<y>`",
+                ));
+            }
+            let coordinates: Vec<isize> =
+                integers(input.as_bytes(), line.as_bytes()).collect::<Result<_, _>>()?;
+            let [x, y, beacon_x, beacon_y] = coordinates[..] else {
+                unreachable!("the pattern has four numbers");
+            };
+            let position = Point2::new(x, y);
+            let nearest_beacon_position = Point2::new(beacon_x, beacon_y);
+            let nearest_beacon_distance = x
+                .abs_diff(beacon_x)
+                .checked_add(y.abs_diff(beacon_y))
+                .and_then(|distance| isize::try_from(distance).ok())
+                .ok_or_else(|| {
+                    ParseError::at(
+                        input.as_bytes(),
+                        line.as_bytes(),
+                        "a beacon at a distance of at most 2^63 - 1",
+                    )
+                })?;
+            Ok(Sensor {
                 position,
                 nearest_beacon_position,
                 nearest_beacon_distance,
-            }
+            })
         })
         .collect()
 }
 
-pub(crate) fn part1(input: &str) -> usize {
-    let sensors = parse_sensors(input);
+pub fn part1(sensors: &[Sensor], row: isize) -> usize {
+    // The covered positions of each sensor in the row, from the left to the right.
+    let mut ranges: Vec<(isize, isize)> = sensors
+        .iter()
+        .filter_map(|sensor| {
+            let vertical_dist = sensor.position.y.abs_diff(row);
+            let remaining_dist =
+                (sensor.nearest_beacon_distance as usize).checked_sub(vertical_dist)?;
+            let remaining_dist = remaining_dist as isize;
+            // Positions beyond the coordinates do not exist.
+            Some((
+                sensor.position.x.saturating_sub(remaining_dist),
+                sensor.position.x.saturating_add(remaining_dist),
+            ))
+        })
+        .collect();
+    ranges.sort_unstable();
 
-    let (left, right) = sensors
+    let mut result: usize = 0;
+    let mut next_x = isize::MIN;
+    for (left, right) in ranges {
+        let left = left.max(next_x);
+        if left <= right {
+            result = result
+                .checked_add(right.abs_diff(left) + 1)
+                .expect("more than 2^64 - 1 positions in the row are covered");
+            next_x = right.saturating_add(1);
+        }
+    }
+
+    let mut beacons: Vec<isize> = sensors
         .iter()
-        .fold((isize::MAX, isize::MIN), |(left, right), sensor| {
-            let (left_, right_) = (sensor.left_most(), sensor.right_most());
-            (std::cmp::min(left, left_), std::cmp::max(right, right_))
-        });
+        .map(|sensor| sensor.nearest_beacon_position)
+        .filter(|beacon| beacon.y == row)
+        .map(|beacon| beacon.x)
+        .collect();
+    beacons.sort_unstable();
+    beacons.dedup();
 
-    let row: isize = 2000000;
+    // Every beacon in the row is covered by its own sensor.
+    result - beacons.len()
+}
 
-    let mut x = left - 1;
-    let mut result: usize = 0;
+/// The positions from 0 to `max_coordinate` where the first position not covered by any sensor,
+/// going row by row, can be. Turned by 45 degrees, to `u = x + y` and `v = x - y`, the ranges of
+/// the sensors are squares. The first position that is not covered has covered positions above
+/// and to its left, unless it is at the edge, so it is on a line of constant `u` or `v` just
+/// outside of a sensor. Either it is on two lines of different kinds, or the covered positions
+/// diagonally above it put it on a line one further out.
+fn candidates(sensors: &[Sensor], max_coordinate: isize) -> Vec<Point2<isize>> {
+    let (mut us, mut vs) = (Vec::new(), Vec::new());
+    for sensor in sensors {
+        let (x, y) = (sensor.position.x as i128, sensor.position.y as i128);
+        let distance = sensor.nearest_beacon_distance as i128;
+        for offset in [distance + 1, distance + 2] {
+            us.extend([x + y - offset, x + y + offset]);
+            vs.extend([x - y - offset, x - y + offset]);
+        }
+    }
+    let max = max_coordinate as i128;
+    for edge in [0, max] {
+        us.extend([edge, edge + max]);
+        vs.extend([edge, edge - max]);
+    }
+    for lines in [&mut us, &mut vs] {
+        lines.sort_unstable();
+        lines.dedup();
+    }
 
-    while x <= right {
-        if let Some(sensor) = sensors.iter().find(|sensor| sensor.contains(x, row)) {
-            let vertical_dist = (sensor.position.1 - row).abs();
-            let remaining_dist = sensor.nearest_beacon_distance - vertical_dist;
-            let next_x = sensor.position.0 + remaining_dist + 1;
-            result += (next_x - x) as usize;
-            if sensor.nearest_beacon_position.1 == row
-                && x <= sensor.nearest_beacon_position.0
-                && next_x > sensor.nearest_beacon_position.0
-            {
-                result -= 1;
+    let mut candidates = Vec::new();
+    let mut add = |x: i128, y: i128| {
+        if (0..=max).contains(&x) && (0..=max).contains(&y) {
+            candidates.push(Point2::new(x as isize, y as isize));
+        }
+    };
+    for u in &us {
+        for v in &vs {
+            if (u + v) % 2 == 0 {
+                add((u + v) / 2, (u - v) / 2);
             }
-            x = next_x;
-        } else {
-            x += 1;
+        }
+        // Where the lines cross the edges.
+        for edge in [0, max] {
+            add(edge, u - edge);
+            add(u - edge, edge);
+        }
+    }
+    for v in &vs {
+        for edge in [0, max] {
+            add(edge, edge - v);
+            add(v + edge, edge);
         }
     }
+    candidates
+}
+
+/// The tuning frequency of the only position with coordinates from 0 to `max_coordinate` that no
+/// sensor covers, or `None` if every such position is covered, e.g. for a negative `max_coordinate`.
+pub fn part2(sensors: &[Sensor], max_coordinate: isize) -> Option<usize> {
+    let mut candidates = candidates(sensors, max_coordinate);
+    candidates.sort_unstable_by_key(|position| (position.y, position.x));
 
-    result
+    let position = candidates
+        .into_iter()
+        .find(|position| !sensors.iter().any(|sensor| sensor.contains(*position)))?;
+    let frequency = position
+        .x
+        .checked_mul(4_000_000)
+        .and_then(|frequency| frequency.checked_add(position.y))
+        .expect("a tuning frequency beyond 64 bits");
+    Some(frequency as usize)
 }
 
-pub(crate) fn part2(input: &str) -> usize {
-    let sensors = parse_sensors(input);
+pub struct Day15;
 
-    let max_dimensions: isize = 4000000;
+impl Solution for Day15 {
+    type Parsed = Vec<Sensor>;
+    type Params = Params;
 
-    for y in 0..=max_dimensions {
-        let mut x: isize = 0;
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(&String::from_utf8_lossy(input))
+    }
 
-        while x <= max_dimensions {
-            if let Some(sensor) = sensors.iter().find(|sensor| sensor.contains(x, y)) {
-                let vertical_dist = (y - sensor.position.1).abs();
-                let remaining_dist = sensor.nearest_beacon_distance - vertical_dist;
-                x = sensor.position.0 + remaining_dist + 1;
-            } else {
-                return (4000000 * x + y) as usize;
-            }
-        }
+    fn part1(sensors: &Self::Parsed, params: &Params) -> Answer {
+        part1(sensors, params.row).into()
     }
 
-    panic!()
+    fn part2(sensors: &Self::Parsed, params: &Params) -> Answer {
+        part2(sensors, params.max_coordinate).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &str = include_str!("../inputs/day15.txt");
+    const EXAMPLE: &str = include_str!("../examples/day15.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 5394423)
+        assert_eq!(part1(&parse(INPUT).unwrap(), 2000000), 5394423)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 11840879211051)
+        assert_eq!(part2(&parse(INPUT).unwrap(), 4000000), Some(11840879211051))
     }
 
-    #[bench]
-    fn bench_parse(b: &mut Bencher) {
-        b.iter(|| parse_sensors(INPUT))
+    #[test]
+    fn test_example() {
+        let sensors = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&sensors, 10), 26);
+        assert_eq!(part2(&sensors, 20), Some(56000011));
+        assert_eq!(part2(&sensors, 0), None);
+        assert_eq!(part2(&sensors, -1), None);
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=15\nSensor at x=9: oops\n";
+        let error = parse(input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        let input = "Sensor at x=2, y=18: closest beacon is at x=-2, y=99999999999999999999\n";
+        let error = parse(input).err().unwrap();
+        assert_eq!(error.column, 51);
+
+        // Beacons too far away for their distance to fit into 64 bits.
+        let (min, max) = (isize::MIN, isize::MAX);
+        let input = format!(
+            "Sensor at x=0, y=0: closest beacon is at x=1, y=1\n\
+             Sensor at x={}, y=0: closest beacon is at x={}, y=0\n",
+            min, max
+        );
+        let error = parse(&input).err().unwrap();
+        assert_eq!((error.line, error.column), (2, 1));
+        let input = format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+            min, min, max, max
+        );
+        assert_eq!(parse(&input).err().unwrap().line, 1);
+
+        // A sensor at the far end of the coordinates is fine, as long as its beacon is close.
+        let input = format!(
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+            min,
+            min,
+            min + 1,
+            min
+        );
+        let sensors = parse(&input).unwrap();
+        assert_eq!(part2(&sensors, 20), Some(0));
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    fn test_large_input() {
+        // A search area 100 times as large as the puzzle's, with the distress beacon between four
+        // sensors at `(±a, ±a)` from it that just miss it, see `gen::sensors`.
+        let (max_coordinate, a) = (400_000_000, 250_000_000);
+        let distress = Point2::new(123_456_789, 234_567_890);
+        let mut input = String::new();
+        for (sx, sy) in [(1, 1), (1, -1), (-1, 1), (-1, -1)] {
+            let position = Point2::new(distress.x + sx * a, distress.y + sy * a);
+            let beacon = Point2::new(position.x + sx * (2 * a - 1), position.y);
+            input.push_str(&format!(
+                "Sensor at x={}, y={}: closest beacon is at x={}, y={}\n",
+                position.x, position.y, beacon.x, beacon.y
+            ));
+        }
+        let sensors = parse(&input).unwrap();
+        assert_eq!(part1(&sensors, distress.y), 2 * (2 * a - 1) as usize);
+        assert_eq!(
+            part2(&sensors, max_coordinate),
+            Some((4_000_000 * distress.x + distress.y) as usize)
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day15::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let sensors = parse(INPUT).unwrap();
+        bench("day15::part1", || part1(&sensors, 2000000))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let sensors = parse(INPUT).unwrap();
+        bench("day15::part2", || part2(&sensors, 4000000))
     }
 }
@@ -1,19 +1,29 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::search::Search;
+use crate::solution::{params, Answer, Solution};
 use std::cmp::Reverse;
 use std::collections::{BinaryHeap, HashMap, HashSet};
 use std::fmt::Debug;
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day16.txt");
+params! {
+    pub struct Params {
+        pub part1_minutes: u16 = 30,
+        /// Minutes left for both you and the elephant in part 2.
+        pub part2_minutes: u16 = 26,
+    }
+}
 
 type Vertex = u16;
 type Edge = (Vertex, Vertex);
 type Distances = HashMap<Edge, usize>;
 
 #[derive(Debug, Ord, PartialOrd, Eq, PartialEq, Hash, Copy, Clone)]
-struct PackedValves(u32);
+struct PackedValves(u64);
 
 impl PackedValves {
+    const CAPACITY: usize = u64::BITS as usize;
+
     fn new() -> Self {
         Self(0)
     }
@@ -32,7 +42,7 @@ struct StateV2 {
     cumulative_flow: usize,
     opened: PackedValves,
     positions: [u8; 2],
-    times: [u8; 2],
+    times: [u16; 2],
 }
 
 impl StateV2 {
@@ -61,14 +71,14 @@ impl StateV2 {
 }
 
 #[derive(Debug)]
-struct FullyConnectedGraph {
+pub struct FullyConnectedGraph {
     num_vertices: usize,
-    weights: Vec<u8>,
-    values: Vec<u8>,
+    weights: Vec<u16>,
+    values: Vec<u32>,
 }
 
 impl FullyConnectedGraph {
-    fn get_weight(&self, v1: usize, v2: usize) -> u8 {
+    fn get_weight(&self, v1: usize, v2: usize) -> u16 {
         self.weights[v1 * self.num_vertices + v2]
     }
 
@@ -84,29 +94,31 @@ impl FullyConnectedGraph {
             .collect();
 
         let num_vertices = vertex_map.len();
-        let distances = initial_graph.distances();
-        let mut weights: Vec<u8> = Vec::with_capacity(num_vertices * num_vertices);
+        let distances = &initial_graph.distances;
+        let mut weights: Vec<u16> = Vec::with_capacity(num_vertices * num_vertices);
 
         for v1 in 0..num_vertices as u8 {
             for v2 in 0..num_vertices as u8 {
                 if v1 == v2 {
                     weights.push(0);
                 } else {
-                    weights.push(
-                        *distances
-                            .get(&(*vertex_map.get(&v1).unwrap(), *vertex_map.get(&v2).unwrap()))
-                            .unwrap() as u8,
-                    );
+                    // There are at most 26 * 26 valves, so distances fit into `u16`. The parser
+                    // checks that `AA` and the valves with a nonzero flow rate reach each other.
+                    let edge = (vertex_map[&v1], vertex_map[&v2]);
+                    let Some(distance) = distances.get(&edge) else {
+                        unreachable!("valve {} cannot reach valve {}", edge.0, edge.1);
+                    };
+                    weights.push(*distance as u16);
                 }
             }
         }
 
-        let values: Vec<u8> = (0..num_vertices as u8)
+        let values: Vec<u32> = (0..num_vertices as u8)
             .map(|v| {
                 *initial_graph
                     .values
                     .get(vertex_map.get(&v).unwrap())
-                    .unwrap() as u8
+                    .unwrap()
             })
             .collect();
 
@@ -122,11 +134,12 @@ impl FullyConnectedGraph {
             .map(|time| {
                 let mut best_for_time: Vec<BestValvesHeuristics> = (0..self.num_vertices)
                     .flat_map(|valve| {
-                        let dists = (1..self.num_vertices)
+                        // From any other valve including `AA`, where both start.
+                        let dists = (0..self.num_vertices)
                             .filter(|next_valve| *next_valve != valve)
                             .map(|next_valve| self.get_weight(next_valve, valve) + 1);
-                        let min_dist = dists.min().unwrap();
-                        (time as u8 > min_dist).then_some(BestValvesHeuristics {
+                        let min_dist = dists.min()?;
+                        (time as u16 > min_dist).then_some(BestValvesHeuristics {
                             valve: valve as u8,
                             dist: min_dist,
                             flow: self.values[valve],
@@ -150,77 +163,136 @@ impl FullyConnectedGraph {
 #[derive(Debug)]
 struct BestValvesHeuristics {
     valve: u8,
-    dist: u8,
-    flow: u8,
+    dist: u16,
+    flow: u32,
 }
 
 struct InitialGraph {
     vertices: Vec<Vertex>,
     edges: Vec<Edge>,
-    values: HashMap<Vertex, usize>,
+    values: HashMap<Vertex, u32>,
+    distances: Distances,
 }
 
 impl InitialGraph {
-    fn parse(bytes: &[u8]) -> Self {
+    fn parse(bytes: &[u8]) -> Result<Self, ParseError> {
         let mut vertices: Vec<Vertex> = Vec::new();
         let mut edges: Vec<(Vertex, Vertex)> = Vec::new();
-        let mut values: HashMap<Vertex, usize> = HashMap::new();
+        let mut values: HashMap<Vertex, u32> = HashMap::new();
         let mut nonzero_vertices: Vec<Vertex> = Vec::new();
+        let mut targets: Vec<(Vertex, &[u8])> = Vec::new();
+        let mut names: Vec<(Vertex, &[u8])> = Vec::new();
 
         for line in bytes.trim_ascii_end().split(|byte| *byte == b'\n') {
-            let mut split = line.split(|byte| *byte == b';');
-            let first_half = split.next().unwrap();
-            let (char1, char2) = ((first_half[6] - b'A') as u16, (first_half[7] - b'A') as u16);
-            let vertex = 26 * char1 + char2;
+            let expected = "`Valve <name> has flow rate=<rate>; tunnels lead to valves <names>`";
+            let Some((first_half, second_half)) = line
+                .iter()
+                .position(|byte| *byte == b';')
+                .map(|index| line.split_at(index))
+            else {
+                return Err(ParseError::at(bytes, line, expected));
+            };
+
+            let Some(rest) = first_half.strip_prefix(b"Valve ") else {
+                return Err(ParseError::at(bytes, line, expected));
+            };
+            let (name, rest) = rest.split_at(rest.len().min(2));
+            let vertex = valve(bytes, name)?;
             vertices.push(vertex);
+            names.push((vertex, name));
 
-            let rate = std::str::from_utf8(&first_half[23..])
-                .unwrap()
-                .parse()
-                .unwrap();
+            let Some(rate) = rest.strip_prefix(b" has flow rate=") else {
+                return Err(ParseError::at(bytes, rest, expected));
+            };
+            let rate = integer_at(bytes, rate)?;
             if rate != 0 {
                 nonzero_vertices.push(vertex);
+                // The opened valves are a bit set, which also holds `AA`.
+                if nonzero_vertices.len() >= PackedValves::CAPACITY {
+                    let expected = format!(
+                        "at most {} valves with a nonzero flow rate",
+                        PackedValves::CAPACITY - 1
+                    );
+                    return Err(ParseError::at(bytes, line, expected));
+                }
             }
 
             values.insert(vertex, rate);
 
-            let second_half = &split.next().unwrap()[23..];
+            let Some(second_half) = second_half
+                .strip_prefix(b"; tunnels lead to valves ")
+                .or_else(|| second_half.strip_prefix(b"; tunnel leads to valve "))
+            else {
+                return Err(ParseError::at(bytes, second_half, expected));
+            };
             for next_vertex in second_half.split(|byte| *byte == b',') {
-                let next_vertex = next_vertex.trim_ascii_start();
-                let (char1, char2) = (
-                    (next_vertex[0] - b'A') as u16,
-                    (next_vertex[1] - b'A') as u16,
-                );
-                let next_vertex = 26 * char1 + char2;
+                let name = next_vertex.trim_ascii_start();
+                let next_vertex = valve(bytes, name)?;
+                targets.push((next_vertex, name));
                 edges.push((vertex, next_vertex));
             }
         }
 
-        InitialGraph {
+        if let Some((_, token)) = targets
+            .iter()
+            .find(|(target, _)| !values.contains_key(target))
+        {
+            return Err(ParseError::at(bytes, token, "the name of a listed valve"));
+        }
+        if !values.contains_key(&0) {
+            return Err(ParseError::at(
+                bytes,
+                &bytes[bytes.len()..],
+                "a valve named `AA`",
+            ));
+        }
+
+        let mut graph = InitialGraph {
             vertices,
             edges,
             values,
+            distances: HashMap::new(),
+        };
+        graph.distances = graph.distances();
+
+        // Both start at `AA` and walk between the valves with a nonzero flow rate.
+        let name = |vertex: Vertex| names.iter().find(|(v, _)| *v == vertex).map(|(_, n)| *n);
+        let valves: Vec<Vertex> = std::iter::once(0).chain(nonzero_vertices).collect();
+        for end in &valves {
+            if let Some(start) = valves
+                .iter()
+                .find(|start| start != &end && !graph.distances.contains_key(&(**start, *end)))
+            {
+                let (Some(start), Some(end)) = (name(*start), name(*end)) else {
+                    unreachable!("every valve with a flow rate has a name");
+                };
+                let expected = format!(
+                    "a valve reachable from `{}`",
+                    String::from_utf8_lossy(start)
+                );
+                return Err(ParseError::at(bytes, end, expected));
+            }
         }
+
+        Ok(graph)
     }
 
     fn distances(&self) -> Distances {
-        let mut distances: Distances = HashMap::new();
-
+        let mut neighbours: HashMap<Vertex, Vec<Vertex>> = HashMap::new();
         for (start, end) in self.edges.iter() {
-            distances.insert((*start, *end), 1);
+            neighbours.entry(*start).or_default().push(*end);
         }
 
-        for vertex_k in self.vertices.iter() {
-            for vertex_i in self.vertices.iter() {
-                for vertex_j in self.vertices.iter() {
-                    let d_ij = distances.get(&(*vertex_i, *vertex_j)).unwrap_or(&10000);
-                    let d_ik = distances.get(&(*vertex_i, *vertex_k)).unwrap_or(&10000);
-                    let d_kj = distances.get(&(*vertex_k, *vertex_j)).unwrap_or(&10000);
-                    if *d_ij > *d_ik + *d_kj {
-                        distances.insert((*vertex_i, *vertex_j), d_ik + d_kj);
-                    }
-                }
-            }
+        let mut distances: Distances = HashMap::new();
+        for start in self.vertices.iter() {
+            Search::new().bfs(
+                [*start],
+                |vertex| neighbours.get(vertex).into_iter().flatten().copied(),
+                |vertex, distance| {
+                    distances.insert((*start, *vertex), distance);
+                    false
+                },
+            );
         }
 
         distances
@@ -228,7 +300,8 @@ impl InitialGraph {
 }
 
 fn max_cumulative_flow(graph: &FullyConnectedGraph, initial_state: &StateV2) -> usize {
-    let heuristics = graph.best_valves_heuristics(30);
+    let max_time = initial_state.times[0].max(initial_state.times[1]);
+    let heuristics = graph.best_valves_heuristics(max_time as usize);
 
     let mut visited: HashSet<StateV2> = HashSet::new();
     let mut queue: BinaryHeap<(usize, StateV2)> = BinaryHeap::new();
@@ -248,6 +321,19 @@ fn max_cumulative_flow(graph: &FullyConnectedGraph, initial_state: &StateV2) ->
             continue;
         }
 
+        // The one with more time left opens no more valves, which leaves the rest to the other.
+        if state.times[1] > 0 {
+            let next_state = StateV2 {
+                positions: [state.positions[1], state.positions[0]],
+                times: [state.times[1], 0],
+                ..state.clone()
+            };
+            let upper = next_state.apply_heuristics(&heuristics);
+            if upper > best {
+                queue.push((upper, next_state))
+            }
+        }
+
         for (next, flow) in graph.values.iter().enumerate().skip(1) {
             {
                 let dist = graph.get_weight(state.positions[0] as usize, next);
@@ -300,75 +386,207 @@ fn max_cumulative_flow(graph: &FullyConnectedGraph, initial_state: &StateV2) ->
     best
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let initial_graph = InitialGraph::parse(input);
-    let graph = FullyConnectedGraph::from_initial_graph(&initial_graph);
+fn valve(input: &[u8], name: &[u8]) -> Result<Vertex, ParseError> {
+    match name {
+        [char1 @ b'A'..=b'Z', char2 @ b'A'..=b'Z'] => {
+            Ok(26 * (char1 - b'A') as u16 + (char2 - b'A') as u16)
+        }
+        _ => Err(ParseError::at(
+            input,
+            name,
+            "a valve name of two uppercase letters",
+        )),
+    }
+}
+
+pub fn parse(input: &[u8]) -> Result<FullyConnectedGraph, ParseError> {
+    InitialGraph::parse(input).map(|graph| FullyConnectedGraph::from_initial_graph(&graph))
+}
 
+pub fn part1(graph: &FullyConnectedGraph, minutes: u16) -> usize {
     let initial_state = StateV2 {
         cumulative_flow: 0,
         opened: PackedValves::new().set(0),
         positions: [0, 0],
-        times: [30, 0],
+        times: [minutes, 0],
     };
 
-    max_cumulative_flow(&graph, &initial_state)
+    max_cumulative_flow(graph, &initial_state)
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let initial_graph = InitialGraph::parse(input);
-    let graph = FullyConnectedGraph::from_initial_graph(&initial_graph);
-
+pub fn part2(graph: &FullyConnectedGraph, minutes: u16) -> usize {
     let initial_state = StateV2 {
         cumulative_flow: 0,
         opened: PackedValves::new().set(0),
         positions: [0, 0],
-        times: [26, 26],
+        times: [minutes, minutes],
     };
 
-    max_cumulative_flow(&graph, &initial_state)
+    max_cumulative_flow(graph, &initial_state)
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Parsed = FullyConnectedGraph;
+    type Params = Params;
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(graph: &Self::Parsed, params: &Params) -> Answer {
+        part1(graph, params.part1_minutes).into()
+    }
+
+    fn part2(graph: &Self::Parsed, params: &Params) -> Answer {
+        part2(graph, params.part2_minutes).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day16.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day16.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 2183)
+        assert_eq!(part1(&parse(INPUT).unwrap(), 30), 2183)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2911)
+        assert_eq!(part2(&parse(INPUT).unwrap(), 26), 2911)
     }
 
-    #[bench]
-    fn bench_parse(b: &mut Bencher) {
-        b.iter(|| InitialGraph::parse(INPUT))
+    #[test]
+    fn test_example() {
+        let graph = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&graph, 30), 1651);
+        assert_eq!(part2(&graph, 26), 1707);
+    }
+
+    #[test]
+    fn test_pruning() {
+        // A scan on which part 2 was wrong, when the upper bound ignored the distances from `AA`
+        // and only the one with more time left could open the next valve.
+        let input = b"Valve SG has flow rate=0; tunnels lead to valves PE, LF, QO, UM\n\
+                      Valve QQ has flow rate=0; tunnel leads to valve DA\n\
+                      Valve AY has flow rate=12; tunnel leads to valve UM\n\
+                      Valve QO has flow rate=9; tunnels lead to valves EI, AA, SG, DA\n\
+                      Valve UM has flow rate=0; tunnels lead to valves AA, AY, SG\n\
+                      Valve PE has flow rate=0; tunnels lead to valves AA, SG, EI\n\
+                      Valve DA has flow rate=10; tunnels lead to valves AA, EI, QQ, QO\n\
+                      Valve EI has flow rate=0; tunnels lead to valves DA, QO, PE, YU\n\
+                      Valve VK has flow rate=0; tunnel leads to valve LF\n\
+                      Valve AA has flow rate=0; tunnels lead to valves PE, UM, DA, QO\n\
+                      Valve LF has flow rate=0; tunnels lead to valves SG, VK, YU\n\
+                      Valve YU has flow rate=4; tunnels lead to valves LF, EI\n";
+        let graph = parse(input).unwrap();
+        assert_eq!(part1(&graph, 30), 846);
+        assert_eq!(part2(&graph, 26), 790);
     }
 
-    #[bench]
-    fn bench_distances(b: &mut Bencher) {
-        let graph = InitialGraph::parse(INPUT);
-        b.iter(|| graph.distances())
+    #[test]
+    fn test_parse_error() {
+        let input = b"Valve AA has flow rate=0; tunnels lead to valves BB, CC\n\
+                      Valve BB has flow rate=x; tunnel leads to valve AA\n";
+        let error = InitialGraph::parse(input).err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (2, 24, "x")
+        );
+        let input = b"Valve AA has flow rate=0; tunnels lead to valves BB, CC\n\
+                      Valve BB has flow rate=3; tunnel leads to valve AA\n";
+        let error = InitialGraph::parse(input).err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (1, 54, "CC")
+        );
+        let input = b"Valve AA has flow rate=0; tunnel leads to valve BB\n\
+                      Valve BB has flow rate=3; tunnel leads to valve AA\n\
+                      Valve CC has flow rate=5; tunnel leads to valve AA\n";
+        let error = InitialGraph::parse(input).err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.expected.as_str()),
+            (3, 7, "a valve reachable from `AA`")
+        );
     }
 
-    #[bench]
-    fn bench_construct_full_graph(b: &mut Bencher) {
-        let initial_graph = InitialGraph::parse(INPUT);
-        b.iter(|| FullyConnectedGraph::from_initial_graph(&initial_graph))
+    /// The valve at `index` when counting from `AA` to `ZZ`.
+    fn name(index: usize) -> String {
+        String::from_utf8(vec![b'A' + (index / 26) as u8, b'A' + (index % 26) as u8]).unwrap()
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    /// A line of the scan for valve `index` with tunnels to `neighbours`.
+    fn scan_line(index: usize, rate: u32, neighbours: impl Iterator<Item = usize>) -> String {
+        let neighbours = neighbours.map(name).collect::<Vec<_>>().join(", ");
+        format!(
+            "Valve {} has flow rate={}; tunnels lead to valves {}\n",
+            name(index),
+            rate,
+            neighbours
+        )
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    fn test_large_input() {
+        // A corridor longer than `u8::MAX` to a single valve with a rate beyond `u8::MAX`.
+        let input: String = (0..=300_usize)
+            .map(|index| {
+                let rate = if index == 300 { 1000 } else { 0 };
+                let neighbours = index.saturating_sub(1)..=(index + 1).min(300);
+                scan_line(index, rate, neighbours.filter(|next| *next != index))
+            })
+            .collect();
+        let graph = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&graph, 30), 0);
+        assert_eq!(part1(&graph, 400), 1000 * (400 - 301));
+        assert_eq!(part2(&graph, 400), 1000 * (400 - 301));
+
+        // The opened valves are a bit set of 64 valves, including `AA`.
+        let input: String = std::iter::once(scan_line(0, 0, 1..=64))
+            .chain((1..=64).map(|index| scan_line(index, 1, 0..1)))
+            .collect();
+        assert_eq!(parse(input.as_bytes()).err().unwrap().line, 65);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day16::parse", || InitialGraph::parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_distances() {
+        let graph = InitialGraph::parse(INPUT).unwrap();
+        bench("day16::distances", || graph.distances())
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_construct_full_graph() {
+        let initial_graph = InitialGraph::parse(INPUT).unwrap();
+        bench("day16::construct_full_graph", || {
+            FullyConnectedGraph::from_initial_graph(&initial_graph)
+        })
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let graph = parse(INPUT).unwrap();
+        bench("day16::part1", || part1(&graph, 30))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let graph = parse(INPUT).unwrap();
+        bench("day16::part2", || part2(&graph, 26))
     }
 }
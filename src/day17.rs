@@ -1,8 +1,17 @@
-extern crate test;
-
+use crate::cycle;
+use crate::error::ParseError;
+use crate::point::Point2;
+use crate::solution::{params, Answer, Solution};
 use std::collections::HashSet;
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day17.txt");
+params! {
+    pub struct Params {
+        /// Number of rocks that fall in part 1.
+        pub part1_rocks: usize = 2022,
+        /// Number of rocks that fall in part 2, found by detecting a cycle in the tower.
+        pub part2_rocks: usize = 1_000_000_000_000,
+    }
+}
 
 #[derive(Copy, Clone, Debug)]
 enum Instruction {
@@ -11,31 +20,43 @@ enum Instruction {
 }
 
 #[derive(Debug)]
-struct Instructions {
+pub struct Instructions {
     instructions: Vec<u8>,
 }
 
 impl Instructions {
-    fn from_bytes(bytes: &[u8]) -> Self {
-        Self {
-            instructions: Vec::from(bytes.trim_ascii_end()),
-        }
-    }
-
     fn get(&self, pos: usize) -> Instruction {
         match self.instructions[pos % self.instructions.len()] {
             b'<' => Instruction::L,
             b'>' => Instruction::R,
-            _ => panic!(),
+            jet => unreachable!("the parser rejects the jet `{}`", jet as char),
         }
     }
 }
 
+pub fn parse(bytes: &[u8]) -> Result<Instructions, ParseError> {
+    let instructions = bytes.trim_ascii_end();
+    if let Some(index) = instructions
+        .iter()
+        .position(|byte| *byte != b'<' && *byte != b'>')
+    {
+        let jet = &instructions[index..=index];
+        return Err(ParseError::at(bytes, jet, "`<` or `>`"));
+    }
+    if instructions.is_empty() {
+        return Err(ParseError::at(bytes, instructions, "a jet pattern"));
+    }
+    Ok(Instructions {
+        instructions: Vec::from(instructions),
+    })
+}
+
 enum FallResult {
     Resting,
     Moving,
 }
 
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Piece {
     Plus,
     Minus,
@@ -55,51 +76,77 @@ impl Piece {
         }
     }
 
-    fn pattern(&self) -> Vec<(usize, usize)> {
+    fn pattern(&self) -> Vec<Point2<usize>> {
         match self {
-            Piece::Plus => vec![(0, 0), (0, 1), (0, 2), (0, 3)],
-            Piece::Minus => vec![(0, 1), (1, 0), (1, 1), (1, 2), (2, 1)],
-            Piece::L => vec![(0, 0), (0, 1), (0, 2), (1, 2), (2, 2)],
-            Piece::I => vec![(0, 0), (1, 0), (2, 0), (3, 0)],
-            Piece::Square => vec![(0, 0), (0, 1), (1, 0), (1, 1)],
+            Piece::Plus => vec![
+                Point2::new(0, 0),
+                Point2::new(1, 0),
+                Point2::new(2, 0),
+                Point2::new(3, 0),
+            ],
+            Piece::Minus => vec![
+                Point2::new(1, 0),
+                Point2::new(0, 1),
+                Point2::new(1, 1),
+                Point2::new(2, 1),
+                Point2::new(1, 2),
+            ],
+            Piece::L => vec![
+                Point2::new(0, 0),
+                Point2::new(1, 0),
+                Point2::new(2, 0),
+                Point2::new(2, 1),
+                Point2::new(2, 2),
+            ],
+            Piece::I => vec![
+                Point2::new(0, 0),
+                Point2::new(0, 1),
+                Point2::new(0, 2),
+                Point2::new(0, 3),
+            ],
+            Piece::Square => vec![
+                Point2::new(0, 0),
+                Point2::new(1, 0),
+                Point2::new(0, 1),
+                Point2::new(1, 1),
+            ],
         }
     }
 
-    fn initial_pattern(&self, height: usize) -> Vec<(usize, usize)> {
+    fn initial_pattern(&self, height: usize) -> Vec<Point2<usize>> {
         let mut pattern = self.pattern();
-        for (y, x) in pattern.iter_mut() {
-            *y += height + 3;
-            *x += 2;
+        for point in pattern.iter_mut() {
+            *point += Point2::new(2, height + 3);
         }
         pattern
     }
 }
 
 fn shift(
-    piece_pattern: &mut Vec<(usize, usize)>,
-    rocks: &HashSet<(usize, usize)>,
+    piece_pattern: &mut Vec<Point2<usize>>,
+    rocks: &HashSet<Point2<usize>>,
     instruction: Instruction,
 ) {
     let mut next_pattern = piece_pattern.clone();
     match instruction {
         Instruction::L => {
-            for (y, x) in next_pattern.iter_mut() {
-                if *x == 0 {
+            for point in next_pattern.iter_mut() {
+                if point.x == 0 {
                     return;
                 }
-                *x -= 1;
-                if rocks.contains(&(*y, *x)) {
+                point.x -= 1;
+                if rocks.contains(point) {
                     return;
                 }
             }
         }
         Instruction::R => {
-            for (y, x) in next_pattern.iter_mut() {
-                if *x >= 6 {
+            for point in next_pattern.iter_mut() {
+                if point.x >= 6 {
                     return;
                 }
-                *x += 1;
-                if rocks.contains(&(*y, *x)) {
+                point.x += 1;
+                if rocks.contains(point) {
                     return;
                 }
             }
@@ -108,18 +155,15 @@ fn shift(
     std::mem::swap(&mut next_pattern, piece_pattern)
 }
 
-fn fall(
-    piece_pattern: &mut Vec<(usize, usize)>,
-    rocks: &mut HashSet<(usize, usize)>,
-) -> FallResult {
+fn fall(piece_pattern: &mut Vec<Point2<usize>>, rocks: &mut HashSet<Point2<usize>>) -> FallResult {
     let mut next_pattern = piece_pattern.clone();
-    for (y, x) in next_pattern.iter_mut() {
-        if *y == 0 {
+    for point in next_pattern.iter_mut() {
+        if point.y == 0 {
             rocks.extend(piece_pattern.iter());
             return FallResult::Resting;
         }
-        *y -= 1;
-        if rocks.contains(&(*y, *x)) {
+        point.y -= 1;
+        if rocks.contains(point) {
             rocks.extend(piece_pattern.iter());
             return FallResult::Resting;
         }
@@ -129,133 +173,227 @@ fn fall(
     FallResult::Moving
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let instructions = Instructions::from_bytes(input);
-
-    let mut rocks: HashSet<(usize, usize)> = HashSet::new();
-    let mut height: usize = 0;
-    let mut piece = Piece::Plus;
-    let mut piece_pattern = piece.initial_pattern(height);
-
-    let mut cycle: usize = 0;
-    let mut pieces_count: usize = 0;
-
-    while pieces_count < 2022 {
-        let instruction = instructions.get(cycle);
-        shift(&mut piece_pattern, &rocks, instruction);
-        match fall(&mut piece_pattern, &mut rocks) {
-            FallResult::Moving => {}
-            FallResult::Resting => {
-                height = std::cmp::max(
-                    height,
-                    piece_pattern.iter().map(|(y, _)| *y + 1).max().unwrap(),
-                );
-                piece = piece.next();
-                piece_pattern = piece.initial_pattern(height);
-                pieces_count += 1;
-            }
+/// How many rows below the top of the tower are part of the state at first. The rows are doubled
+/// until no piece of the repetition falls deeper, which makes the deeper rows irrelevant.
+const TOP_ROWS: usize = 64;
+
+/// The number of pieces after which a repetition must have been found.
+const MAX_PIECES: usize = 100_000;
+
+struct Chamber<'a> {
+    instructions: &'a Instructions,
+    rocks: HashSet<Point2<usize>>,
+    height: usize,
+    piece: Piece,
+    jet: usize,
+    /// How many rows below the top of the tower the last piece got to, counting the one it rests
+    /// on and the floor as a row.
+    reach: usize,
+}
+
+impl<'a> Chamber<'a> {
+    fn new(instructions: &'a Instructions) -> Self {
+        Chamber {
+            instructions,
+            rocks: HashSet::new(),
+            height: 0,
+            piece: Piece::Plus,
+            jet: 0,
+            reach: 0,
         }
+    }
 
-        cycle += 1;
+    fn drop_piece(&mut self) {
+        let mut piece_pattern = self.piece.initial_pattern(self.height);
+        loop {
+            let instruction = self.instructions.get(self.jet);
+            self.jet = (self.jet + 1) % self.instructions.instructions.len();
+            shift(&mut piece_pattern, &self.rocks, instruction);
+            if let FallResult::Resting = fall(&mut piece_pattern, &mut self.rocks) {
+                break;
+            }
+        }
+        let bottom = piece_pattern.iter().map(|point| point.y).min().unwrap();
+        self.reach = (self.height + 1).saturating_sub(bottom);
+        self.height = std::cmp::max(
+            self.height,
+            piece_pattern.iter().map(|point| point.y + 1).max().unwrap(),
+        );
+        self.piece = self.piece.next();
     }
 
-    height
+    /// The next piece and jet, and the top `rows` rows as bit masks, with the floor as full rows.
+    fn key(&self, rows: usize) -> (Piece, usize, Vec<u8>) {
+        let rows = (0..rows)
+            .map(|depth| match self.height.checked_sub(depth + 1) {
+                Some(y) => (0..7)
+                    .filter(|x| self.rocks.contains(&Point2::new(*x, y)))
+                    .fold(0, |row, x| row | 1 << x),
+                None => 0b111_1111,
+            })
+            .collect();
+        (self.piece, self.jet, rows)
+    }
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let instructions = Instructions::from_bytes(input);
-
-    let mut rocks: HashSet<(usize, usize)> = HashSet::new();
-    let mut height: usize = 0;
-    let mut piece = Piece::Plus;
-    let mut piece_pattern = piece.initial_pattern(height);
-
-    let mut cycle: usize = 0;
-    let mut pieces_count: usize = 0;
-
-    let mut diffs: Vec<usize> = Vec::new();
-
-    while pieces_count < 200000 {
-        let instruction = instructions.get(cycle);
-        shift(&mut piece_pattern, &rocks, instruction);
-        match fall(&mut piece_pattern, &mut rocks) {
-            FallResult::Moving => {}
-            FallResult::Resting => {
-                let old_height = height;
-                height = std::cmp::max(
-                    height,
-                    piece_pattern.iter().map(|(y, _)| *y + 1).max().unwrap(),
-                );
-                piece = piece.next();
-                piece_pattern = piece.initial_pattern(height);
-                pieces_count += 1;
-
-                diffs.push(height - old_height);
-            }
+/// The height of the tower after `pieces` pieces, extrapolated from a repetition within the first
+/// `limit` pieces, or `None` if it is higher than `usize::MAX`. Without a repetition, the pieces
+/// are simulated if there are at most `limit` of them.
+fn height_after(instructions: &Instructions, pieces: usize, limit: usize) -> Option<usize> {
+    let mut rows = TOP_ROWS;
+    loop {
+        let mut reaches = Vec::new();
+        let cycle = cycle::find_by_key(
+            Chamber::new(instructions),
+            |chamber| {
+                chamber.drop_piece();
+                reaches.push(chamber.reach);
+            },
+            |chamber| chamber.key(rows),
+            |chamber| chamber.height,
+            limit,
+        );
+        let Some(cycle) = cycle else {
+            return (pieces <= limit).then(|| part1(instructions, pieces));
+        };
+
+        // The pieces of one period only depend on the top rows if none of them falls deeper.
+        let period = cycle.start..cycle.start + cycle.period;
+        let reach = reaches[period].iter().copied().max().unwrap_or(0);
+        if reach <= rows {
+            return cycle.value_at(pieces);
         }
+        rows = reach.max(2 * rows);
+    }
+}
 
-        cycle += 1;
+pub fn part1(instructions: &Instructions, rocks: usize) -> usize {
+    let mut chamber = Chamber::new(instructions);
+    for _ in 0..rocks {
+        chamber.drop_piece();
     }
+    chamber.height
+}
 
-    let before: usize;
-    let mut window_size = if instructions.instructions.len() % 5 == 0 {
-        instructions.instructions.len()
-    } else {
-        instructions.instructions.len() * 5
-    };
+/// The height of the tower, or `None` if it does not repeat soon enough to extrapolate it or
+/// grows higher than `usize::MAX`.
+pub fn part2(instructions: &Instructions, rocks: usize) -> Option<usize> {
+    height_after(instructions, rocks, MAX_PIECES)
+}
 
-    'outer: loop {
-        for i in 0..diffs.len() {
-            if i + 2 * window_size >= diffs.len() {
-                break;
-            }
-            let window_1 = &diffs[i..i + window_size];
-            let window_2 = &diffs[i + window_size..i + 2 * window_size];
-            if window_1 == window_2 {
-                before = i;
-                break 'outer;
-            }
-        }
-        window_size += 5;
-    }
+pub struct Day17;
 
-    let total = 1000000000000;
-    let cycle_length = window_size;
-    let fitting_cycles = (total - before) / cycle_length;
-    let after = (total - before) % cycle_length;
+impl Solution for Day17 {
+    type Parsed = Instructions;
+    type Params = Params;
 
-    let sum_before: usize = diffs.iter().take(before).sum();
-    let sum_fitting: usize =
-        diffs.iter().skip(before).take(cycle_length).sum::<usize>() * fitting_cycles;
-    let sum_after: usize = diffs.iter().skip(before).take(after).sum();
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
 
-    sum_before + sum_fitting + sum_after
+    fn part1(instructions: &Self::Parsed, params: &Params) -> Answer {
+        part1(instructions, params.part1_rocks).into()
+    }
+
+    fn part2(instructions: &Self::Parsed, params: &Params) -> Answer {
+        part2(instructions, params.part2_rocks).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day17.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day17.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 3119)
+        assert_eq!(part1(&parse(INPUT).unwrap(), 2022), 3119)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 1536994219669)
+        assert_eq!(
+            part2(&parse(INPUT).unwrap(), 1_000_000_000_000),
+            Some(1536994219669)
+        )
+    }
+
+    #[test]
+    fn test_example() {
+        let instructions = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&instructions, 2022), 3068);
+        assert_eq!(part2(&instructions, 1_000_000_000_000), Some(1514285714288));
+    }
+
+    #[test]
+    fn test_no_repetition() {
+        let instructions = parse(EXAMPLE).unwrap();
+        assert_eq!(height_after(&instructions, 1_000_000, 10), None);
+        assert_eq!(
+            height_after(&instructions, 10, 10),
+            Some(part1(&instructions, 10))
+        );
+    }
+
+    #[test]
+    fn test_too_high() {
+        // The tower of the example grows by about 1.5 rows per piece.
+        let instructions = parse(EXAMPLE).unwrap();
+        assert_eq!(part2(&instructions, usize::MAX), None);
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_deep_shaft() {
+        // Pieces of the repetition fall 54 rows below the top of the tower.
+        let instructions = parse(b"<<>>>>>><>><><<>>><><><><<>>><>").unwrap();
+        assert_eq!(
+            part2(&instructions, 10_000),
+            Some(part1(&instructions, 10_000))
+        );
+        assert_eq!(part2(&instructions, 10_000), Some(16601));
+        let instructions = parse(b">").unwrap();
+        assert_eq!(
+            part2(&instructions, 10_000),
+            Some(part1(&instructions, 10_000))
+        );
+    }
+
+    #[test]
+    fn test_large_input() {
+        // The jets of the example 2500 times over, 10 times as many as in the puzzle, push the
+        // pieces the same way but only repeat after 17,500 pieces.
+        let instructions = parse(EXAMPLE.trim_ascii_end().repeat(2500).as_slice()).unwrap();
+        assert_eq!(part1(&instructions, 2022), 3068);
+        assert_eq!(part2(&instructions, 1_000_000_000_000), Some(1514285714288));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"<<>\n>").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 4, ""));
+        assert!(parse(b"\n").is_err());
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day17::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let instructions = parse(INPUT).unwrap();
+        bench("day17::part1", || part1(&instructions, 2022))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let instructions = parse(INPUT).unwrap();
+        bench("day17::part2", || part2(&instructions, 1_000_000_000_000))
     }
 }
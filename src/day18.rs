@@ -1,171 +1,268 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer;
+use crate::point::Point3;
+use crate::search::{Indexed, Search};
+use crate::solution::{Answer, Solution};
 use bitvec::bitvec;
 use bitvec::prelude::BitVec;
-use std::collections::VecDeque;
-
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day18.txt");
-
-const GRID_SIZE: usize = 23;
-const GRID_CENTRE_DOUBLED: usize = 23;
-
-const POWERS_OF_TEN: [usize; 3] = [1, 10, 100];
+use std::cell::Cell;
 
-fn usize_from_bytes(bytes: &[u8]) -> usize {
-    bytes
-        .iter()
-        .rev()
-        .enumerate()
-        .fold(0, |acc, (index, byte)| {
-            acc + (byte - b'0') as usize * POWERS_OF_TEN[index]
-        })
-}
+/// Largest number of cells of the compressed grid, so that it stays below 200 MB.
+const MAX_CELLS: usize = 1 << 30;
 
-struct Grid {
+pub struct Grid {
     grid: BitVec,
-    cubes: Vec<(usize, usize, usize)>,
+    cubes: Vec<Point3<usize>>,
+    size: Point3<usize>,
 }
 
 impl Grid {
-    fn parse(bytes: &[u8]) -> Self {
-        let mut cubes = Vec::new();
-        let mut grid = bitvec![0; GRID_SIZE*GRID_SIZE*GRID_SIZE];
-
-        bytes
-            .trim_ascii_end()
-            .split(|byte| *byte == b'\n')
-            .map(|line| {
-                let mut coords = line.split(|byte| *byte == b',');
-
-                // Adding 2 to all coordinates to make sure we have a boundary of 1 and can work with
-                // unsigned integers
-                let x = usize_from_bytes(coords.next().unwrap()) + 2;
-                let y = usize_from_bytes(coords.next().unwrap()) + 2;
-                let z = usize_from_bytes(coords.next().unwrap()) + 2;
-                (x, y, z)
-            })
-            .for_each(|(x, y, z)| {
-                cubes.push((x, y, z));
-                grid.set(z * GRID_SIZE * GRID_SIZE + y * GRID_SIZE + x, true);
-            });
+    fn get_cubes(&self) -> &[Point3<usize>] {
+        &self.cubes
+    }
 
-        Grid { grid, cubes }
+    fn get_index(&self, position: &Point3<usize>) -> usize {
+        (position.z * self.size.y + position.y) * self.size.x + position.x
     }
 
-    fn get_cubes(&self) -> &[(usize, usize, usize)] {
-        &self.cubes
+    fn is_cube(&self, position: &Point3<usize>) -> bool {
+        self.grid[self.get_index(position)]
     }
 
-    fn get_index(position: &(usize, usize, usize)) -> usize {
-        position.2 * GRID_SIZE * GRID_SIZE + position.1 * GRID_SIZE + position.0
+    fn is_in_bounds(&self, position: &Point3<usize>) -> bool {
+        (1..self.size.x - 1).contains(&position.x)
+            && (1..self.size.y - 1).contains(&position.y)
+            && (1..self.size.z - 1).contains(&position.z)
     }
+}
 
-    fn is_cube(&self, position: &(usize, usize, usize)) -> bool {
-        self.grid[Self::get_index(position)]
+/// Maps the coordinates of one axis to consecutive cells, with a single cell for every gap
+/// between them, so that cubes touch in the grid exactly when they touch in space and the air
+/// around them stays connected the same way. Returns the cell of every coordinate and the number
+/// of cells, leaving 2 cells on both sides for the boundary and its neighbours.
+fn compress(coordinates: impl Iterator<Item = usize>) -> (Vec<(usize, usize)>, usize) {
+    let mut coordinates: Vec<usize> = coordinates.collect();
+    coordinates.sort_unstable();
+    coordinates.dedup();
+
+    let mut cells = Vec::with_capacity(coordinates.len());
+    let mut cell = 0;
+    for (index, coordinate) in coordinates.iter().enumerate() {
+        cell += match index.checked_sub(1).map(|previous| coordinates[previous]) {
+            Some(previous) if coordinate - previous == 1 => 1,
+            _ => 2,
+        };
+        cells.push((*coordinate, cell));
     }
+    (cells, cell + 3)
+}
+
+pub fn parse(bytes: &[u8]) -> Result<Grid, ParseError> {
+    let mut positions = Vec::new();
+    let mut lines = bytes.trim_ascii_end().split(|byte| *byte == b'\n');
 
-    fn copy_neighbours_into_buffer(
-        position: &(usize, usize, usize),
-        neighbours: &mut [(usize, usize, usize)],
-    ) {
-        let (x, y, z) = (position.0, position.1, position.2);
-        neighbours.copy_from_slice(&[
-            (x - 1, y, z),
-            (x + 1, y, z),
-            (x, y - 1, z),
-            (x, y + 1, z),
-            (x, y, z - 1),
-            (x, y, z + 1),
-        ]);
+    for line in lines.clone() {
+        let mut coords = line.split(|byte| *byte == b',');
+        let mut coord = || {
+            let coord = coords.next().unwrap_or(&line[line.len()..]);
+            integer::<usize>(coord).ok_or_else(|| ParseError::at(bytes, coord, "a coordinate"))
+        };
+
+        let (x, y, z) = (coord()?, coord()?, coord()?);
+        if let Some(rest) = coords.next() {
+            return Err(ParseError::at(bytes, rest, "end of line"));
+        }
+        positions.push(Point3::new(x, y, z));
     }
 
-    fn is_in_bounds(position: &(usize, usize, usize)) -> bool {
-        GRID_CENTRE_DOUBLED.abs_diff(2 * position.0).pow(2)
-            + GRID_CENTRE_DOUBLED.abs_diff(2 * position.1).pow(2)
-            + GRID_CENTRE_DOUBLED.abs_diff(2 * position.2).pow(2)
-            <= (GRID_CENTRE_DOUBLED - 1).pow(2)
+    let (xs, size_x) = compress(positions.iter().map(|position| position.x));
+    let (ys, size_y) = compress(positions.iter().map(|position| position.y));
+    let (zs, size_z) = compress(positions.iter().map(|position| position.z));
+    let size = Point3::new(size_x, size_y, size_z);
+    let cells = size_x
+        .checked_mul(size_y)
+        .and_then(|cells| cells.checked_mul(size_z));
+    if cells.is_none_or(|cells| cells > MAX_CELLS) {
+        let last = lines.next_back().unwrap_or_default();
+        return Err(ParseError::end_of(
+            bytes,
+            last,
+            "cubes at fewer different coordinates",
+        ));
     }
-}
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let grid = Grid::parse(input);
-    let mut neighbours_buffer: Vec<(usize, usize, usize)> = vec![(0, 0, 0); 6];
+    let cell = |cells: &[(usize, usize)], coordinate: usize| {
+        let index = cells.partition_point(|(other, _)| *other < coordinate);
+        cells[index].1
+    };
+    let cubes: Vec<Point3<usize>> = positions
+        .iter()
+        .map(|position| {
+            Point3::new(
+                cell(&xs, position.x),
+                cell(&ys, position.y),
+                cell(&zs, position.z),
+            )
+        })
+        .collect();
+
+    let mut grid = Grid {
+        grid: bitvec![0; size.x * size.y * size.z],
+        cubes: Vec::new(),
+        size,
+    };
+    for cube in &cubes {
+        let index = grid.get_index(cube);
+        grid.grid.set(index, true);
+    }
+    grid.cubes = cubes;
+
+    Ok(grid)
+}
 
+pub fn part1(grid: &Grid) -> usize {
     grid.get_cubes().iter().fold(0, |acc, cube| {
-        Grid::copy_neighbours_into_buffer(cube, &mut neighbours_buffer);
-        acc + neighbours_buffer
-            .iter()
+        acc + cube
+            .neighbours_6()
             .filter(|neighbour| !grid.is_cube(neighbour))
             .count()
     })
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let grid = Grid::parse(input);
-    let mut neighbours_buffer: Vec<(usize, usize, usize)> = vec![(0, 0, 0); 6];
+pub fn part2(grid: &Grid) -> usize {
+    if grid.cubes.is_empty() {
+        return 0;
+    }
 
-    let mut visited: BitVec = bitvec![0; GRID_SIZE*GRID_SIZE*GRID_SIZE];
+    let surface_area = Cell::new(0);
+    let faces = &surface_area;
 
-    let mut queue: VecDeque<(usize, usize, usize)> = VecDeque::new();
-    queue.push_back((1, GRID_SIZE / 2, GRID_SIZE / 2));
+    // Flood fill the air around the droplet, counting the faces of the cubes it touches.
+    Search::with_visited(Indexed::new(grid.grid.len(), |position: &Point3<usize>| {
+        grid.get_index(position)
+    }))
+    .bfs(
+        [Point3::new(1, 1, 1)],
+        move |position| {
+            position.neighbours_6().filter(move |next| {
+                if grid.is_cube(next) {
+                    faces.set(faces.get() + 1);
+                    false
+                } else {
+                    grid.is_in_bounds(next)
+                }
+            })
+        },
+        |_, _| false,
+    );
 
-    let mut surface_area: usize = 0;
+    surface_area.get()
+}
 
-    while let Some(position) = queue.pop_front() {
-        let index = Grid::get_index(&position);
-        if visited[index] {
-            continue;
-        }
+pub struct Day18;
 
-        if grid.is_cube(&position) {
-            surface_area += 1
-        } else {
-            visited.set(index, true);
-            Grid::copy_neighbours_into_buffer(&position, &mut neighbours_buffer);
-            for next in &neighbours_buffer {
-                if !Grid::is_in_bounds(next) {
-                    continue;
-                }
-                if visited[Grid::get_index(next)] {
-                    continue;
-                }
-                queue.push_back(*next);
-            }
-        }
+impl Solution for Day18 {
+    type Parsed = Grid;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(grid: &Self::Parsed, _: &()) -> Answer {
+        part1(grid).into()
     }
 
-    surface_area
+    fn part2(grid: &Self::Parsed, _: &()) -> Answer {
+        part2(grid).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day18.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day18.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 3448)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 3448)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 2052)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 2052)
+    }
+
+    #[test]
+    fn test_example() {
+        let grid = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&grid), 64);
+        assert_eq!(part2(&grid), 58);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"2,2,2\n1,2\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 4, ""));
+        let error = parse(b"2,-1,2\n").err().unwrap();
+        assert_eq!((error.column, error.text.as_str()), (3, "-1"));
+    }
+
+    #[test]
+    fn test_large_input() {
+        // Beyond the coordinates of the puzzle: a solid block of 10 x 10 x 10 cubes, and a block
+        // of 3 x 3 x 3 cubes without its center, whose 6 inner faces are not on the outside.
+        let mut input = String::new();
+        for (x, y, z) in (0..1000).map(|index| (index % 10, index / 10 % 10, index / 100)) {
+            input.push_str(&format!("{},{},{}\n", 25 + x, 25 + y, 25 + z));
+        }
+        for (x, y, z) in (0..27).map(|index| (index % 3, index / 3 % 3, index / 9)) {
+            if (x, y, z) != (1, 1, 1) {
+                input.push_str(&format!("{},{},{}\n", 40 + x, 40 + y, 40 + z));
+            }
+        }
+        let grid = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&grid), 600 + 54 + 6);
+        assert_eq!(part2(&grid), 600 + 54);
+
+        // Cubes far apart, and a hollow block of 5 x 5 x 5 cubes far out with a cube in its pocket.
+        let grid = parse(b"1,1,1\n1000000,1000000,1000000\n").unwrap();
+        assert_eq!((part1(&grid), part2(&grid)), (12, 12));
+        let mut input = String::from("0,0,0\n1000002,1000002,1000002\n");
+        for (x, y, z) in (0..125).map(|index| (index % 5, index / 5 % 5, index / 25)) {
+            if [x, y, z]
+                .iter()
+                .any(|coordinate| [0, 4].contains(coordinate))
+            {
+                let [x, y, z] = [x, y, z].map(|coordinate| 1_000_000 + coordinate);
+                input.push_str(&format!("{},{},{}\n", x, y, z));
+            }
+        }
+        let grid = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&grid), 6 + 150 + 54 + 6);
+        assert_eq!(part2(&grid), 6 + 150);
     }
 
-    #[bench]
-    fn bench_parse(b: &mut Bencher) {
-        b.iter(|| Grid::parse(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day18::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let grid = parse(INPUT).unwrap();
+        bench("day18::part1", || part1(&grid))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let grid = parse(INPUT).unwrap();
+        bench("day18::part2", || part2(&grid))
     }
 }
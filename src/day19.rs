@@ -1,8 +1,16 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integers;
+use crate::solution::{params, Answer, Solution};
 use regex::Regex;
 
-pub(crate) const INPUT: &str = include_str!("../inputs/day19.txt");
+params! {
+    pub struct Params {
+        pub part1_minutes: u32 = 24,
+        pub part2_minutes: u32 = 32,
+        /// Number of blueprints that are not eaten by the elephants in part 2.
+        pub part2_blueprints: usize = 3,
+    }
+}
 
 #[derive(Debug, Clone)]
 enum Robot {
@@ -13,7 +21,7 @@ enum Robot {
 }
 
 #[derive(Debug, Clone)]
-struct Blueprint {
+pub struct Blueprint {
     id: u32,
     ore_robot_costs_ore: u32,
     clay_robot_costs_ore: u32,
@@ -25,6 +33,10 @@ struct Blueprint {
 }
 
 impl Blueprint {
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
     fn required_ore(&self, robot: &Robot) -> u32 {
         match robot {
             Robot::Ore => self.ore_robot_costs_ore,
@@ -73,7 +85,7 @@ struct State {
 }
 
 const fn div_up(a: u32, b: u32) -> u32 {
-    (a + b - 1) / b
+    a.div_ceil(b)
 }
 
 impl State {
@@ -232,89 +244,157 @@ impl State {
     }
 }
 
-fn parse_blueprints(input: &str, max_blueprints: Option<usize>) -> Vec<Blueprint> {
-    let re = Regex::new(r"Blueprint (\d+): Each ore robot costs (\d+) ore\. Each clay robot costs (\d+) ore\. Each obsidian robot costs (\d+) ore and (\d+) clay\. Each geode robot costs (\d+) ore and (\d+) obsidian\.").unwrap();
+pub fn parse(input: &str) -> Result<Vec<Blueprint>, ParseError> {
+    let re = Regex::new(r"^Blueprint \d+: Each ore robot costs \d+ ore\. Each clay robot costs \d+ ore\. Each obsidian robot costs \d+ ore and \d+ clay\. Each geode robot costs \d+ ore and \d+ obsidian\.$").unwrap();
 
     input
         .trim_end()
         .lines()
-        .take(max_blueprints.unwrap_or(usize::MAX))
         .map(|line| {
-            let cap = re.captures(line).unwrap();
-            Blueprint {
-                id: cap[1].parse().unwrap(),
-                ore_robot_costs_ore: cap[2].parse().unwrap(),
-                clay_robot_costs_ore: cap[3].parse().unwrap(),
-                obsidian_robot_costs_ore: cap[4].parse().unwrap(),
-                obsidian_robot_costs_clay: cap[5].parse().unwrap(),
-                geode_robot_costs_ore: cap[6].parse().unwrap(),
-                geode_robot_costs_obsidian: cap[7].parse().unwrap(),
-                max_costs_ore: 0,
+            if !re.is_match(line) {
+                return Err(ParseError::at(
+                    input.as_bytes(),
+                    line.as_bytes(),
+                    "`Blueprint <id>: Each ore robot costs <n> ore. ...`",
+                ));
             }
-        })
-        .map(|blueprint: Blueprint| Blueprint {
-            id: blueprint.id,
-            ore_robot_costs_ore: blueprint.ore_robot_costs_ore,
-            clay_robot_costs_ore: blueprint.clay_robot_costs_ore,
-            obsidian_robot_costs_ore: blueprint.obsidian_robot_costs_ore,
-            obsidian_robot_costs_clay: blueprint.obsidian_robot_costs_clay,
-            geode_robot_costs_ore: blueprint.geode_robot_costs_ore,
-            geode_robot_costs_obsidian: blueprint.geode_robot_costs_obsidian,
-            max_costs_ore: blueprint.ore_robot_costs_ore.max(
-                blueprint.clay_robot_costs_ore.max(
-                    blueprint
-                        .obsidian_robot_costs_ore
-                        .max(blueprint.geode_robot_costs_ore),
+            let numbers: Vec<u32> =
+                integers(input.as_bytes(), line.as_bytes()).collect::<Result<_, _>>()?;
+            let [id, ore, clay, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian] =
+                numbers[..]
+            else {
+                unreachable!("the pattern has seven numbers");
+            };
+            let blueprint = Blueprint {
+                id,
+                ore_robot_costs_ore: ore,
+                clay_robot_costs_ore: clay,
+                obsidian_robot_costs_ore: obsidian_ore,
+                obsidian_robot_costs_clay: obsidian_clay,
+                geode_robot_costs_ore: geode_ore,
+                geode_robot_costs_obsidian: geode_obsidian,
+                max_costs_ore: 0,
+            };
+            Ok(Blueprint {
+                max_costs_ore: blueprint.ore_robot_costs_ore.max(
+                    blueprint.clay_robot_costs_ore.max(
+                        blueprint
+                            .obsidian_robot_costs_ore
+                            .max(blueprint.geode_robot_costs_ore),
+                    ),
                 ),
-            ),
+                ..blueprint
+            })
         })
-        .collect::<Vec<Blueprint>>()
+        .collect()
 }
 
-pub(crate) fn part1(input: &str) -> usize {
-    let blueprints = parse_blueprints(input, None);
-
+pub fn part1(blueprints: &[Blueprint], minutes: u32) -> usize {
     blueprints
         .iter()
         .map(|blueprint: &Blueprint| {
-            blueprint.id as usize * State::new_with_one_ore(24).dfs(0, blueprint) as usize
+            blueprint.id as usize * State::new_with_one_ore(minutes).dfs(0, blueprint) as usize
         })
-        .sum()
+        .try_fold(0_usize, |sum, quality| sum.checked_add(quality))
+        .expect("the quality levels add up to more than 2^64 - 1")
 }
 
-pub(crate) fn part2(input: &str) -> usize {
-    let blueprints = parse_blueprints(input, Some(3));
-
+pub fn part2(blueprints: &[Blueprint], minutes: u32, count: usize) -> usize {
     blueprints
         .iter()
-        .map(|blueprint: &Blueprint| State::new_with_one_ore(32).dfs(0, blueprint) as usize)
-        .product()
+        .take(count)
+        .map(|blueprint: &Blueprint| State::new_with_one_ore(minutes).dfs(0, blueprint) as usize)
+        .try_fold(1_usize, |product, geodes| product.checked_mul(geodes))
+        .expect("the numbers of geodes multiply to more than 2^64 - 1")
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Parsed = Vec<Blueprint>;
+    type Params = Params;
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(&String::from_utf8_lossy(input))
+    }
+
+    fn part1(blueprints: &Self::Parsed, params: &Params) -> Answer {
+        part1(blueprints, params.part1_minutes).into()
+    }
+
+    fn part2(blueprints: &Self::Parsed, params: &Params) -> Answer {
+        part2(blueprints, params.part2_minutes, params.part2_blueprints).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &str = include_str!("../inputs/day19.txt");
+    const EXAMPLE: &str = include_str!("../examples/day19.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 1266)
+        assert_eq!(part1(&parse(INPUT).unwrap(), 24), 1266)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 5800)
+        assert_eq!(part2(&parse(INPUT).unwrap(), 32, 3), 5800)
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_example() {
+        let blueprints = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&blueprints, 24), 33);
+        assert_eq!(part2(&blueprints, 32, 3), 3472);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let error = parse("Blueprint 1: Each ore robot costs 4 ore.\n")
+            .err()
+            .unwrap();
+        assert_eq!((error.line, error.column), (1, 1));
+    }
+
+    #[test]
+    fn test_large_input() {
+        // The first blueprint of the example 100 times, more than 3 times as many as in the
+        // puzzle, with ids so large that their quality levels only add up in 64 bits.
+        let blueprint = EXAMPLE.lines().next().unwrap();
+        let blueprint = blueprint.split_once(": ").unwrap().1;
+        let id = |index: usize| 4_000_000_000 - index;
+        let input: String = (0..100)
+            .map(|index| format!("Blueprint {}: {}\n", id(index), blueprint))
+            .collect();
+        let blueprints = parse(&input).unwrap();
+        assert_eq!(
+            part1(&blueprints, 24),
+            (0..100).map(|index| 9 * id(index)).sum()
+        );
+        assert_eq!(part2(&blueprints, 32, 3), 56 * 56 * 56);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day19::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let blueprints = parse(INPUT).unwrap();
+        bench("day19::part1", || part1(&blueprints, 24))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let blueprints = parse(INPUT).unwrap();
+        bench("day19::part2", || part2(&blueprints, 32, 3))
     }
 }
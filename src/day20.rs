@@ -1,14 +1,52 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::solution::{params, Answer, Solution};
 use std::cmp::Ordering;
 
-pub(crate) const INPUT: &str = include_str!("../inputs/day20.txt");
+params! {
+    pub struct Params {
+        /// Multiplier applied to every number in part 2.
+        pub decryption_key: isize = 811_589_153,
+        /// Number of times the list is mixed in part 2.
+        pub part2_rounds: usize = 10,
+    }
+}
 
-fn solution(input: &str, decryption_key: isize, cycles: usize) -> isize {
-    let numbers = input
+pub fn parse(input: &str) -> Result<Vec<isize>, ParseError> {
+    let numbers: Vec<isize> = input
         .trim_end()
         .lines()
-        .map(|line| line.parse::<isize>().unwrap() * decryption_key)
+        .map(|line| integer_at(input.as_bytes(), line.as_bytes()))
+        .collect::<Result<_, _>>()?;
+
+    let end = &input.as_bytes()[input.len()..];
+    // Numbers move modulo the length minus one, which needs at least two of them.
+    if numbers.len() < 2 {
+        return Err(ParseError::at(
+            input.as_bytes(),
+            end,
+            "at least two numbers",
+        ));
+    }
+    if !numbers.contains(&0) {
+        return Err(ParseError::at(
+            input.as_bytes(),
+            end,
+            "a line containing `0`",
+        ));
+    }
+
+    Ok(numbers)
+}
+
+fn solution(numbers: &[isize], decryption_key: isize, cycles: usize) -> isize {
+    let numbers = numbers
+        .iter()
+        .map(|number| {
+            number
+                .checked_mul(decryption_key)
+                .expect("the decrypted numbers overflow")
+        })
         .collect::<Vec<isize>>();
     let modulus = numbers.len() - 1;
 
@@ -19,11 +57,14 @@ fn solution(input: &str, decryption_key: isize, cycles: usize) -> isize {
             let position = decrypted.iter().position(|(k, _)| *k == index).unwrap();
 
             if decrypted[position].1 != number {
-                panic!();
+                unreachable!(
+                    "number {} at position {} is {} instead of {}",
+                    index, position, decrypted[position].1, number
+                );
             }
 
-            let mut new_position =
-                (position as isize + number).rem_euclid(modulus as isize) as usize;
+            let mut new_position = (position as isize + number.rem_euclid(modulus as isize))
+                .rem_euclid(modulus as isize) as usize;
 
             if new_position == 0 {
                 new_position = modulus;
@@ -52,43 +93,115 @@ fn solution(input: &str, decryption_key: isize, cycles: usize) -> isize {
     let result = decrypted.iter().map(|(_, v)| *v).collect::<Vec<isize>>();
     let position_zero = result.iter().position(|x| *x == 0).unwrap();
 
-    result[(position_zero + 1000).rem_euclid(numbers.len())]
-        + result[(position_zero + 2000).rem_euclid(numbers.len())]
-        + result[(position_zero + 3000).rem_euclid(numbers.len())]
+    [1000, 2000, 3000]
+        .iter()
+        .map(|offset| result[(position_zero + offset) % numbers.len()])
+        .try_fold(0_isize, |sum, number| sum.checked_add(number))
+        .expect("the grove coordinates overflow")
 }
 
-pub(crate) fn part1(input: &str) -> isize {
-    solution(input, 1, 1)
+pub fn part1(numbers: &[isize]) -> isize {
+    solution(numbers, 1, 1)
 }
 
-pub(crate) fn part2(input: &str) -> isize {
-    solution(input, 811589153, 10)
+pub fn part2(numbers: &[isize], decryption_key: isize, rounds: usize) -> isize {
+    solution(numbers, decryption_key, rounds)
+}
+
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Parsed = Vec<isize>;
+    type Params = Params;
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(&String::from_utf8_lossy(input))
+    }
+
+    fn part1(numbers: &Self::Parsed, _: &Params) -> Answer {
+        part1(numbers).into()
+    }
+
+    fn part2(numbers: &Self::Parsed, params: &Params) -> Answer {
+        part2(numbers, params.decryption_key, params.part2_rounds).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &str = include_str!("../inputs/day20.txt");
+    const EXAMPLE: &str = include_str!("../examples/day20.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 6640)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 6640)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 11893839037215)
+        assert_eq!(part2(&parse(INPUT).unwrap(), 811589153, 10), 11893839037215)
+    }
+
+    #[test]
+    fn test_example() {
+        let numbers = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&numbers), 3);
+        assert_eq!(part2(&numbers, 811589153, 10), 1623178306);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse("1\n0\n+-3\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (3, 1, "+-3")
+        );
+        let error = parse("1\n2\n").err().unwrap();
+        assert_eq!(error.line, 3);
+        let error = parse("0\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "at least two numbers")
+        );
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_large_input() {
+        // Numbers beyond `i32::MAX` that are multiples of the 1000 other positions, so that
+        // mixing moves none of them, followed by a zero. The grove coordinates wrap around to
+        // the 1000th, 999th and 998th number.
+        let input: String = (1..=1000_isize)
+            .map(|index| format!("{}\n", index * 1_000_000_000_000))
+            .chain(["0\n".to_string()])
+            .collect();
+        let numbers = parse(&input).unwrap();
+        assert_eq!(part1(&numbers), (1000 + 999 + 998) * 1_000_000_000_000);
+        assert_eq!(
+            part2(&numbers, 1000, 10),
+            (1000 + 999 + 998) * 1_000_000_000_000_000
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day20::parse", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let numbers = parse(INPUT).unwrap();
+        bench("day20::part1", || part1(&numbers))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let numbers = parse(INPUT).unwrap();
+        bench("day20::part2", || part2(&numbers, 811589153, 10))
     }
 }
@@ -1,10 +1,9 @@
-extern crate test;
-
+use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::solution::{Answer, Solution};
 use std::collections::HashMap;
 
-pub(crate) const INPUT: &str = include_str!("../inputs/day21.txt");
-
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 enum Monkey {
     Value(i64),
     Addition(String, String),
@@ -13,204 +12,260 @@ enum Monkey {
     Division(String, String),
 }
 
+/// The result of a checked operation on the numbers of monkeys.
+fn checked(value: Option<i64>) -> i64 {
+    value.expect("a monkey yells a number beyond 64 bits or divides by zero")
+}
+
+/// Only monkeys with a single operand that depends on `humn` can be solved for it.
+fn both_unknown(l1: &str, l2: &str) -> ! {
+    panic!("both `{}` and `{}` depend on `humn`", l1, l2)
+}
+
 impl Monkey {
+    /// The names of the monkeys whose numbers this one needs.
+    fn operands(&self) -> Option<(&String, &String)> {
+        match self {
+            Monkey::Value(_) => None,
+            Monkey::Addition(l1, l2)
+            | Monkey::Subtraction(l1, l2)
+            | Monkey::Multiplication(l1, l2)
+            | Monkey::Division(l1, l2) => Some((l1, l2)),
+        }
+    }
+
     fn compute(&self, values: &HashMap<String, i64>) -> Option<i64> {
         match self {
-            Monkey::Value(_) => {
-                panic!()
-            }
+            Monkey::Value(value) => Some(*value),
             Monkey::Addition(l1, l2) => {
                 let m1 = values.get(l1)?;
                 let m2 = values.get(l2)?;
-                Some(m1 + m2)
+                Some(checked(m1.checked_add(*m2)))
             }
             Monkey::Subtraction(l1, l2) => {
                 let m1 = values.get(l1)?;
                 let m2 = values.get(l2)?;
-                Some(m1 - m2)
+                Some(checked(m1.checked_sub(*m2)))
             }
             Monkey::Multiplication(l1, l2) => {
                 let m1 = values.get(l1)?;
                 let m2 = values.get(l2)?;
-                Some(m1 * m2)
+                Some(checked(m1.checked_mul(*m2)))
             }
             Monkey::Division(l1, l2) => {
                 let m1 = values.get(l1)?;
                 let m2 = values.get(l2)?;
-                Some(m1 / m2)
+                Some(checked(m1.checked_div(*m2)))
             }
         }
     }
 
     fn unapply(&self, values: &HashMap<String, i64>, v: i64) -> (i64, String) {
         match self {
-            Monkey::Value(_) => {
-                panic!()
+            Monkey::Value(value) => {
+                panic!("a monkey yelling {} cannot depend on `humn`", value)
             }
             Monkey::Addition(l1, l2) => {
                 let v1 = values.get(l1);
                 let v2 = values.get(l2);
                 if let Some(v1) = v1 {
-                    (v - *v1, l2.to_string())
+                    (checked(v.checked_sub(*v1)), l2.to_string())
                 } else if let Some(v2) = v2 {
-                    (v - *v2, l1.to_string())
+                    (checked(v.checked_sub(*v2)), l1.to_string())
                 } else {
-                    panic!()
+                    both_unknown(l1, l2)
                 }
             }
             Monkey::Subtraction(l1, l2) => {
                 let v1 = values.get(l1);
                 let v2 = values.get(l2);
                 if let Some(v1) = v1 {
-                    (*v1 - v, l2.to_string())
+                    (checked(v1.checked_sub(v)), l2.to_string())
                 } else if let Some(v2) = v2 {
-                    (*v2 + v, l1.to_string())
+                    (checked(v2.checked_add(v)), l1.to_string())
                 } else {
-                    panic!()
+                    both_unknown(l1, l2)
                 }
             }
             Monkey::Multiplication(l1, l2) => {
                 let v1 = values.get(l1);
                 let v2 = values.get(l2);
                 if let Some(v1) = v1 {
-                    (v / *v1, l2.to_string())
+                    (checked(v.checked_div(*v1)), l2.to_string())
                 } else if let Some(v2) = v2 {
-                    (v / *v2, l1.to_string())
+                    (checked(v.checked_div(*v2)), l1.to_string())
                 } else {
-                    panic!()
+                    both_unknown(l1, l2)
                 }
             }
             Monkey::Division(l1, l2) => {
                 let v1 = values.get(l1);
                 let v2 = values.get(l2);
                 if let Some(v1) = v1 {
-                    (*v1 / v, l2.to_string())
+                    (checked(v1.checked_div(v)), l2.to_string())
                 } else if let Some(v2) = v2 {
-                    (*v2 * v, l1.to_string())
+                    (checked(v2.checked_mul(v)), l1.to_string())
                 } else {
-                    panic!()
+                    both_unknown(l1, l2)
                 }
             }
         }
     }
 }
 
-struct ParseMonkeyResult {
+#[derive(Clone)]
+pub struct ParseMonkeyResult {
     monkeys: Vec<Monkey>,
     monkey_values: HashMap<String, i64>,
     monkey_positions: HashMap<String, usize>,
     unsolved_monkeys: Vec<String>,
 }
 
-fn parse_monkeys(input: &str) -> ParseMonkeyResult {
+pub fn parse(input: &str) -> Result<ParseMonkeyResult, ParseError> {
     let mut monkeys: Vec<Monkey> = Vec::new();
     let mut monkey_values: HashMap<String, i64> = HashMap::new();
     let mut monkey_positions: HashMap<String, usize> = HashMap::new();
     let mut unsolved_monkeys: Vec<String> = Vec::new();
+    let mut references: Vec<&str> = Vec::new();
+
+    let error =
+        |token: &str, expected: &str| ParseError::at(input.as_bytes(), token.as_bytes(), expected);
 
     for line in input.trim_end().lines() {
-        let words = line.split(' ').collect::<Vec<&str>>();
-        let label = &words[0][0..4];
+        let Some((label, job)) = line.split_once(": ") else {
+            return Err(error(
+                line,
+                "`<name>: <number>` or `<name>: <name> <op> <name>`",
+            ));
+        };
+        if label.is_empty() || !label.bytes().all(|byte| byte.is_ascii_lowercase()) {
+            return Err(error(label, "a monkey name of lowercase letters"));
+        }
         monkey_positions.insert(label.to_string(), monkeys.len());
-        if words.len() == 2 {
-            let value = words[1].parse::<i64>().unwrap();
+        let words = job.split(' ').collect::<Vec<&str>>();
+        if let [value] = words[..] {
+            if label == "root" {
+                // Part 2 compares the numbers of the two monkeys that `root` waits for.
+                return Err(error(value, "`<name> <op> <name>` for `root`"));
+            }
+            let value = integer_at::<i64>(input.as_bytes(), value.as_bytes())?;
             monkeys.push(Monkey::Value(value));
             monkey_values.insert(label.to_string(), value);
-        } else {
-            let label_1 = String::from(words[1]);
-            let label_2 = String::from(words[3]);
-            let monkey = match words[2] {
+        } else if let [label_1, operation, label_2] = words[..] {
+            references.extend([label_1, label_2]);
+            let (label_1, label_2) = (String::from(label_1), String::from(label_2));
+            let monkey = match operation {
                 "+" => Monkey::Addition(label_1, label_2),
                 "-" => Monkey::Subtraction(label_1, label_2),
                 "*" => Monkey::Multiplication(label_1, label_2),
                 "/" => Monkey::Division(label_1, label_2),
-                _ => panic!(),
+                _ => return Err(error(operation, "`+`, `-`, `*` or `/`")),
             };
             monkeys.push(monkey);
             unsolved_monkeys.push(label.to_string());
+        } else {
+            return Err(error(job, "a number or `<name> <op> <name>`"));
         };
     }
 
-    ParseMonkeyResult {
+    if let Some(unknown) = references
+        .iter()
+        .find(|label| !monkey_positions.contains_key(**label))
+    {
+        return Err(error(unknown, "the name of a listed monkey"));
+    }
+    for label in ["root", "humn"] {
+        if !monkey_positions.contains_key(label) {
+            let end = &input[input.len()..];
+            return Err(error(end, &format!("a monkey named `{}`", label)));
+        }
+    }
+
+    Ok(ParseMonkeyResult {
         monkeys,
         monkey_values,
         monkey_positions,
         unsolved_monkeys,
+    })
+}
+
+/// Computes the numbers of all `unsolved_monkeys` whose operands are known or can be computed.
+/// A monkey is tried again only when one of its operands becomes known, so that long chains of
+/// monkeys take a single pass.
+fn solve(
+    monkeys: &[Monkey],
+    monkey_positions: &HashMap<String, usize>,
+    monkey_values: &mut HashMap<String, i64>,
+    unsolved_monkeys: &[String],
+) {
+    let mut dependents: HashMap<&str, Vec<&str>> = HashMap::new();
+    for monkey in unsolved_monkeys {
+        if let Some((l1, l2)) = monkeys[monkey_positions[monkey]].operands() {
+            dependents.entry(l1).or_default().push(monkey);
+            dependents.entry(l2).or_default().push(monkey);
+        }
+    }
+
+    let mut stack: Vec<&str> = unsolved_monkeys.iter().map(String::as_str).collect();
+    while let Some(monkey) = stack.pop() {
+        if monkey_values.contains_key(monkey) {
+            continue;
+        }
+        if let Some(value) = monkeys[monkey_positions[monkey]].compute(monkey_values) {
+            monkey_values.insert(monkey.to_string(), value);
+            stack.extend(dependents.get(monkey).into_iter().flatten());
+        }
     }
 }
 
-pub(crate) fn part1(input: &str) -> i64 {
+pub fn part1(parsed: &ParseMonkeyResult) -> i64 {
     let ParseMonkeyResult {
         monkeys,
         mut monkey_values,
         monkey_positions,
-        mut unsolved_monkeys,
-    } = parse_monkeys(input);
-
-    while !monkey_values.contains_key("root") {
-        let mut new_unsolved_monkeys = Vec::new();
-
-        for monkey in unsolved_monkeys {
-            if let Some(value) =
-                &monkeys[*monkey_positions.get(&monkey).unwrap()].compute(&monkey_values)
-            {
-                monkey_values.insert(monkey, *value);
-            } else {
-                new_unsolved_monkeys.push(monkey);
-            }
-        }
+        unsolved_monkeys,
+    } = parsed.clone();
 
-        unsolved_monkeys = new_unsolved_monkeys;
-    }
+    solve(
+        &monkeys,
+        &monkey_positions,
+        &mut monkey_values,
+        &unsolved_monkeys,
+    );
 
-    *monkey_values.get("root").unwrap()
+    *monkey_values
+        .get("root")
+        .expect("the monkeys depend on each other in a cycle")
 }
 
-pub(crate) fn part2(input: &str) -> i64 {
+pub fn part2(parsed: &ParseMonkeyResult) -> i64 {
     let ParseMonkeyResult {
         monkeys,
         mut monkey_values,
         monkey_positions,
-        mut unsolved_monkeys,
-    } = parse_monkeys(input);
+        unsolved_monkeys,
+    } = parsed.clone();
 
     monkey_values.remove("humn");
 
     let root_monkey = &monkeys[*monkey_positions.get("root").unwrap()];
-    let (r1, r2) = match root_monkey {
-        Monkey::Addition(l1, l2) => (l1, l2),
-        Monkey::Subtraction(l1, l2) => (l1, l2),
-        Monkey::Multiplication(l1, l2) => (l1, l2),
-        Monkey::Division(l1, l2) => (l1, l2),
-        Monkey::Value(_) => panic!(),
+    let Some((r1, r2)) = root_monkey.operands() else {
+        unreachable!("the parser rejects a `root` that yells a number");
     };
 
-    loop {
-        let mut new_unsolved_monkeys = Vec::new();
-
-        for monkey in unsolved_monkeys.iter() {
-            if let Some(value) =
-                &monkeys[*monkey_positions.get(monkey).unwrap()].compute(&monkey_values)
-            {
-                monkey_values.insert(monkey.clone(), *value);
-            } else {
-                new_unsolved_monkeys.push(monkey.clone());
-            }
-        }
-
-        if unsolved_monkeys.len() == new_unsolved_monkeys.len() {
-            break;
-        }
-
-        unsolved_monkeys = new_unsolved_monkeys;
-    }
+    solve(
+        &monkeys,
+        &monkey_positions,
+        &mut monkey_values,
+        &unsolved_monkeys,
+    );
 
     let (test_value, mut current_monkey) = if let Some(v) = monkey_values.get(r1) {
         (v, r2.clone())
     } else if let Some(v) = monkey_values.get(r2) {
         (v, r1.clone())
     } else {
-        panic!()
+        both_unknown(r1, r2)
     };
 
     let mut result = *test_value;
@@ -225,30 +280,117 @@ pub(crate) fn part2(input: &str) -> i64 {
     result
 }
 
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Parsed = ParseMonkeyResult;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(&String::from_utf8_lossy(input))
+    }
+
+    fn part1(parsed: &Self::Parsed, _: &()) -> Answer {
+        part1(parsed).into()
+    }
+
+    fn part2(parsed: &Self::Parsed, _: &()) -> Answer {
+        part2(parsed).into()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &str = include_str!("../inputs/day21.txt");
+    const EXAMPLE: &str = include_str!("../examples/day21.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 49288254556480)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 49288254556480)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 3558714869436)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 3558714869436)
+    }
+
+    #[test]
+    fn test_example() {
+        let monkeys = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&monkeys), 152);
+        assert_eq!(part2(&monkeys), 301);
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let error = parse("root: humn % abcd\nhumn: 5\nabcd: 3\n")
+            .err()
+            .unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (1, 12, "%")
+        );
+        let error = parse("root: humn + abcd\nhumn: 5\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (1, 14, "abcd")
+        );
+        let error = parse("root: 5\nhumn: 5\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (1, 7, "5"));
+    }
+
+    /// A monkey name for `index`, from `aaaa` on.
+    fn name(index: usize) -> String {
+        (0..4)
+            .rev()
+            .map(|digit| (b'a' + (index / 26_usize.pow(digit) % 26) as u8) as char)
+            .collect()
+    }
+
+    #[test]
+    fn test_large_input() {
+        // A chain of 10000 additions of numbers beyond `i32::MAX` from `humn` to `root`.
+        let mut input = String::from("root: aaaa + zzzz\nzzzz: 20000000000005\nhumn: 5\n");
+        input.push_str("zzzy: 1000000000\n");
+        for index in 0..10_000 {
+            let previous = if index == 9999 {
+                "humn".to_string()
+            } else {
+                name(index + 1)
+            };
+            input.push_str(&format!("{}: {} + zzzy\n", name(index), previous));
+        }
+        let parsed = parse(&input).unwrap();
+        assert_eq!(part1(&parsed), 30_000_000_000_010);
+        assert_eq!(part2(&parsed), 10_000_000_000_005);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[should_panic(expected = "beyond 64 bits")]
+    fn test_overflow() {
+        part1(&parse("root: aaaa * aaaa\naaaa: 9999999999\nhumn: 1\n").unwrap());
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day21::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let monkeys = parse(INPUT).unwrap();
+        bench("day21::part1", || part1(&monkeys))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let monkeys = parse(INPUT).unwrap();
+        bench("day21::part2", || part2(&monkeys))
     }
 }
@@ -1,120 +1,11 @@
-extern crate test;
+use crate::error::ParseError;
+use crate::grid::Grid2D;
+use crate::parse::integer;
+use crate::solution::{Answer, Solution};
+use std::collections::VecDeque;
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day22.txt");
-
-const CUBE_SIZE: usize = 50;
 const NUM_FACES: usize = 6;
 
-const FACES_2D: [Face; NUM_FACES] = [
-    Face {
-        id: 0,
-        position: (0, CUBE_SIZE),
-        next_up: (4, Direction::U, Orientation::Normal),
-        next_down: (2, Direction::D, Orientation::Normal),
-        next_left: (1, Direction::L, Orientation::Normal),
-        next_right: (1, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 1,
-        position: (0, 2 * CUBE_SIZE),
-        next_up: (1, Direction::U, Orientation::Normal),
-        next_down: (1, Direction::D, Orientation::Normal),
-        next_left: (0, Direction::L, Orientation::Normal),
-        next_right: (0, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 2,
-        position: (CUBE_SIZE, CUBE_SIZE),
-        next_up: (0, Direction::U, Orientation::Normal),
-        next_down: (4, Direction::D, Orientation::Normal),
-        next_left: (2, Direction::L, Orientation::Normal),
-        next_right: (2, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 3,
-        position: (2 * CUBE_SIZE, 0),
-        next_up: (5, Direction::U, Orientation::Normal),
-        next_down: (5, Direction::D, Orientation::Normal),
-        next_left: (4, Direction::L, Orientation::Normal),
-        next_right: (4, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 4,
-        position: (2 * CUBE_SIZE, CUBE_SIZE),
-        next_up: (2, Direction::U, Orientation::Normal),
-        next_down: (0, Direction::D, Orientation::Normal),
-        next_left: (3, Direction::L, Orientation::Normal),
-        next_right: (3, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 5,
-        position: (3 * CUBE_SIZE, 0),
-        next_up: (3, Direction::U, Orientation::Normal),
-        next_down: (3, Direction::D, Orientation::Normal),
-        next_left: (5, Direction::L, Orientation::Normal),
-        next_right: (5, Direction::R, Orientation::Normal),
-    },
-];
-
-const FACES_3D: [Face; NUM_FACES] = [
-    Face {
-        id: 0,
-        position: (0, CUBE_SIZE),
-        next_up: (5, Direction::R, Orientation::Normal),
-        next_down: (2, Direction::D, Orientation::Normal),
-        next_left: (3, Direction::R, Orientation::Flipped),
-        next_right: (1, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 1,
-        position: (0, 2 * CUBE_SIZE),
-        next_up: (5, Direction::U, Orientation::Normal),
-        next_down: (2, Direction::L, Orientation::Normal),
-        next_left: (0, Direction::L, Orientation::Normal),
-        next_right: (4, Direction::L, Orientation::Flipped),
-    },
-    Face {
-        id: 2,
-        position: (CUBE_SIZE, CUBE_SIZE),
-        next_up: (0, Direction::U, Orientation::Normal),
-        next_down: (4, Direction::D, Orientation::Normal),
-        next_left: (3, Direction::D, Orientation::Normal),
-        next_right: (1, Direction::U, Orientation::Normal),
-    },
-    Face {
-        id: 3,
-        position: (2 * CUBE_SIZE, 0),
-        next_up: (2, Direction::R, Orientation::Normal),
-        next_down: (5, Direction::D, Orientation::Normal),
-        next_left: (0, Direction::R, Orientation::Flipped),
-        next_right: (4, Direction::R, Orientation::Normal),
-    },
-    Face {
-        id: 4,
-        position: (2 * CUBE_SIZE, CUBE_SIZE),
-        next_up: (2, Direction::U, Orientation::Normal),
-        next_down: (5, Direction::L, Orientation::Normal),
-        next_left: (3, Direction::L, Orientation::Normal),
-        next_right: (1, Direction::L, Orientation::Flipped),
-    },
-    Face {
-        id: 5,
-        position: (3 * CUBE_SIZE, 0),
-        next_up: (3, Direction::U, Orientation::Normal),
-        next_down: (1, Direction::D, Orientation::Normal),
-        next_left: (0, Direction::D, Orientation::Normal),
-        next_right: (4, Direction::U, Orientation::Normal),
-    },
-];
-
-const POWERS_OF_TEN: [usize; 2] = [1, 10];
-
-fn usize_from_bytes(bytes: &[u8]) -> usize {
-    bytes.iter().rev().enumerate().fold(0, |acc, (ix, c)| {
-        acc + (c - b'0') as usize * POWERS_OF_TEN[ix]
-    })
-}
-
 #[derive(Debug, Copy, Clone)]
 enum Instruction {
     Fwd(usize),
@@ -122,7 +13,7 @@ enum Instruction {
     R,
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Direction {
     U,
     L,
@@ -130,10 +21,12 @@ enum Direction {
     D,
 }
 
+const DIRECTIONS: [Direction; 4] = [Direction::U, Direction::L, Direction::R, Direction::D];
+
 impl Direction {
     fn turn(&self, instruction: &Instruction) -> Self {
         match instruction {
-            Instruction::Fwd(_) => panic!(),
+            Instruction::Fwd(steps) => unreachable!("moving {} steps is not a turn", steps),
             Instruction::L => match self {
                 Direction::U => Self::L,
                 Direction::L => Self::D,
@@ -149,6 +42,15 @@ impl Direction {
         }
     }
 
+    fn opposite(&self) -> Self {
+        match self {
+            Direction::U => Self::D,
+            Direction::L => Self::R,
+            Direction::R => Self::L,
+            Direction::D => Self::U,
+        }
+    }
+
     fn score(&self) -> usize {
         match self {
             Direction::U => 3,
@@ -159,7 +61,7 @@ impl Direction {
     }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
 enum Orientation {
     Normal,
     Flipped,
@@ -175,6 +77,180 @@ struct Face {
     next_right: (usize, Direction, Orientation),
 }
 
+impl Face {
+    fn new(
+        id: usize,
+        position: (usize, usize),
+        mut next: impl FnMut(Direction) -> (usize, Direction, Orientation),
+    ) -> Self {
+        Face {
+            id,
+            position,
+            next_up: next(Direction::U),
+            next_down: next(Direction::D),
+            next_left: next(Direction::L),
+            next_right: next(Direction::R),
+        }
+    }
+}
+
+type Vector = [i8; 3];
+
+fn neg(vector: Vector) -> Vector {
+    vector.map(|component| -component)
+}
+
+/// Where a face of the net ends up on the folded cube, as unit vectors pointing outwards and in
+/// the directions of increasing column and row.
+#[derive(Debug, Copy, Clone)]
+struct Placement {
+    normal: Vector,
+    right: Vector,
+    down: Vector,
+}
+
+impl Placement {
+    /// The direction in which `direction` leaves the face.
+    fn edge(&self, direction: Direction) -> Vector {
+        match direction {
+            Direction::U => neg(self.down),
+            Direction::L => neg(self.right),
+            Direction::R => self.right,
+            Direction::D => self.down,
+        }
+    }
+
+    /// The direction in which the position along the edge left by `direction` increases.
+    fn along(&self, direction: Direction) -> Vector {
+        match direction {
+            Direction::U | Direction::D => self.right,
+            Direction::L | Direction::R => self.down,
+        }
+    }
+
+    /// The placement of the neighbouring face of the net, folded over the edge in `direction`.
+    fn fold(&self, direction: Direction) -> Self {
+        let Placement {
+            normal,
+            right,
+            down,
+        } = *self;
+        match direction {
+            Direction::U => Placement {
+                normal: neg(down),
+                right,
+                down: normal,
+            },
+            Direction::L => Placement {
+                normal: neg(right),
+                right: normal,
+                down,
+            },
+            Direction::R => Placement {
+                normal: right,
+                right: neg(normal),
+                down,
+            },
+            Direction::D => Placement {
+                normal: down,
+                right,
+                down: neg(normal),
+            },
+        }
+    }
+}
+
+/// Connects the faces as they lie in the net, wrapping around to the opposite side of the map.
+fn faces_2d(blocks: &[(usize, usize)], num_block_rows: usize, num_block_cols: usize) -> Vec<Face> {
+    let (rows, cols) = (num_block_rows, num_block_cols);
+    let mut faces = Vec::with_capacity(blocks.len());
+
+    for (id, block) in blocks.iter().enumerate() {
+        let next = |direction: Direction| {
+            let mut next = *block;
+            loop {
+                next = match direction {
+                    Direction::U => ((next.0 + rows - 1) % rows, next.1),
+                    Direction::L => (next.0, (next.1 + cols - 1) % cols),
+                    Direction::R => (next.0, (next.1 + 1) % cols),
+                    Direction::D => ((next.0 + 1) % rows, next.1),
+                };
+                if let Some(next_id) = blocks.iter().position(|other| *other == next) {
+                    return (next_id, direction, Orientation::Normal);
+                }
+            }
+        };
+        faces.push(Face::new(id, *block, next));
+    }
+
+    faces
+}
+
+/// Connects the faces as they lie on the cube obtained by folding the net, or returns `None` if
+/// the net does not fold into a cube.
+fn faces_3d(blocks: &[(usize, usize)]) -> Option<Vec<Face>> {
+    let mut placements: Vec<Option<Placement>> = vec![None; blocks.len()];
+    placements[0] = Some(Placement {
+        normal: [0, 0, 1],
+        right: [1, 0, 0],
+        down: [0, 1, 0],
+    });
+
+    let mut queue = VecDeque::from([0]);
+    while let Some(id) = queue.pop_front() {
+        let (row, col) = blocks[id];
+        let placement = placements[id]?;
+        for direction in DIRECTIONS {
+            let neighbour = match direction {
+                Direction::U => (row.wrapping_sub(1), col),
+                Direction::L => (row, col.wrapping_sub(1)),
+                Direction::R => (row, col + 1),
+                Direction::D => (row + 1, col),
+            };
+            if let Some(next_id) = blocks.iter().position(|block| *block == neighbour) {
+                if placements[next_id].is_none() {
+                    placements[next_id] = Some(placement.fold(direction));
+                    queue.push_back(next_id);
+                }
+            }
+        }
+    }
+
+    let placements: Vec<Placement> = placements.into_iter().collect::<Option<_>>()?;
+    let mut faces = Vec::with_capacity(blocks.len());
+
+    for (id, placement) in placements.iter().enumerate() {
+        let mut valid = true;
+        let next = |direction: Direction| {
+            let edge = placement.edge(direction);
+            let next_id = placements.iter().position(|other| other.normal == edge);
+            let entry = next_id.and_then(|next_id| {
+                let next = &placements[next_id];
+                let entry = DIRECTIONS
+                    .into_iter()
+                    .find(|entry| next.edge(*entry) == placement.normal)?;
+                Some((next_id, next, entry))
+            });
+            let Some((next_id, next, entry)) = entry else {
+                valid = false;
+                return (id, direction, Orientation::Normal);
+            };
+            let orientation = if next.along(entry) == placement.along(direction) {
+                Orientation::Normal
+            } else {
+                Orientation::Flipped
+            };
+            (next_id, entry.opposite(), orientation)
+        };
+        faces.push(Face::new(id, blocks[id], next));
+        if !valid {
+            return None;
+        }
+    }
+
+    Some(faces)
+}
+
 #[derive(Debug, Copy, Clone)]
 enum Cell {
     Open,
@@ -187,18 +263,14 @@ enum AfterStep {
 }
 
 struct Grid {
-    cells: [Cell; NUM_FACES * CUBE_SIZE * CUBE_SIZE],
+    cube_size: usize,
+    /// The tiles of each face, indexed by `(column, row)` within the face.
+    tiles: Vec<Grid2D<Cell>>,
+    faces_2d: Vec<Face>,
+    faces_3d: Vec<Face>,
 }
 
 impl Grid {
-    fn set_cell_as_wall(&mut self, face_id: usize, row: usize, col: usize) {
-        self.cells[face_id * CUBE_SIZE * CUBE_SIZE + row * CUBE_SIZE + col] = Cell::Wall
-    }
-
-    fn get_cell(&self, face_id: usize, row: usize, col: usize) -> Cell {
-        self.cells[face_id * CUBE_SIZE * CUBE_SIZE + row * CUBE_SIZE + col]
-    }
-
     fn fwd(
         &self,
         faces: &[Face],
@@ -207,6 +279,7 @@ impl Grid {
         row: usize,
         col: usize,
     ) -> AfterStep {
+        let size = self.cube_size;
         let (next_face, next_dir, next_row, next_col) = match dir {
             Direction::U => {
                 if row > 0 {
@@ -215,11 +288,11 @@ impl Grid {
                     let next_face = &faces[face.next_up.0];
                     let next_dir = &face.next_up.1;
                     let col = if let Orientation::Flipped = face.next_up.2 {
-                        CUBE_SIZE - col - 1
+                        size - col - 1
                     } else {
                         col
                     };
-                    let (next_row, next_col) = Self::pos_on_next_face(next_dir, col);
+                    let (next_row, next_col) = self.pos_on_next_face(next_dir, col);
                     (next_face, next_dir, next_row, next_col)
                 }
             }
@@ -230,166 +303,215 @@ impl Grid {
                     let next_face = &faces[face.next_left.0];
                     let next_dir = &face.next_left.1;
                     let row = if let Orientation::Flipped = face.next_left.2 {
-                        CUBE_SIZE - row - 1
+                        size - row - 1
                     } else {
                         row
                     };
-                    let (next_row, next_col) = Self::pos_on_next_face(next_dir, row);
+                    let (next_row, next_col) = self.pos_on_next_face(next_dir, row);
                     (next_face, next_dir, next_row, next_col)
                 }
             }
             Direction::R => {
-                if col < CUBE_SIZE - 1 {
+                if col < size - 1 {
                     (face, dir, row, col + 1)
                 } else {
                     let next_face = &faces[face.next_right.0];
                     let next_dir = &face.next_right.1;
                     let row = if let Orientation::Flipped = face.next_right.2 {
-                        CUBE_SIZE - row - 1
+                        size - row - 1
                     } else {
                         row
                     };
-                    let (next_row, next_col) = Self::pos_on_next_face(next_dir, row);
+                    let (next_row, next_col) = self.pos_on_next_face(next_dir, row);
                     (next_face, next_dir, next_row, next_col)
                 }
             }
             Direction::D => {
-                if row < CUBE_SIZE - 1 {
+                if row < size - 1 {
                     (face, dir, row + 1, col)
                 } else {
                     let next_face = &faces[face.next_down.0];
                     let next_dir = &face.next_down.1;
                     let col = if let Orientation::Flipped = face.next_down.2 {
-                        CUBE_SIZE - col - 1
+                        size - col - 1
                     } else {
                         col
                     };
-                    let (next_row, next_col) = Self::pos_on_next_face(next_dir, col);
+                    let (next_row, next_col) = self.pos_on_next_face(next_dir, col);
                     (next_face, next_dir, next_row, next_col)
                 }
             }
         };
-        match self.get_cell(next_face.id, next_row, next_col) {
+        match self.tiles[next_face.id][(next_col, next_row)] {
             Cell::Open => AfterStep::Proceed(next_face.id, *next_dir, next_row, next_col),
             Cell::Wall => AfterStep::HitWall,
         }
     }
 
-    fn pos_on_next_face(dir: &Direction, pos_on_border: usize) -> (usize, usize) {
+    fn pos_on_next_face(&self, dir: &Direction, pos_on_border: usize) -> (usize, usize) {
+        let size = self.cube_size;
         match dir {
-            Direction::U => (CUBE_SIZE - 1, pos_on_border),
-            Direction::L => (pos_on_border, CUBE_SIZE - 1),
+            Direction::U => (size - 1, pos_on_border),
+            Direction::L => (pos_on_border, size - 1),
             Direction::R => (pos_on_border, 0),
             Direction::D => (0, pos_on_border),
         }
     }
 
-    fn parse(input: &[u8]) -> Self {
-        let mut grid = Grid {
-            cells: [Cell::Open; NUM_FACES * CUBE_SIZE * CUBE_SIZE],
-        };
-
-        let mut lines_it = input.split(|byte| *byte == b'\n');
-
-        for (row, line) in lines_it.by_ref().enumerate().take(CUBE_SIZE) {
-            let mut line_it = line.iter();
-            for (column, cell) in line_it.by_ref().skip(CUBE_SIZE).enumerate().take(CUBE_SIZE) {
-                if *cell == b'#' {
-                    grid.set_cell_as_wall(0, row, column);
-                }
-            }
-            for (column, cell) in line_it.by_ref().enumerate().take(CUBE_SIZE) {
-                if *cell == b'#' {
-                    grid.set_cell_as_wall(1, row, column);
+    fn parse(input: &[u8]) -> Result<Self, ParseError> {
+        let lines: Vec<&[u8]> = input
+            .split(|byte| *byte == b'\n')
+            .take_while(|line| !line.is_empty())
+            .collect();
+        let first = lines.first().copied().unwrap_or(&input[..0]);
+        let last = lines.last().copied().unwrap_or(&input[..0]);
+
+        let mut num_tiles = 0;
+        for line in &lines {
+            for (index, byte) in line.iter().enumerate() {
+                match byte {
+                    b'.' | b'#' => num_tiles += 1,
+                    b' ' => {}
+                    _ => {
+                        let tile = &line[index..=index];
+                        return Err(ParseError::at(input, tile, "`.` or `#`"));
+                    }
                 }
             }
         }
 
-        for (row, line) in lines_it.by_ref().enumerate().take(CUBE_SIZE) {
-            let mut line_it = line.iter();
-            for (column, cell) in line_it.by_ref().skip(CUBE_SIZE).enumerate().take(CUBE_SIZE) {
-                if *cell == b'#' {
-                    grid.set_cell_as_wall(2, row, column);
+        let cube_size = (1..)
+            .take_while(|size| NUM_FACES * size * size <= num_tiles)
+            .last()
+            .filter(|size| NUM_FACES * size * size == num_tiles);
+        let Some(cube_size) = cube_size else {
+            return Err(ParseError::at(input, first, "a map of six square faces"));
+        };
+
+        let num_block_rows = lines.len().div_ceil(cube_size);
+        let num_block_cols = lines.iter().map(|line| line.len()).max().unwrap_or(0);
+        let num_block_cols = num_block_cols.div_ceil(cube_size);
+
+        let mut blocks = Vec::new();
+        for block_row in 0..num_block_rows {
+            for block_col in 0..num_block_cols {
+                let line = lines[block_row * cube_size];
+                if matches!(line.get(block_col * cube_size), Some(b'.' | b'#')) {
+                    blocks.push((block_row, block_col));
                 }
             }
         }
+        if blocks.len() != NUM_FACES {
+            return Err(ParseError::at(input, first, "a map of six square faces"));
+        }
 
-        for (row, line) in lines_it.by_ref().enumerate().take(CUBE_SIZE) {
-            let mut line_it = line.iter();
-            for (column, cell) in line_it.by_ref().enumerate().take(CUBE_SIZE) {
-                if *cell == b'#' {
-                    grid.set_cell_as_wall(3, row, column);
-                }
-            }
-            for (column, cell) in line_it.by_ref().enumerate().take(CUBE_SIZE) {
-                if *cell == b'#' {
-                    grid.set_cell_as_wall(4, row, column);
+        let mut grid = Grid {
+            cube_size,
+            tiles: vec![Grid2D::new(cube_size, cube_size, Cell::Open); NUM_FACES],
+            faces_2d: Vec::new(),
+            faces_3d: Vec::new(),
+        };
+
+        for (id, (block_row, block_col)) in blocks.iter().enumerate() {
+            let (first_row, first_column) = (block_row * cube_size, block_col * cube_size);
+            for row in 0..cube_size {
+                let Some(line) = lines.get(first_row + row) else {
+                    let expected = format!("a map of {} rows", first_row + cube_size);
+                    return Err(ParseError::end_of(input, last, expected));
+                };
+                for column in 0..cube_size {
+                    let index = first_column + column;
+                    match line.get(index) {
+                        Some(b'.') => {}
+                        Some(b'#') => grid.tiles[id][(column, row)] = Cell::Wall,
+                        _ => {
+                            let expected = format!("a row of {} tiles", first_column + cube_size);
+                            let line = &line[..index.min(line.len())];
+                            return Err(ParseError::end_of(input, line, expected));
+                        }
+                    }
                 }
             }
         }
 
-        for (row, line) in lines_it.by_ref().enumerate().take(CUBE_SIZE) {
-            let mut line_it = line.iter();
-            for (column, cell) in line_it.by_ref().enumerate().take(CUBE_SIZE) {
-                if *cell == b'#' {
-                    grid.set_cell_as_wall(5, row, column);
-                }
-            }
+        grid.faces_2d = faces_2d(&blocks, num_block_rows, num_block_cols);
+        grid.faces_3d = faces_3d(&blocks)
+            .ok_or_else(|| ParseError::at(input, first, "a map that folds into a cube"))?;
+        for face in grid.faces_2d.iter_mut().chain(grid.faces_3d.iter_mut()) {
+            face.position = (face.position.0 * cube_size, face.position.1 * cube_size);
         }
 
-        grid
+        Ok(grid)
     }
 }
 
-fn parse_instructions(input: &[u8]) -> Vec<Instruction> {
-    let bytes = input
+fn parse_instructions(input: &[u8]) -> Result<Vec<Instruction>, ParseError> {
+    let line = input
         .trim_ascii_end()
         .rsplit(|byte| *byte == b'\n')
         .next()
-        .unwrap()
-        .iter();
-
-    let mut buf: Vec<u8> = Vec::with_capacity(2);
+        .unwrap();
+
+    let steps = |bytes: &[u8]| {
+        integer::<usize>(bytes)
+            .map(Instruction::Fwd)
+            .ok_or_else(|| ParseError::at(input, bytes, "a number of steps"))
+    };
+    let mut start = 0;
     let mut instructions = Vec::new();
 
-    for byte in bytes {
+    for (index, byte) in line.iter().enumerate() {
         match byte {
-            b'L' => {
-                if !buf.is_empty() {
-                    instructions.push(Instruction::Fwd(usize_from_bytes(&buf)));
-                    buf.clear()
+            b'L' | b'R' => {
+                if start < index {
+                    instructions.push(steps(&line[start..index])?);
                 }
-                instructions.push(Instruction::L)
+                instructions.push(if *byte == b'L' {
+                    Instruction::L
+                } else {
+                    Instruction::R
+                });
+                start = index + 1;
             }
-            b'R' => {
-                if !buf.is_empty() {
-                    instructions.push(Instruction::Fwd(usize_from_bytes(&buf)));
-                    buf.clear()
-                }
-                instructions.push(Instruction::R)
+            b'0'..=b'9' => {}
+            _ => {
+                let instruction = &line[index..=index];
+                return Err(ParseError::at(input, instruction, "a number, `L` or `R`"));
             }
-            _ => buf.push(*byte),
         }
     }
 
-    if !buf.is_empty() {
-        instructions.push(Instruction::Fwd(usize_from_bytes(&buf)));
+    if start < line.len() {
+        instructions.push(steps(&line[start..])?);
     }
 
-    instructions
+    Ok(instructions)
 }
 
-fn solution(input: &[u8], faces: &[Face]) -> usize {
-    let instructions = parse_instructions(input);
-    let grid = Grid::parse(input);
+pub struct Notes {
+    grid: Grid,
+    instructions: Vec<Instruction>,
+}
+
+pub fn parse(input: &[u8]) -> Result<Notes, ParseError> {
+    Ok(Notes {
+        grid: Grid::parse(input)?,
+        instructions: parse_instructions(input)?,
+    })
+}
+
+fn solution(notes: &Notes, faces: &[Face]) -> usize {
+    let Notes { grid, instructions } = notes;
 
     let (face, dir, row, col) = instructions.iter().fold(
         (&faces[0], Direction::R, 0_usize, 0_usize),
         |(face, dir, row, col), instruction| {
             if let Instruction::Fwd(v) = instruction {
+                let start = (face.id, dir, row, col);
                 let (mut face, mut dir, mut row, mut col) = (face, dir, row, col);
 
-                for _ in 0..*v {
+                let (mut steps, mut taken) = (*v, 0);
+                while taken < steps {
                     if let AfterStep::Proceed(next_face_id, next_direction, next_row, next_col) =
                         grid.fwd(faces, &dir, face, row, col)
                     {
@@ -397,6 +519,11 @@ fn solution(input: &[u8], faces: &[Face]) -> usize {
                         dir = next_direction;
                         row = next_row;
                         col = next_col;
+                        taken += 1;
+                        // Back at the start after going around, the remaining rounds change nothing.
+                        if (face.id, dir, row, col) == start {
+                            steps = taken + (steps - taken) % taken;
+                        }
                     } else {
                         break;
                     }
@@ -412,48 +539,117 @@ fn solution(input: &[u8], faces: &[Face]) -> usize {
     (face.position.0 + row + 1) * 1000 + 4 * (face.position.1 + col + 1) + dir.score()
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    solution(input, &FACES_2D)
+pub fn part1(notes: &Notes) -> usize {
+    solution(notes, &notes.grid.faces_2d)
+}
+
+pub fn part2(notes: &Notes) -> usize {
+    solution(notes, &notes.grid.faces_3d)
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    solution(input, &FACES_3D)
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Parsed = Notes;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(notes: &Self::Parsed, _: &()) -> Answer {
+        part1(notes).into()
+    }
+
+    fn part2(notes: &Self::Parsed, _: &()) -> Answer {
+        part2(notes).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day22.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day22.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 67390)
+        assert_eq!(part1(&parse(INPUT).unwrap()), 67390)
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 95291)
+        assert_eq!(part2(&parse(INPUT).unwrap()), 95291)
     }
 
-    #[bench]
-    fn bench_parse_grid(b: &mut Bencher) {
-        b.iter(|| Grid::parse(INPUT))
+    #[test]
+    fn test_example() {
+        let notes = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&notes), 6032);
+        assert_eq!(part2(&notes), 5031);
     }
 
-    #[bench]
-    fn bench_parse_instructions(b: &mut Bencher) {
-        b.iter(|| parse_instructions(INPUT))
+    #[test]
+    fn test_parse_error() {
+        let mut input = INPUT.to_vec();
+        input[53] = b'x';
+        let error = parse(&input).err().unwrap();
+        assert_eq!(
+            (error.line, error.column, error.text.as_str()),
+            (1, 54, "x")
+        );
+
+        let error = parse_instructions(b"\n10R5X3").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 5, "X"));
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    fn test_large_input() {
+        // The map of the example without walls and with faces of 500 x 500 tiles, 100 times as
+        // many as in the puzzle, and far more steps than it takes to go around the map or cube.
+        let size = 500;
+        let mut input = String::new();
+        for (blocks, indent) in [(1, 2), (3, 0), (2, 2)] {
+            let line = format!(
+                "{}{}\n",
+                " ".repeat(indent * size),
+                ".".repeat(blocks * size)
+            );
+            input.push_str(&line.repeat(size));
+        }
+        // A multiple of the 500, 1500 and 2000 steps around the first row, its column and the cube.
+        let rounds: usize = 999_999_996_000;
+        input.push_str(&format!("\n{}R{}\n", rounds + 7, rounds + 1003));
+        let notes = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&notes), 1004 * 1000 + 4 * 1008 + 1);
+        assert_eq!(part2(&notes), 1004 * 1000 + 4 * 1008 + 1);
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse_grid() {
+        bench("day22::parse_grid", || Grid::parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse_instructions() {
+        bench("day22::parse_instructions", || parse_instructions(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let notes = parse(INPUT).unwrap();
+        bench("day22::part1", || part1(&notes))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let notes = parse(INPUT).unwrap();
+        bench("day22::part2", || part2(&notes))
     }
 }
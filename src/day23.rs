@@ -1,177 +1,264 @@
-use std::collections::{HashMap, HashSet};
+use crate::error::ParseError;
+use crate::grid::Grid2D;
+use crate::point::Direction;
+use crate::solution::{Answer, Solution};
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day23.txt");
-// const INPUT: &[u8] = include_bytes!("input_test.txt");
+/// The directions in the order they are considered in the first round.
+const DIRECTIONS: [Direction; 4] = [
+    Direction::Up,
+    Direction::Down,
+    Direction::Left,
+    Direction::Right,
+];
 
-const DIRS: [Dir; 4] = [Dir::U, Dir::D, Dir::L, Dir::R];
+/// The number of free rows and columns added on each side when an elf reaches the edge.
+const MARGIN: usize = 16;
 
-#[derive(Debug, Copy, Clone)]
-enum Dir {
-    U,
-    D,
-    L,
-    R,
+/// The elves on a grid with free space around them, which grows as they spread out.
+struct Elves {
+    occupied: Grid2D<bool>,
+    elves: Vec<(usize, usize)>,
+    /// The number of elves proposing to move to each tile, zero between rounds.
+    proposals: Grid2D<u8>,
 }
 
-impl Dir {
-    fn next_position(&self, position: &(isize, isize)) -> (isize, isize) {
-        match self {
-            Dir::U => (position.0 - 1, position.1),
-            Dir::D => (position.0 + 1, position.1),
-            Dir::L => (position.0, position.1 - 1),
-            Dir::R => (position.0, position.1 + 1),
-        }
+impl Elves {
+    fn new(map: &Grid2D<bool>) -> Self {
+        let mut elves = Elves {
+            occupied: map.clone(),
+            elves: map.positions().filter(|position| map[*position]).collect(),
+            proposals: Grid2D::new(0, 0, 0),
+        };
+        elves.grow();
+        elves
     }
 
-    fn test(&self, position: &(isize, isize), positions: &HashSet<(isize, isize)>) -> bool {
-        let (row, col) = *position;
-        match self {
-            Dir::U => {
-                !positions.contains(&(row - 1, col))
-                    && !positions.contains(&(row - 1, col - 1))
-                    && !positions.contains(&(row - 1, col + 1))
-            }
-            Dir::D => {
-                !positions.contains(&(row + 1, col))
-                    && !positions.contains(&(row + 1, col - 1))
-                    && !positions.contains(&(row + 1, col + 1))
-            }
-            Dir::L => {
-                !positions.contains(&(row, col - 1))
-                    && !positions.contains(&(row - 1, col - 1))
-                    && !positions.contains(&(row + 1, col - 1))
-            }
-            Dir::R => {
-                !positions.contains(&(row, col + 1))
-                    && !positions.contains(&(row - 1, col + 1))
-                    && !positions.contains(&(row + 1, col + 1))
-            }
+    /// Adds free space around the grid, moving the elves along.
+    fn grow(&mut self) {
+        let (width, height) = (
+            self.occupied.width() + 2 * MARGIN,
+            self.occupied.height() + 2 * MARGIN,
+        );
+        self.occupied = Grid2D::new(width, height, false);
+        for elf in self.elves.iter_mut() {
+            *elf = (elf.0 + MARGIN, elf.1 + MARGIN);
+            self.occupied[*elf] = true;
         }
+        self.proposals = Grid2D::new(width, height, 0);
     }
-}
 
-fn get_bounds<'a>(
-    positions: impl Iterator<Item = &'a (isize, isize)>,
-) -> (isize, isize, isize, isize) {
-    positions.fold(
-        (isize::MAX, isize::MIN, isize::MAX, isize::MIN),
-        |(min_row, max_row, min_col, max_col), (row, col)| {
-            (
-                min_row.min(*row),
-                max_row.max(*row),
-                min_col.min(*col),
-                max_col.max(*col),
-            )
-        },
-    )
-}
+    fn step(&self, (x, y): (usize, usize), direction: Direction) -> (usize, usize) {
+        let offset = direction.offset::<isize>();
+        (
+            x.wrapping_add_signed(offset.x),
+            y.wrapping_add_signed(offset.y),
+        )
+    }
 
-fn evolve(positions: &HashSet<(isize, isize)>, step: usize) -> (HashSet<(isize, isize)>, bool) {
-    let mut target_map: HashMap<(isize, isize), (isize, isize)> = HashMap::new();
-    let mut target_counts: HashMap<(isize, isize), usize> = HashMap::new();
+    /// Whether the three tiles towards `direction`, including the diagonal ones, are all free.
+    fn is_free(&self, elf: (usize, usize), direction: Direction) -> bool {
+        let side = self.step(elf, direction);
+        [
+            side,
+            self.step(side, direction.turn_left()),
+            self.step(side, direction.turn_right()),
+        ]
+        .iter()
+        .all(|tile| !self.occupied[*tile])
+    }
 
-    let dirs = [
-        DIRS[step % 4],
-        DIRS[(step + 1) % 4],
-        DIRS[(step + 2) % 4],
-        DIRS[(step + 3) % 4],
-    ];
+    /// Moves the elves for one round, and returns whether any of them moved.
+    fn round(&mut self, round: usize) -> bool {
+        let (width, height) = (self.occupied.width(), self.occupied.height());
+        if self
+            .elves
+            .iter()
+            .any(|(x, y)| *x == 0 || *y == 0 || *x == width - 1 || *y == height - 1)
+        {
+            self.grow();
+        }
 
-    for position in positions.iter() {
-        let free_dirs: Vec<Dir> = dirs
+        let targets: Vec<Option<(usize, usize)>> = self
+            .elves
             .iter()
-            .cloned()
-            .filter(|dir| dir.test(position, positions))
+            .map(|elf| {
+                let free = DIRECTIONS.map(|direction| self.is_free(*elf, direction));
+                if free.iter().all(|free| *free) {
+                    return None;
+                }
+                (0..4)
+                    .map(|index| (round + index) % 4)
+                    .find(|index| free[*index])
+                    .map(|index| self.step(*elf, DIRECTIONS[index]))
+            })
             .collect();
-
-        if free_dirs.len() == 4 {
-            continue;
+        for target in targets.iter().flatten() {
+            self.proposals[*target] = self.proposals[*target].saturating_add(1);
         }
 
-        if let Some(dir) = free_dirs.get(0) {
-            let next_position = dir.next_position(position);
-            target_map.insert(*position, next_position);
-            if let Some(count) = target_counts.get_mut(&next_position) {
-                *count += 1;
-            } else {
-                target_counts.insert(next_position, 1);
+        let mut moved = false;
+        for (elf, target) in self.elves.iter_mut().zip(&targets) {
+            if let Some(target) = target {
+                if self.proposals[*target] == 1 {
+                    self.occupied[*elf] = false;
+                    self.occupied[*target] = true;
+                    *elf = *target;
+                    moved = true;
+                }
             }
         }
+        for target in targets.iter().flatten() {
+            self.proposals[*target] = 0;
+        }
+        moved
     }
 
-    let mut next_positions = HashSet::new();
+    /// The number of free tiles in the smallest rectangle containing all elves.
+    fn empty_tiles(&self) -> usize {
+        let xs = self.elves.iter().map(|(x, _)| *x);
+        let ys = self.elves.iter().map(|(_, y)| *y);
+        let (Some(min_x), Some(max_x)) = (xs.clone().min(), xs.max()) else {
+            return 0;
+        };
+        let (min_y, max_y) = (ys.clone().min().unwrap(), ys.max().unwrap());
+        (max_x - min_x + 1) * (max_y - min_y + 1) - self.elves.len()
+    }
+}
 
-    let mut finished = true;
+/// Parses the map into a grid that is `true` where an elf stands.
+pub fn parse(input: &[u8]) -> Result<Grid2D<bool>, ParseError> {
+    Grid2D::parse(input, "tiles", "`.` or `#`", |byte| match byte {
+        b'#' => Some(true),
+        b'.' => Some(false),
+        _ => None,
+    })
+}
 
-    for position in positions.iter() {
-        if let Some(target) = target_map.get(position) {
-            match target_counts.get(target) {
-                None => {
-                    panic!();
-                }
-                Some(0) => {
-                    panic!();
-                }
-                Some(1) => {
-                    next_positions.insert(*target);
-                    finished = false;
-                }
-                _ => {
-                    next_positions.insert(*position);
-                }
-            }
-        } else {
-            next_positions.insert(*position);
+pub fn part1(map: &Grid2D<bool>) -> usize {
+    let mut elves = Elves::new(map);
+    for round in 0..10 {
+        if !elves.round(round) {
+            break;
         }
     }
-
-    (next_positions, finished)
+    elves.empty_tiles()
 }
 
-fn parse_positions(input: &[u8]) -> HashSet<(isize, isize)> {
-    input
-        .trim_ascii_end()
-        .split(|byte| *byte == b'\n')
-        .enumerate()
-        .flat_map(|(row, line)| {
-            line.iter()
-                .enumerate()
-                .map(|(col, byte)| ((row as isize, col as isize), *byte))
-                .collect::<Vec<((isize, isize), u8)>>()
-        })
-        .filter(|(_, byte)| *byte == b'#')
-        .map(|(a, _)| a)
-        .collect()
+pub fn part2(map: &Grid2D<bool>) -> usize {
+    let mut elves = Elves::new(map);
+    let mut round = 0;
+    while elves.round(round) {
+        round += 1;
+    }
+    round + 1
 }
 
-pub(crate) fn part1(input: &[u8]) -> isize {
-    let mut positions = parse_positions(input);
+pub struct Day23;
 
-    for i in 0..10 {
-        let (next_positions, finished) = evolve(&positions, i);
-        if finished {
-            break;
-        }
-        positions = next_positions
+impl Solution for Day23 {
+    type Parsed = Grid2D<bool>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
     }
 
-    let (row_min, row_max, col_min, col_max) = get_bounds(positions.iter());
+    fn part1(map: &Self::Parsed, _: &()) -> Answer {
+        part1(map).into()
+    }
 
-    (row_max - row_min + 1) * (col_max - col_min + 1) - positions.len() as isize
+    fn part2(map: &Self::Parsed, _: &()) -> Answer {
+        part2(map).into()
+    }
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let mut positions = parse_positions(input);
-    let mut round = 0;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::bench;
 
-    loop {
-        let (next_positions, finished) = evolve(&positions, round);
-        if finished {
-            break;
+    const INPUT: &[u8] = include_bytes!("../inputs/day23.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day23.txt");
+
+    #[test]
+    fn test_part1() {
+        assert_eq!(part1(&parse(INPUT).unwrap()), 4034)
+    }
+
+    #[test]
+    fn test_part2() {
+        assert_eq!(part2(&parse(INPUT).unwrap()), 960)
+    }
+
+    #[test]
+    fn test_example() {
+        let map = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&map), 110);
+        assert_eq!(part2(&map), 20);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"#.\n#x\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "x"));
+    }
+
+    #[test]
+    fn test_spreading() {
+        // A crowd that spreads beyond the free space around the map several times.
+        let input = "#".repeat(60) + "\n";
+        let reference = crate::reference::reference(23).unwrap();
+        let expected = reference.solve(&input, &crate::day(23).unwrap().default_params());
+        let map = parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            expected,
+            Some([Answer::from(part1(&map)), Answer::from(part2(&map))])
+        );
+    }
+
+    #[test]
+    fn test_large_input() {
+        // 14 x 14 copies of the example 50 tiles apart on a map 100 times the size of the puzzle,
+        // far enough apart that the elves of different copies never meet.
+        let example: Vec<&str> = std::str::from_utf8(EXAMPLE).unwrap().lines().collect();
+        let mut input = String::new();
+        for y in 0..700 {
+            for x in 0..700 {
+                let tile = example
+                    .get(y % 50)
+                    .and_then(|line| line.as_bytes().get(x % 50));
+                input.push(tile.map_or('.', |byte| *byte as char));
+            }
+            input.push('\n');
         }
-        positions = next_positions;
-        round += 1;
+
+        // After 10 rounds, the 22 elves of each copy are spread over 12 x 11 tiles.
+        let (width, height) = (12, 11);
+        let map = parse(input.as_bytes()).unwrap();
+        assert_eq!(
+            part1(&map),
+            (13 * 50 + width) * (13 * 50 + height) - 14 * 14 * 22
+        );
+        assert_eq!(part2(&map), 20);
     }
 
-    round + 1
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day23::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let map = parse(INPUT).unwrap();
+        bench("day23::part1", || part1(&map))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let map = parse(INPUT).unwrap();
+        bench("day23::part2", || part2(&map))
+    }
 }
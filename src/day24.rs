@@ -1,206 +1,427 @@
-extern crate test;
+use crate::error::ParseError;
+use crate::grid::Grid2D;
+use crate::solution::{Answer, Solution};
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day24.txt");
-const MAX_NUM_ROWS: usize = 32;
+const WORD_BITS: usize = u64::BITS as usize;
 
-const fn shift_bits_right_with_wrapping(bits: u128, num_bits: usize) -> u128 {
-    (bits >> 1) | (bits & 1_u128) << (num_bits - 1)
+/// Moves the lowest `num_bits` bits of `bits` one bit down, with the lowest bit wrapping around.
+fn rotate_right(bits: &mut [u64], num_bits: usize) {
+    let lowest = bits[0] & 1;
+    for index in 1..bits.len() {
+        bits[index - 1] = (bits[index - 1] >> 1) | (bits[index] << (WORD_BITS - 1));
+    }
+    bits[bits.len() - 1] >>= 1;
+    bits[(num_bits - 1) / WORD_BITS] |= lowest << ((num_bits - 1) % WORD_BITS);
 }
 
-const fn shift_bits_left_with_wrapping(bits: u128, num_bits: usize) -> u128 {
-    let mask = 1_u128 << (num_bits - 1);
-    let left_bits = bits & mask;
-    let remaining_bits = bits & !mask;
-    (remaining_bits << 1) | left_bits >> (num_bits - 1)
+/// Moves the lowest `num_bits` bits of `bits` one bit up, with the highest bit wrapping around.
+fn rotate_left(bits: &mut [u64], num_bits: usize) {
+    let (word, bit) = ((num_bits - 1) / WORD_BITS, (num_bits - 1) % WORD_BITS);
+    let highest = bits[word] >> bit & 1;
+    bits[word] &= !(1 << bit);
+    for index in (1..bits.len()).rev() {
+        bits[index] = (bits[index] << 1) | (bits[index - 1] >> (WORD_BITS - 1));
+    }
+    bits[0] = (bits[0] << 1) | highest;
 }
 
-struct Grid {
-    arrows_up: [u128; MAX_NUM_ROWS],
-    arrows_down: [u128; MAX_NUM_ROWS],
-    arrows_left: [u128; MAX_NUM_ROWS],
-    arrows_right: [u128; MAX_NUM_ROWS],
-    positions: [u128; MAX_NUM_ROWS],
+const fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// The blizzards and the tiles the expedition can be on as bits, `words` words per row of the
+/// valley with bit `col` of a row for its column `col`.
+#[derive(Clone)]
+pub struct Grid {
+    arrows_up: Vec<u64>,
+    arrows_down: Vec<u64>,
+    arrows_left: Vec<u64>,
+    arrows_right: Vec<u64>,
+    positions: Vec<u64>,
+    /// The positions of the next minute while they are computed.
+    next_positions: Vec<u64>,
     start_bit: bool,
     end_bit: bool,
     num_rows: usize,
     num_cols: usize,
+    words: usize,
 }
 
 impl Grid {
-    fn parse(input: &[u8]) -> Self {
-        let mut arrows_up = [0_u128; MAX_NUM_ROWS];
-        let mut arrows_down = [0_u128; MAX_NUM_ROWS];
-        let mut arrows_left = [0_u128; MAX_NUM_ROWS];
-        let mut arrows_right = [0_u128; MAX_NUM_ROWS];
-        let positions = [0_u128; MAX_NUM_ROWS];
-
-        let num_cols = input.iter().position(|byte| *byte == b'\n').unwrap() - 2;
-        let num_rows = input.trim_ascii_end().split(|byte| *byte == b'\n').count() - 2;
-
-        input
-            .trim_ascii_end()
-            .split(|byte| *byte == b'\n')
-            .skip(1)
-            .take(num_rows)
-            .enumerate()
-            .for_each(|(row, line)| {
-                {
-                    line.iter().skip(1).take(num_cols).enumerate().for_each(
-                        |(col, byte)| match byte {
-                            b'^' => arrows_up[row] |= 1_u128 << col,
-                            b'v' => arrows_down[row] |= 1_u128 << col,
-                            b'<' => arrows_left[row] |= 1_u128 << col,
-                            b'>' => arrows_right[row] |= 1_u128 << col,
-                            b'.' => {}
-                            _ => panic!(),
-                        },
-                    )
-                }
-            });
-
-        Self {
-            arrows_up,
-            arrows_down,
-            arrows_left,
-            arrows_right,
-            positions,
-            start_bit: false,
-            end_bit: false,
-            num_rows,
-            num_cols,
-        }
-    }
-
     fn step(&mut self) {
-        self.arrows_up[0..self.num_rows].rotate_left(1);
-        self.arrows_down[0..self.num_rows].rotate_right(1);
+        let words = self.words;
+        self.arrows_up.rotate_left(words);
+        self.arrows_down.rotate_right(words);
 
-        for col in self.arrows_left.iter_mut() {
-            *col = shift_bits_right_with_wrapping(*col, self.num_cols);
+        for row in self.arrows_left.chunks_mut(words) {
+            rotate_right(row, self.num_cols);
         }
-        for col in self.arrows_right.iter_mut() {
-            *col = shift_bits_left_with_wrapping(*col, self.num_cols);
+        for row in self.arrows_right.chunks_mut(words) {
+            rotate_left(row, self.num_cols);
         }
 
         let previous_start = self.start_bit;
         let previous_end = self.end_bit;
 
-        if self.positions[0] & 1_u128 != 0_u128 {
+        if self.positions[0] & 1 != 0 {
             self.start_bit = true
         }
 
-        if self.positions[self.num_rows - 1] & (1_u128 << (self.num_cols - 1)) != 0_u128 {
+        let (end_word, end_bit) = (
+            (self.num_cols - 1) / WORD_BITS,
+            (self.num_cols - 1) % WORD_BITS,
+        );
+        let last = self.num_rows - 1;
+        if self.positions[last * words + end_word] >> end_bit & 1 != 0 {
             self.end_bit = true
         }
 
-        let mut previous_col = 0_u128;
-        for row in 0..self.num_rows {
-            let current_col = self.positions[row];
-            self.positions[row] |= previous_col | (current_col >> 1) | (current_col << 1);
-            previous_col = current_col;
-            if row + 1 < self.num_rows {
-                self.positions[row] |= self.positions[row + 1]
+        let mask = match self.num_cols % WORD_BITS {
+            0 => u64::MAX,
+            bits => u64::MAX >> (WORD_BITS - bits),
+        };
+        let positions = &self.positions;
+        let rows = positions.chunks_exact(words);
+        let above = std::iter::once(None).chain(rows.clone().map(Some));
+        let below = rows.clone().skip(1).map(Some).chain(std::iter::once(None));
+        let blizzards = self
+            .arrows_up
+            .chunks_exact(words)
+            .zip(self.arrows_down.chunks_exact(words))
+            .zip(self.arrows_left.chunks_exact(words))
+            .zip(self.arrows_right.chunks_exact(words));
+        let next_rows = self.next_positions.chunks_exact_mut(words);
+        for ((((current, above), below), (((up, down), left), right)), next) in
+            rows.zip(above).zip(below).zip(blizzards).zip(next_rows)
+        {
+            for word in 0..words {
+                // The bits that move into this word from the neighbouring words of the row.
+                let from_lower = match word {
+                    0 => 0,
+                    _ => current[word - 1] >> (WORD_BITS - 1),
+                };
+                let from_higher = match word + 1 < words {
+                    true => current[word + 1] << (WORD_BITS - 1),
+                    false => 0,
+                };
+                let mut reachable = current[word]
+                    | (current[word] << 1 | from_lower)
+                    | (current[word] >> 1 | from_higher)
+                    | above.map_or(0, |above| above[word])
+                    | below.map_or(0, |below| below[word]);
+                if word + 1 == words {
+                    reachable &= mask;
+                }
+                next[word] = reachable & !(up[word] | down[word] | left[word] | right[word]);
             }
-            self.positions[row] &= !(1_u128 << self.num_cols);
-            self.positions[row] &= !(self.arrows_up[row]
-                | self.arrows_down[row]
-                | self.arrows_left[row]
-                | self.arrows_right[row])
         }
+        std::mem::swap(&mut self.positions, &mut self.next_positions);
 
+        // Leaving the start or the end is only possible when no blizzard is in the way.
         if previous_start {
-            self.positions[0] |= 1_u128
+            self.positions[0] |= 1 & !self.blizzards(0)
         }
 
         if previous_end {
-            self.positions[self.num_rows - 1] |= 1_u128 << (self.num_cols - 1)
+            let index = last * words + end_word;
+            self.positions[index] |= (1 << end_bit) & !self.blizzards(index)
+        }
+    }
+
+    fn blizzards(&self, index: usize) -> u64 {
+        self.arrows_up[index]
+            | self.arrows_down[index]
+            | self.arrows_left[index]
+            | self.arrows_right[index]
+    }
+
+    /// The minutes it takes to get from the start to the end, or from the end back to the start,
+    /// or `None` if there is no way through.
+    ///
+    /// The blizzards repeat after `period` minutes, and waiting at the start is always possible,
+    /// so every tile that can be reached at some minute can also be reached a period later. Once
+    /// the number of reachable tiles stays the same for a whole period, so do the tiles.
+    fn cross(&mut self, back: bool) -> Option<usize> {
+        self.positions.fill(0);
+        (self.start_bit, self.end_bit) = (!back, back);
+        let period = self.num_rows / gcd(self.num_rows, self.num_cols) * self.num_cols;
+        let mut counts = vec![usize::MAX; period];
+        let mut unchanged = 0;
+
+        let mut time = 0;
+        loop {
+            time += 1;
+            self.step();
+            if if back { self.start_bit } else { self.end_bit } {
+                return Some(time);
+            }
+
+            // Crossings usually take less than a period, which needs no counting at all.
+            if time < period {
+                continue;
+            }
+            let count: usize = self
+                .positions
+                .iter()
+                .map(|word| word.count_ones() as usize)
+                .sum();
+            let phase = time % period;
+            if counts[phase] == count {
+                unchanged += 1;
+                if unchanged >= period {
+                    return None;
+                }
+            } else {
+                counts[phase] = count;
+                unchanged = 0;
+            }
         }
     }
 }
 
-pub(crate) fn part1(input: &[u8]) -> usize {
-    let mut grid = Grid::parse(input);
-    grid.start_bit = true;
+pub fn parse(input: &[u8]) -> Result<Grid, ParseError> {
+    let tiles = Grid2D::parse(input, "tiles", "`#`, `.`, `^`, `v`, `<` or `>`", |byte| {
+        matches!(byte, b'#' | b'.' | b'^' | b'v' | b'<' | b'>').then_some(byte)
+    })?;
+    // The lines of the input, to point at the offending tiles.
+    let lines: Vec<&[u8]> = input
+        .trim_ascii_end()
+        .split(|byte| *byte == b'\n')
+        .collect();
+    let (width, height) = (tiles.width(), tiles.height());
+    if width < 3 {
+        return Err(ParseError::at(
+            input,
+            lines[0],
+            "a valley at least 1 tile wide",
+        ));
+    }
+    if height < 3 {
+        let last = lines[height - 1];
+        return Err(ParseError::at(input, last, "a valley at least 1 row high"));
+    }
+    let num_cols = width - 2;
+    let num_rows = height - 2;
+    let words = num_cols.div_ceil(WORD_BITS);
+
+    let mut arrows_up = vec![0; num_rows * words];
+    let mut arrows_down = vec![0; num_rows * words];
+    let mut arrows_left = vec![0; num_rows * words];
+    let mut arrows_right = vec![0; num_rows * words];
+
+    // The solver starts at the top left and ends at the bottom right of the valley.
+    let wall = |y: usize, gap: usize| {
+        let row = tiles.row(y);
+        (0..width).all(|x| row[x] == if x == gap { b'.' } else { b'#' })
+    };
+    if !wall(0, 1) {
+        let expected = format!("a wall of {} tiles with the entrance second", width);
+        return Err(ParseError::at(input, lines[0], expected));
+    }
+    if !wall(height - 1, width - 2) {
+        let expected = format!("a wall of {} tiles with the exit second to last", width);
+        return Err(ParseError::at(input, lines[height - 1], expected));
+    }
 
-    let mut time = 0;
-    while !grid.end_bit {
-        time += 1;
-        grid.step();
+    for row in 0..num_rows {
+        for col in 0..num_cols {
+            let (index, bit) = (row * words + col / WORD_BITS, 1 << (col % WORD_BITS));
+            match tiles[(col + 1, row + 1)] {
+                b'^' => arrows_up[index] |= bit,
+                b'v' => arrows_down[index] |= bit,
+                b'<' => arrows_left[index] |= bit,
+                b'>' => arrows_right[index] |= bit,
+                b'.' => {}
+                _ => {
+                    let tile = &lines[row + 1][col + 1..=col + 1];
+                    return Err(ParseError::at(input, tile, "`.`, `^`, `v`, `<` or `>`"));
+                }
+            }
+        }
     }
 
-    time
+    Ok(Grid {
+        arrows_up,
+        arrows_down,
+        arrows_left,
+        arrows_right,
+        positions: vec![0; num_rows * words],
+        next_positions: vec![0; num_rows * words],
+        start_bit: false,
+        end_bit: false,
+        num_rows,
+        num_cols,
+        words,
+    })
+}
+
+pub fn part1(grid: &Grid) -> Option<usize> {
+    grid.clone().cross(false)
+}
+
+pub fn part2(grid: &Grid) -> Option<usize> {
+    let mut grid = grid.clone();
+    Some(grid.cross(false)? + grid.cross(true)? + grid.cross(false)?)
 }
 
-pub(crate) fn part2(input: &[u8]) -> usize {
-    let mut grid = Grid::parse(input);
-    let mut time = 0;
+pub struct Day24;
 
-    grid.start_bit = true;
-    while !grid.end_bit {
-        time += 1;
-        grid.step();
-    }
+impl Solution for Day24 {
+    type Parsed = Grid;
+    type Params = ();
 
-    grid.positions.fill(0);
-    grid.start_bit = false;
-    grid.end_bit = true;
-    while !grid.start_bit {
-        time += 1;
-        grid.step();
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
     }
 
-    grid.positions.fill(0);
-    grid.start_bit = true;
-    grid.end_bit = false;
-    while !grid.end_bit {
-        time += 1;
-        grid.step();
+    fn part1(grid: &Self::Parsed, _: &()) -> Answer {
+        part1(grid).into()
     }
 
-    time
+    fn part2(grid: &Self::Parsed, _: &()) -> Answer {
+        part2(grid).into()
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day24.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day24.txt");
 
     #[test]
-    fn test_shift_right() {
-        let bits: u128 = 0b0000000010000101;
-        assert_eq!(shift_bits_right_with_wrapping(bits, 8), 0b0000000011000010);
+    fn test_rotate_right() {
+        let mut bits = [0b0000000010000101];
+        rotate_right(&mut bits, 8);
+        assert_eq!(bits, [0b0000000011000010]);
+        let mut bits = [1 | 1 << 63, 0b101];
+        rotate_right(&mut bits, 67);
+        assert_eq!(bits, [1 << 62 | 1 << 63, 0b110]);
     }
 
     #[test]
-    fn test_shift_left() {
-        let bits: u128 = 0b0000000010000101;
-        assert_eq!(shift_bits_left_with_wrapping(bits, 8), 0b0000000000001011);
+    fn test_rotate_left() {
+        let mut bits = [0b0000000010000101];
+        rotate_left(&mut bits, 8);
+        assert_eq!(bits, [0b0000000000001011]);
+        let mut bits = [1 | 1 << 63, 0b100];
+        rotate_left(&mut bits, 67);
+        assert_eq!(bits, [0b11, 0b001]);
     }
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), 308)
+        assert_eq!(part1(&parse(INPUT).unwrap()), Some(308))
     }
 
     #[test]
     fn test_part2() {
-        assert_eq!(part2(INPUT), 908)
+        assert_eq!(part2(&parse(INPUT).unwrap()), Some(908))
+    }
+
+    #[test]
+    fn test_example() {
+        let grid = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&grid), Some(18));
+        assert_eq!(part2(&grid), Some(54));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"#.###\n#>x.#\n###.#\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "x"));
+        let error = parse(b"#.###\n#>.#\n###.#\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "a row of 5 tiles")
+        );
+        let error = parse(b"#.###\n#>..#\n#.###\n").err().unwrap();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (3, "a wall of 5 tiles with the exit second to last")
+        );
+    }
+
+    #[test]
+    fn test_blizzard_at_exit() {
+        // On the way back, blizzards keep passing the tile next to the exit, which is only free
+        // to step on in between.
+        let grid = parse(b"#.#######\n#>..>..<#\n#######.#\n").unwrap();
+        assert_eq!(part1(&grid), Some(9));
+        assert_eq!(part2(&grid), Some(30));
+    }
+
+    #[test]
+    fn test_smallest_valley() {
+        let grid = parse(b"#.#\n#.#\n#.#\n").unwrap();
+        assert_eq!(part1(&grid), Some(2));
+        assert_eq!(part2(&grid), Some(6));
+    }
+
+    #[test]
+    fn test_no_way_through() {
+        // The blizzards always fill the row.
+        let grid = parse(b"#.###\n#>>>#\n###.#\n").unwrap();
+        assert_eq!(part1(&grid), None);
+        assert_eq!(part2(&grid), None);
+        // Two blizzards take turns on both tiles next to the exit.
+        let grid = parse(b"#.###\n#..^#\n#..v#\n###.#\n").unwrap();
+        assert_eq!(part1(&grid), None);
     }
 
-    #[bench]
-    fn bench_parse_grid(b: &mut Bencher) {
-        b.iter(|| Grid::parse(INPUT))
+    #[test]
+    fn test_large_input() {
+        // An empty valley 100 times the size of the puzzle input, with rows of several words.
+        let (cols, rows) = (1200, 250);
+        let input = format!(
+            "#.{}\n{}{}.#\n",
+            "#".repeat(cols),
+            format!("#{}#\n", ".".repeat(cols)).repeat(rows),
+            "#".repeat(cols)
+        );
+        let grid = parse(input.as_bytes()).unwrap();
+        assert_eq!(part1(&grid), Some(cols + rows));
+        assert_eq!(part2(&grid), Some(3 * (cols + rows)));
+        // Blizzards on the last bit of a word and the first of the next one.
+        let input = format!(
+            "#.{}\n#{}<>{}#\n{}.#\n",
+            "#".repeat(130),
+            ".".repeat(63),
+            ".".repeat(65),
+            "#".repeat(130)
+        );
+        let grid = parse(input.as_bytes()).unwrap();
+        let reference = crate::reference::reference(24).unwrap();
+        let expected = reference.solve(&input, &crate::day(24).unwrap().default_params());
+        assert_eq!(
+            expected,
+            Some([Answer::from(part1(&grid)), Answer::from(part2(&grid))])
+        );
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse_grid() {
+        bench("day24::parse_grid", || parse(INPUT))
     }
 
-    #[bench]
-    fn bench_part2(b: &mut Bencher) {
-        b.iter(|| part2(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let grid = parse(INPUT).unwrap();
+        bench("day24::part1", || part1(&grid))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let grid = parse(INPUT).unwrap();
+        bench("day24::part2", || part2(&grid))
     }
 }
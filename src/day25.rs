@@ -1,25 +1,29 @@
-extern crate test;
+use crate::error::ParseError;
+use crate::solution::{Answer, Solution};
 
-pub(crate) const INPUT: &[u8] = include_bytes!("../inputs/day25.txt");
-
-const SNAFU_DIGIT_ARRAY: [isize; 62] = build_snafu_digit_array();
+const SNAFU_DIGIT_ARRAY: [i64; 62] = build_snafu_digit_array();
 const INVERTED_SNAFU_DIGIT_ARRAY: [u8; 5] = *b"=-012";
 
-const fn build_snafu_digit_array() -> [isize; 62] {
+/// The most digits of a number in the input, so that it fits into `i64`.
+const NUMBER_DIGITS: usize = 27;
+/// The digits needed for any `i128`, so that the sum of the numbers always fits.
+const SUM_DIGITS: usize = 56;
+
+const fn build_snafu_digit_array() -> [i64; 62] {
     let mut array = [0; 62];
 
-    array[b'2' as usize] = 2_isize;
-    array[b'1' as usize] = 1_isize;
-    array[b'0' as usize] = 0_isize;
-    array[b'-' as usize] = -1_isize;
-    array[b'=' as usize] = -2_isize;
+    array[b'2' as usize] = 2_i64;
+    array[b'1' as usize] = 1_i64;
+    array[b'0' as usize] = 0_i64;
+    array[b'-' as usize] = -1_i64;
+    array[b'=' as usize] = -2_i64;
 
     array
 }
 
-const fn from_snafu(snafu: &[u8]) -> isize {
+const fn from_snafu(snafu: &[u8]) -> i64 {
     let mut index: usize = 0;
-    let mut result: isize = 0;
+    let mut result: i64 = 0;
 
     while index < snafu.len() {
         result *= 5;
@@ -30,9 +34,9 @@ const fn from_snafu(snafu: &[u8]) -> isize {
     result
 }
 
-const fn to_snafu(decimal: isize) -> [u8; 26] {
-    let mut snafu = [b'0'; 26];
-    let mut index = 25;
+const fn to_snafu(decimal: i128) -> [u8; SUM_DIGITS] {
+    let mut snafu = [b'0'; SUM_DIGITS];
+    let mut index = SUM_DIGITS - 1;
     let mut decimal = decimal;
 
     while decimal != 0 {
@@ -49,44 +53,133 @@ const fn to_snafu(decimal: isize) -> [u8; 26] {
     snafu
 }
 
-pub(crate) fn part1(input: &[u8]) -> [u8; 26] {
-    to_snafu(
-        input
-            .split(|byte| *byte == b'\n')
-            .map(from_snafu)
-            .sum::<isize>(),
-    )
+fn parse_snafu(input: &[u8], snafu: &[u8]) -> Result<i64, ParseError> {
+    if snafu.is_empty() || snafu.len() > NUMBER_DIGITS {
+        let expected = format!("a SNAFU number of 1 to {} digits", NUMBER_DIGITS);
+        return Err(ParseError::at(input, snafu, expected));
+    }
+    match snafu
+        .iter()
+        .position(|digit| !INVERTED_SNAFU_DIGIT_ARRAY.contains(digit))
+    {
+        Some(index) => Err(ParseError::at(
+            input,
+            &snafu[index..=index],
+            "a SNAFU digit `=`, `-`, `0`, `1` or `2`",
+        )),
+        None => Ok(from_snafu(snafu)),
+    }
+}
+
+pub fn parse(input: &[u8]) -> Result<Vec<i64>, ParseError> {
+    input
+        .trim_ascii_end()
+        .split(|byte| *byte == b'\n')
+        .map(|snafu| parse_snafu(input, snafu))
+        .collect()
+}
+
+pub fn part1(numbers: &[i64]) -> [u8; SUM_DIGITS] {
+    to_snafu(numbers.iter().map(|number| *number as i128).sum())
+}
+
+/// The digits of `snafu` without leading zeros.
+pub fn digits(snafu: &[u8]) -> &[u8] {
+    let first_digit = snafu.iter().position(|digit| *digit != b'0');
+    &snafu[first_digit.unwrap_or(snafu.len() - 1)..]
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Parsed = Vec<i64>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(numbers: &Self::Parsed, _: &()) -> Answer {
+        Answer::text(digits(&part1(numbers)))
+    }
+
+    fn part2(_numbers: &Self::Parsed, _: &()) -> Answer {
+        Answer::NoPart
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day25.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day25.txt");
 
     #[test]
     fn test_part1() {
-        assert_eq!(part1(INPUT), *b"0000002-121-=10=200==2==21")
+        assert_eq!(
+            digits(&part1(&parse(INPUT).unwrap())),
+            b"2-121-=10=200==2==21"
+        )
+    }
+
+    #[test]
+    fn test_example() {
+        let numbers = parse(EXAMPLE).unwrap();
+        assert_eq!(digits(&part1(&numbers)), b"2=-1=0");
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = parse(b"1=-0\n12a\n").err().unwrap();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 3, "a"));
+        assert!(parse(&b"1".repeat(28)).is_err());
     }
 
-    #[bench]
-    fn bench_to_snafu(b: &mut Bencher) {
-        b.iter(|| to_snafu(123456789))
+    #[test]
+    fn test_large_input() {
+        // The largest numbers of 27 digits, whose sum needs 33 digits and more than 64 bits.
+        let input = format!("{}\n", "2".repeat(27)).repeat(10_000);
+        let numbers = parse(input.as_bytes()).unwrap();
+        assert_eq!(numbers[0], (5_i64.pow(27) - 1) / 2);
+
+        let snafu = part1(&numbers);
+        let sum = digits(&snafu).iter().fold(0_i128, |sum, digit| {
+            5 * sum + SNAFU_DIGIT_ARRAY[*digit as usize] as i128
+        });
+        assert_eq!(digits(&snafu).len(), 33);
+        assert_eq!(sum, 10_000 * (5_i128.pow(27) - 1) / 2);
     }
 
-    #[bench]
-    fn bench_sum(b: &mut Bencher) {
-        b.iter(|| {
+    #[test]
+    #[ignore]
+    fn bench_to_snafu() {
+        bench("day25::to_snafu", || to_snafu(123456789))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_sum() {
+        bench("day25::sum", || {
             INPUT
+                .trim_ascii_end()
                 .split(|byte| *byte == b'\n')
                 .map(from_snafu)
-                .sum::<isize>();
+                .sum::<i64>()
         })
     }
 
-    #[bench]
-    fn bench_part1(b: &mut Bencher) {
-        b.iter(|| part1(INPUT))
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day25::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let numbers = parse(INPUT).unwrap();
+        bench("day25::part1", || part1(&numbers))
     }
 }
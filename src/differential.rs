@@ -0,0 +1,271 @@
+//! Differential testing: the solutions and their [`crate::reference`] solutions have to give the
+//! same answers on generated inputs, and any input where they differ is shrunk to a small one
+//! that still shows the difference.
+
+use crate::reference::Reference;
+use crate::runner::{self, Options, Status};
+use crate::solution::{Answer, Day};
+use std::fmt;
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+use std::time::Duration;
+
+/// How long a solution may take on an input before it counts as a difference.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Upper limit for the number of inputs tried while shrinking a difference.
+const MAX_ATTEMPTS: usize = 2000;
+
+/// The answers of both parts of a solution, or what went wrong instead.
+type Outcomes = [Result<Answer, String>; 2];
+
+/// An input on which a solution does not give the answers of its reference solution.
+#[derive(Debug, Clone)]
+pub struct Mismatch {
+    pub day: u8,
+    pub seed: u64,
+    pub size: usize,
+    pub input: String,
+    pub expected: [Answer; 2],
+    pub actual: Outcomes,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "day {} differs from its reference on an input shrunk from seed {} and size {}:",
+            self.day, self.seed, self.size
+        )?;
+        writeln!(f, "{}", self.input.trim_end())?;
+        for (part, (expected, actual)) in self.expected.iter().zip(&self.actual).enumerate() {
+            match actual {
+                Ok(actual) if actual == expected => {
+                    writeln!(f, "part {}: {}", part + 1, actual)?;
+                }
+                Ok(actual) => {
+                    writeln!(
+                        f,
+                        "part {}: expected {}, got {}",
+                        part + 1,
+                        expected,
+                        actual
+                    )?;
+                }
+                Err(err) => {
+                    writeln!(f, "part {}: expected {}, {}", part + 1, expected, err)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The answers of the reference solution, or `None` if it rejects the input or is not written.
+fn expected(reference: &Reference, day: &Day, input: &str) -> Option<[Answer; 2]> {
+    let params = day
+        .params(&reference.values())
+        .expect("valid reference parameters");
+    panic::catch_unwind(AssertUnwindSafe(|| reference.solve(input, &params)))
+        .ok()
+        .flatten()
+}
+
+/// The answers of the solution, or an error if it rejects the input as invalid.
+fn actual(reference: &Reference, day: &'static Day, input: &str) -> Result<Outcomes, String> {
+    let params = day
+        .params(&reference.values())
+        .expect("valid reference parameters");
+    let options = Options {
+        timeout: Some(TIMEOUT),
+        ..Options::default()
+    };
+    let results = runner::run_isolated(day, input.as_bytes().to_vec(), params, &[1, 2], &options);
+    let mut actual = Vec::new();
+    for result in results {
+        actual.push(match result.status {
+            Status::Ok => Ok(result.answer),
+            Status::Panic(message) => Err(format!("panicked: {}", message)),
+            Status::Timeout => Err(format!("timed out after {:?}", TIMEOUT)),
+            Status::Error(message) => return Err(message),
+        });
+    }
+    Ok(actual.try_into().expect("results of both parts"))
+}
+
+/// Both answers if the solution differs from the reference on a valid `input`. With `parsed`,
+/// inputs that the solution rejects do not count as valid, as while shrinking.
+fn differs(
+    reference: &Reference,
+    day: &'static Day,
+    input: &str,
+    parsed: bool,
+) -> Option<([Answer; 2], Outcomes)> {
+    let expected = expected(reference, day, input)?;
+    let actual = match actual(reference, day, input) {
+        Ok(actual) => actual,
+        Err(_) if parsed => return None,
+        Err(err) => [Err(err.clone()), Err(err)],
+    };
+    let same = expected
+        .iter()
+        .zip(&actual)
+        .all(|(expected, actual)| actual.as_ref() == Ok(expected));
+    (!same).then_some((expected, actual))
+}
+
+/// Compares the solution of the reference's day with the reference on an input for every seed,
+/// and returns the first difference, shrunk. Days without a reference solution pass.
+pub fn check(reference: &Reference, seeds: Range<u64>) -> Result<(), Box<Mismatch>> {
+    if !reference.is_written() {
+        return Ok(());
+    }
+    let day = crate::day(reference.day).expect("a solution for every reference");
+    for seed in seeds {
+        let size = 1 + seed as usize % reference.size;
+        let input = reference.input(seed, size);
+        if let Some((expected, actual)) = differs(reference, day, &input, false) {
+            let mismatch = Mismatch {
+                day: reference.day,
+                seed,
+                size,
+                input,
+                expected,
+                actual,
+            };
+            return Err(Box::new(shrink(reference, day, mismatch)));
+        }
+    }
+    Ok(())
+}
+
+/// The smallest input found that still differs: first generated with a smaller size, then with
+/// as many lines and characters removed as possible.
+fn shrink(reference: &Reference, day: &'static Day, mut mismatch: Mismatch) -> Mismatch {
+    let mut attempts = 0;
+    let mut try_input = |mismatch: &mut Mismatch, input: String| {
+        attempts += 1;
+        if attempts > MAX_ATTEMPTS || input.len() >= mismatch.input.len() {
+            return false;
+        }
+        match differs(reference, day, &input, true) {
+            Some((expected, actual)) => {
+                mismatch.input = input;
+                mismatch.expected = expected;
+                mismatch.actual = actual;
+                true
+            }
+            None => false,
+        }
+    };
+
+    let seed = mismatch.seed;
+    for size in 1..mismatch.size {
+        if try_input(&mut mismatch, reference.input(seed, size)) {
+            break;
+        }
+    }
+
+    // Removes chunks of lines, starting with halves of the input.
+    let mut chunk = mismatch.input.lines().count() / 2;
+    while chunk > 0 {
+        let mut start = 0;
+        loop {
+            let lines: Vec<&str> = mismatch.input.lines().collect();
+            if start >= lines.len() {
+                break;
+            }
+            let end = (start + chunk).min(lines.len());
+            let mut input = [&lines[..start], &lines[end..]].concat().join("\n");
+            input.push('\n');
+            if !try_input(&mut mismatch, input) {
+                start += chunk;
+            }
+        }
+        chunk /= 2;
+    }
+
+    // Removes single characters within the remaining lines, e.g. digits of numbers.
+    let mut index = 0;
+    while index < mismatch.input.len() {
+        let mut input = mismatch.input.clone();
+        if input.is_char_boundary(index) && input.as_bytes()[index] != b'\n' {
+            input.remove(index);
+            if try_input(&mut mismatch, input) {
+                continue;
+            }
+        }
+        index += 1;
+    }
+    mismatch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reference::{self, REFERENCES};
+
+    fn assert_same(seeds: Range<u64>) {
+        for reference in REFERENCES {
+            if let Err(mismatch) = check(reference, seeds.clone()) {
+                panic!("{}", mismatch);
+            }
+        }
+    }
+
+    #[test]
+    fn test_references() {
+        assert_eq!(REFERENCES.len(), usize::from(crate::NUM_DAYS));
+        for (reference, day) in REFERENCES.iter().zip(crate::DAYS) {
+            assert_eq!(reference.day, day.number);
+            assert!(day.params(&reference.values()).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_unwritten() {
+        let reference = Reference::unwritten(1);
+        assert_eq!(
+            reference.solve("1\n", &crate::DAYS[0].default_params()),
+            None
+        );
+        assert!(check(&reference, 0..8).is_ok());
+    }
+
+    #[test]
+    fn test_differential() {
+        assert_same(0..8);
+    }
+
+    /// Run with `cargo test --release -- --ignored differential`, as the CI workflow does.
+    #[test]
+    #[ignore]
+    fn test_differential_many_seeds() {
+        assert_same(0..500);
+    }
+
+    #[test]
+    fn test_shrink() {
+        // A reference that differs from day 1 whenever an elf carries more than 9 calories.
+        fn wrong(input: &str, params: &crate::solution::DayParams) -> [Answer; 2] {
+            let answers = reference::reference(1)
+                .unwrap()
+                .solve(input, params)
+                .unwrap();
+            match answers[0] {
+                Answer::Integer(calories) if calories > 9 => {
+                    [Answer::Integer(0), answers[1].clone()]
+                }
+                _ => answers,
+            }
+        }
+
+        let reference = Reference::new(1, 20, wrong);
+        let mismatch = check(&reference, 0..10).unwrap_err();
+        assert_eq!(mismatch.input.lines().count(), 1);
+        assert_eq!(mismatch.input.trim().len(), 2);
+        assert_eq!(mismatch.expected[0], Answer::Integer(0));
+        let part1 = format!("part 1: expected 0, got {}", mismatch.input.trim());
+        assert!(mismatch.to_string().contains(&part1));
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+
+/// A malformed puzzle input, pointing at the offending text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ParseError {
+    /// 1-based line number.
+    pub line: usize,
+    /// 1-based column, counted in bytes.
+    pub column: usize,
+    /// The offending text, or an empty string if the line ended too early.
+    pub text: String,
+    /// Description of what the parser expected instead.
+    pub expected: String,
+}
+
+impl ParseError {
+    /// Creates an error for `token`, which has to be a slice of `input`.
+    pub fn at(input: &[u8], token: &[u8], expected: impl Into<String>) -> Self {
+        let offset = (token.as_ptr() as usize)
+            .saturating_sub(input.as_ptr() as usize)
+            .min(input.len());
+        let before = &input[..offset];
+        let line_start = before
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map_or(0, |index| index + 1);
+        let text = token.split(|byte| *byte == b'\n').next().unwrap_or(token);
+
+        ParseError {
+            line: before.iter().filter(|byte| **byte == b'\n').count() + 1,
+            column: offset - line_start + 1,
+            text: String::from_utf8_lossy(text).into_owned(),
+            expected: expected.into(),
+        }
+    }
+
+    /// Creates an error for the end of `line`, for lines that are shorter than expected.
+    pub fn end_of(input: &[u8], line: &[u8], expected: impl Into<String>) -> Self {
+        Self::at(input, &line[line.len()..], expected)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, found ",
+            self.line, self.column, self.expected
+        )?;
+        if self.text.is_empty() {
+            write!(f, "end of line")
+        } else {
+            write!(f, "`{}`", self.text)
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Why a day could not be solved through [`crate::run_day`].
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Error {
+    /// There is no solution for the day.
+    UnknownDay(u8),
+    /// The input is malformed.
+    Parse(ParseError),
+}
+
+impl From<ParseError> for Error {
+    fn from(error: ParseError) -> Self {
+        Error::Parse(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::UnknownDay(day) => write!(f, "there is no solution for day {}", day),
+            Error::Parse(error) => write!(f, "invalid input: {}", error),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::UnknownDay(_) => None,
+            Error::Parse(error) => Some(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position() {
+        let input = b"1,2\n3,x4\n";
+        let error = ParseError::at(input, &input[6..8], "a number");
+        assert_eq!(error.line, 2);
+        assert_eq!(error.column, 3);
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected a number, found `x4`"
+        );
+    }
+
+    #[test]
+    fn test_end_of_line() {
+        let input = b"1,2\n3";
+        let line = &input[4..];
+        assert_eq!(
+            ParseError::end_of(input, line, "`,`").to_string(),
+            "line 2, column 2: expected `,`, found end of line"
+        );
+    }
+}
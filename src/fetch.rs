@@ -0,0 +1,157 @@
+use crate::client::{Client, Remote, YEAR};
+use std::path::{Path, PathBuf};
+
+pub fn default_cache_dir() -> PathBuf {
+    PathBuf::from(".cache")
+}
+
+/// Where the downloaded input of `day` is kept below `cache_dir`.
+pub fn cache_path(cache_dir: &Path, day: u8) -> PathBuf {
+    cache_dir
+        .join(YEAR.to_string())
+        .join(format!("day{:02}.txt", day))
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Outcome {
+    /// Downloaded the input, which has the given number of bytes.
+    Downloaded(usize),
+    /// Copied the input from an earlier download.
+    Cached(usize),
+}
+
+/// Writes the input of `day` to `target`, downloading it unless it is in the cache.
+///
+/// An existing `target` is only overwritten if it is empty or `force` is set, which also bypasses
+/// the cache.
+/// The session file is only read when a download is needed.
+pub fn fetch(
+    remote: &Remote,
+    cache_dir: &Path,
+    day: u8,
+    target: &Path,
+    force: bool,
+) -> Result<Outcome, String> {
+    let is_empty = |path: &Path| path.metadata().is_ok_and(|metadata| metadata.len() == 0);
+    if target.exists() && !is_empty(target) && !force {
+        return Err(format!(
+            "{} already exists, use --force to overwrite it",
+            target.display()
+        ));
+    }
+
+    let cached = cache_path(cache_dir, day);
+    let (input, outcome) = match std::fs::read(&cached) {
+        Ok(input) if !force => {
+            let size = input.len();
+            (input, Outcome::Cached(size))
+        }
+        _ => {
+            let input = Client::connect(remote)?.input(day)?;
+            write(&cached, &input)?;
+            let size = input.len();
+            (input, Outcome::Downloaded(size))
+        }
+    };
+
+    write(target, &input)?;
+    Ok(outcome)
+}
+
+fn write(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|err| format!("cannot create {}: {}", parent.display(), err))?;
+    }
+    std::fs::write(path, contents)
+        .map_err(|err| format!("cannot write {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::testing::{serve, temp_dir, SESSION};
+
+    #[test]
+    fn test_fetch() {
+        let dir = temp_dir("fetch");
+        let (url, server) = serve(vec![(200, "1\n2\n"), (200, "3\n4\n")]);
+        let remote = Remote {
+            url,
+            session: dir.join(".session"),
+        };
+        std::fs::write(&remote.session, format!("SESSION={}\n", SESSION)).unwrap();
+        let (cache, target) = (dir.join("cache"), dir.join("inputs/day01.txt"));
+
+        assert_eq!(
+            fetch(&remote, &cache, 1, &target, false),
+            Ok(Outcome::Downloaded(4))
+        );
+        assert_eq!(std::fs::read(&target).unwrap(), b"1\n2\n");
+        assert_eq!(std::fs::read(cache_path(&cache, 1)).unwrap(), b"1\n2\n");
+
+        assert!(fetch(&remote, &cache, 1, &target, false)
+            .unwrap_err()
+            .contains("already exists"));
+
+        std::fs::remove_file(&target).unwrap();
+        assert_eq!(
+            fetch(&remote, &cache, 1, &target, false),
+            Ok(Outcome::Cached(4))
+        );
+
+        assert_eq!(
+            fetch(&remote, &cache, 1, &target, true),
+            Ok(Outcome::Downloaded(4))
+        );
+        assert_eq!(std::fs::read(&target).unwrap(), b"3\n4\n");
+
+        assert_eq!(server.join().unwrap().len(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_jets() {
+        let dir = temp_dir("fetch-jets");
+        let (url, server) = serve(vec![(200, "<<><>>\n")]);
+        let remote = Remote {
+            url,
+            session: dir.join(".session"),
+        };
+        std::fs::write(&remote.session, SESSION).unwrap();
+        let (cache, target) = (dir.join("cache"), dir.join("day17.txt"));
+
+        assert_eq!(
+            fetch(&remote, &cache, 17, &target, false),
+            Ok(Outcome::Downloaded(7))
+        );
+        assert_eq!(std::fs::read(&target).unwrap(), b"<<><>>\n");
+
+        server.join().unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_fetch_error() {
+        let dir = temp_dir("fetch-error");
+        let (url, server) = serve(vec![(500, "")]);
+        let remote = Remote {
+            url,
+            session: dir.join(".session"),
+        };
+        let (cache, target) = (dir.join("cache"), dir.join("day01.txt"));
+
+        assert!(fetch(&remote, &cache, 1, &target, false)
+            .unwrap_err()
+            .contains("cannot read session"));
+
+        std::fs::write(&remote.session, SESSION).unwrap();
+        assert!(fetch(&remote, &cache, 1, &target, false)
+            .unwrap_err()
+            .contains("session is invalid"));
+        assert!(!target.exists() && !cache_path(&cache, 1).exists());
+
+        server.join().unwrap();
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
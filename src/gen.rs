@@ -0,0 +1,1237 @@
+//! Random puzzle inputs, for testing without the personal inputs in `inputs/`.
+//!
+//! Every input is valid for its puzzle with the default parameters, and is determined by the seed
+//! and the size alone. Sizes too small for a valid input are raised to the smallest valid one.
+
+use crate::day13::Value;
+use crate::day24;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::ops::RangeInclusive;
+
+/// A pseudorandom number generator (SplitMix64), so that the inputs of a seed are the same on
+/// every platform and with every version of the dependencies.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Rng(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number from 0 to `bound - 1`. Panics if `bound` is 0.
+    pub fn below(&mut self, bound: usize) -> usize {
+        assert!(bound > 0, "empty range");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    /// A number in `range`. Panics if the range is empty.
+    pub fn range(&mut self, range: RangeInclusive<i64>) -> i64 {
+        let (low, high) = range.into_inner();
+        assert!(low <= high, "empty range");
+        let span = high.abs_diff(low) as u128 + 1;
+        low.wrapping_add(((self.next_u64() as u128 * span) >> 64) as i64)
+    }
+
+    /// `true` with a probability of `numerator / denominator`.
+    pub fn chance(&mut self, numerator: usize, denominator: usize) -> bool {
+        self.below(denominator) < numerator
+    }
+
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len())]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.below(index + 1));
+        }
+    }
+}
+
+/// Random inputs for one day.
+pub struct Generator {
+    pub day: u8,
+    /// What the size of an input counts, e.g. `elves`.
+    pub size: &'static str,
+    /// About the size of the actual puzzle inputs.
+    pub default_size: usize,
+    generate: fn(&mut Rng, usize) -> String,
+}
+
+impl Generator {
+    pub const fn new(
+        day: u8,
+        size: &'static str,
+        default_size: usize,
+        generate: fn(&mut Rng, usize) -> String,
+    ) -> Self {
+        Generator {
+            day,
+            size,
+            default_size,
+            generate,
+        }
+    }
+
+    /// The input for `seed`, of [`Generator::default_size`] if `size` is `None`.
+    pub fn generate(&self, seed: u64, size: Option<usize>) -> String {
+        (self.generate)(&mut Rng::new(seed), size.unwrap_or(self.default_size))
+    }
+}
+
+/// The generators of all days, ordered by day.
+pub static GENERATORS: &[Generator] = &[
+    Generator::new(1, "elves", 250, day01),
+    Generator::new(2, "rounds", 2500, day02),
+    Generator::new(3, "groups of three rucksacks", 100, day03),
+    Generator::new(4, "pairs", 1000, day04),
+    Generator::new(5, "moves", 500, day05),
+    Generator::new(6, "characters", 4096, day06),
+    Generator::new(7, "directories", 200, day07),
+    Generator::new(8, "trees per row and column", 99, day08),
+    Generator::new(9, "motions", 2000, day09),
+    Generator::new(10, "cycles", 240, day10),
+    Generator::new(11, "monkeys", 8, day11),
+    Generator::new(12, "columns", 160, day12),
+    Generator::new(13, "pairs of packets", 150, day13),
+    Generator::new(14, "paths of rock", 150, day14),
+    Generator::new(
+        15,
+        "sensors besides the four around the distress beacon",
+        20,
+        day15,
+    ),
+    Generator::new(16, "valves with a nonzero flow rate", 15, day16),
+    Generator::new(17, "jets", 10091, day17),
+    Generator::new(18, "cubes across the droplet", 20, day18),
+    Generator::new(19, "blueprints", 30, day19),
+    Generator::new(20, "numbers", 5000, day20),
+    Generator::new(21, "monkeys", 2000, day21),
+    Generator::new(22, "tiles along an edge of the cube", 50, day22),
+    Generator::new(23, "tiles along a side of the grove", 72, day23),
+    Generator::new(24, "columns of the valley", 120, day24),
+    Generator::new(25, "numbers", 120, day25),
+];
+
+pub fn generator(day: u8) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+/// Elves carrying 1 to 15 snacks each.
+fn day01(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for elf in 0..size.max(1) {
+        if elf > 0 {
+            input.push('\n');
+        }
+        for _ in 0..rng.range(1..=15) {
+            writeln!(input, "{}", rng.range(1000..=60_000)).unwrap();
+        }
+    }
+    input
+}
+
+fn day02(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let (opponent, response) = (*rng.pick(b"ABC"), *rng.pick(b"XYZ"));
+        writeln!(input, "{} {}", opponent as char, response as char).unwrap();
+    }
+    input
+}
+
+/// Rucksacks with exactly one item type in both compartments, in groups with exactly one item type
+/// in all three rucksacks.
+fn day03(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let mut items = *b"abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+        rng.shuffle(&mut items);
+        let badge = items[0];
+        // Every rucksack of the group has its own 17 item types besides the badge.
+        for kinds in items[1..].chunks(17) {
+            let (shared, len) = (kinds[0], 3 + rng.below(13));
+            let mut compartments = [&kinds[1..9], &kinds[9..]].map(|kinds| {
+                let mut compartment: Vec<u8> = (0..len).map(|_| *rng.pick(kinds)).collect();
+                compartment[0] = shared;
+                compartment
+            });
+            compartments[rng.below(2)][1] = badge;
+            for compartment in compartments.iter_mut() {
+                rng.shuffle(compartment);
+            }
+            writeln!(input, "{}", String::from_utf8_lossy(&compartments.concat())).unwrap();
+        }
+    }
+    input
+}
+
+fn day04(rng: &mut Rng, size: usize) -> String {
+    let sections = |rng: &mut Rng| {
+        let first = rng.range(1..=99);
+        (first, rng.range(first..=99))
+    };
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let ((a, b), (c, d)) = (sections(rng), sections(rng));
+        writeln!(input, "{}-{},{}-{}", a, b, c, d).unwrap();
+    }
+    input
+}
+
+/// Nine stacks, and moves that never take the last crate of a stack, so that both cranes end with
+/// a crate on top of every stack.
+fn day05(rng: &mut Rng, size: usize) -> String {
+    let stacks: Vec<Vec<u8>> = (0..9)
+        .map(|_| {
+            let height = rng.range(2..=8);
+            (0..height).map(|_| b'A' + rng.below(26) as u8).collect()
+        })
+        .collect();
+
+    let mut input = String::new();
+    let height = stacks.iter().map(Vec::len).max().unwrap_or(0);
+    for row in (0..height).rev() {
+        let crates: Vec<String> = stacks
+            .iter()
+            .map(|stack| match stack.get(row) {
+                Some(crate_) => format!("[{}]", *crate_ as char),
+                None => String::from("   "),
+            })
+            .collect();
+        writeln!(input, "{}", crates.join(" ")).unwrap();
+    }
+    let labels: Vec<String> = (1..=stacks.len())
+        .map(|stack| format!(" {} ", stack))
+        .collect();
+    writeln!(input, "{}\n", labels.join(" ")).unwrap();
+
+    let mut heights: Vec<usize> = stacks.iter().map(Vec::len).collect();
+    for _ in 0..size.max(1) {
+        let from = loop {
+            let stack = rng.below(heights.len());
+            if heights[stack] >= 2 {
+                break stack;
+            }
+        };
+        let to = loop {
+            let stack = rng.below(heights.len());
+            if stack != from {
+                break stack;
+            }
+        };
+        let count = 1 + rng.below(heights[from] - 1);
+        heights[from] -= count;
+        heights[to] += count;
+        writeln!(input, "move {} from {} to {}", count, from + 1, to + 1).unwrap();
+    }
+    input
+}
+
+/// A signal with 14 different characters in a row at a random position, and only 13 different
+/// characters before them.
+fn day06(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(14);
+    let marker = rng.below(size - 13);
+    let mut letters = *b"abcdefghijklmnopqrstuvwxyz";
+
+    let mut signal: Vec<u8> = (0..marker).map(|_| b'a' + rng.below(13) as u8).collect();
+    rng.shuffle(&mut letters);
+    signal.extend_from_slice(&letters[..14]);
+    signal.extend((marker + 14..size).map(|_| b'a' + rng.below(26) as u8));
+
+    format!("{}\n", String::from_utf8_lossy(&signal))
+}
+
+const EXTENSIONS: [&str; 3] = [".txt", ".dat", ".log"];
+
+/// A name of 1 to 8 letters that is not in `names` yet, with an extension if `extension`.
+fn file_name(rng: &mut Rng, names: &mut HashSet<String>, extension: bool) -> String {
+    loop {
+        let mut name: String = (0..rng.range(1..=8))
+            .map(|_| (b'a' + rng.below(26) as u8) as char)
+            .collect();
+        if extension && rng.chance(1, 2) {
+            let extension = rng.pick(&EXTENSIONS);
+            name.push_str(extension);
+        }
+        if names.insert(name.clone()) {
+            return name;
+        }
+    }
+}
+
+/// Lists the directory `dir` of the tree given by the `children` of every directory, and then
+/// explores its subdirectories.
+fn explore(rng: &mut Rng, children: &[Vec<usize>], dir: usize, input: &mut String) {
+    let mut names = HashSet::new();
+    let subdirs: Vec<(String, usize)> = children[dir]
+        .iter()
+        .map(|child| (file_name(rng, &mut names, false), *child))
+        .collect();
+
+    let mut entries: Vec<String> = subdirs
+        .iter()
+        .map(|(name, _)| format!("dir {}", name))
+        .collect();
+    for _ in 0..rng.below(5) {
+        let size = rng.range(1000..=300_000);
+        entries.push(format!("{} {}", size, file_name(rng, &mut names, true)));
+    }
+    rng.shuffle(&mut entries);
+
+    input.push_str("$ ls\n");
+    for entry in entries {
+        writeln!(input, "{}", entry).unwrap();
+    }
+    for (name, child) in subdirs {
+        writeln!(input, "$ cd {}", name).unwrap();
+        explore(rng, children, child, input);
+        input.push_str("$ cd ..\n");
+    }
+}
+
+/// A terminal session that lists every directory of a random tree once.
+fn day07(rng: &mut Rng, size: usize) -> String {
+    let mut children: Vec<Vec<usize>> = vec![Vec::new(); size.max(1)];
+    for dir in 1..children.len() {
+        let parent = rng.below(dir);
+        children[parent].push(dir);
+    }
+
+    let mut input = String::from("$ cd /\n");
+    explore(rng, &children, 0, &mut input);
+    input
+}
+
+fn day08(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut input = String::new();
+    for _ in 0..size {
+        let row: String = (0..size)
+            .map(|_| (b'0' + rng.below(10) as u8) as char)
+            .collect();
+        writeln!(input, "{}", row).unwrap();
+    }
+    input
+}
+
+fn day09(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        let direction = *rng.pick(b"UDLR") as char;
+        writeln!(input, "{} {}", direction, rng.range(1..=19)).unwrap();
+    }
+    input
+}
+
+/// A program that runs for the given number of cycles and keeps the sprite on the screen.
+fn day10(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    let (mut cycle, mut register) = (0, 1);
+    while cycle < size.max(1) {
+        if cycle + 2 > size || rng.chance(1, 3) {
+            input.push_str("noop\n");
+            cycle += 1;
+        } else {
+            let value = loop {
+                let value = rng.range(-15..=15);
+                if value != 0 && (0..40).contains(&(register + value)) {
+                    break value;
+                }
+            };
+            writeln!(input, "addx {}", value).unwrap();
+            register += value;
+            cycle += 2;
+        }
+    }
+    input
+}
+
+/// The divisors of the monkeys, whose product fits into 64 bits.
+const PRIMES: [u64; 15] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47];
+
+#[derive(Copy, Clone)]
+enum Operation {
+    Add(u64),
+    Multiply(u64),
+    Square,
+}
+
+struct Monkey {
+    items: Vec<u64>,
+    operation: Operation,
+    divisor: u64,
+    targets: [usize; 2],
+}
+
+/// Whether the worry levels of the 20 rounds of part 1 fit into 64 bits.
+fn calm_enough(monkeys: &[Monkey]) -> bool {
+    let mut items: Vec<Vec<u64>> = monkeys.iter().map(|monkey| monkey.items.clone()).collect();
+    for _ in 0..20 {
+        for (index, monkey) in monkeys.iter().enumerate() {
+            for old in std::mem::take(&mut items[index]) {
+                let old = old as u128;
+                let new = match monkey.operation {
+                    Operation::Add(summand) => old + summand as u128,
+                    Operation::Multiply(factor) => old * factor as u128,
+                    Operation::Square => old * old,
+                };
+                let Ok(new) = u64::try_from(new / 3) else {
+                    return false;
+                };
+                let target = monkey.targets[usize::from(new % monkey.divisor != 0)];
+                items[target].push(new);
+            }
+        }
+    }
+    true
+}
+
+/// From 2 to 15 monkeys with different prime divisors, one of which squares the worry levels.
+/// The worry levels of part 1 fit into 64 bits, and if no squaring monkey keeps them that small
+/// after many tries, which is likely for only a few monkeys, none squares them.
+fn day11(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(2, PRIMES.len());
+    let mut tries = 0;
+    let monkeys = loop {
+        let mut divisors = PRIMES;
+        rng.shuffle(&mut divisors);
+        tries += 1;
+        let squaring = if tries <= 1000 { rng.below(size) } else { size };
+        let monkeys: Vec<Monkey> = (0..size)
+            .map(|index| {
+                let mut others: Vec<usize> = (0..size).filter(|other| *other != index).collect();
+                rng.shuffle(&mut others);
+                let operation = if index == squaring {
+                    Operation::Square
+                } else if rng.chance(1, 3) {
+                    Operation::Multiply(rng.range(2..=19) as u64)
+                } else {
+                    Operation::Add(rng.range(1..=8) as u64)
+                };
+                Monkey {
+                    items: (0..rng.range(1..=8))
+                        .map(|_| rng.range(50..=99) as u64)
+                        .collect(),
+                    operation,
+                    divisor: divisors[index],
+                    targets: [others[0], others[others.len() - 1]],
+                }
+            })
+            .collect();
+        if calm_enough(&monkeys) {
+            break monkeys;
+        }
+    };
+
+    let mut input = String::new();
+    for (index, monkey) in monkeys.iter().enumerate() {
+        let items: Vec<String> = monkey.items.iter().map(u64::to_string).collect();
+        let operation = match monkey.operation {
+            Operation::Add(summand) => format!("+ {}", summand),
+            Operation::Multiply(factor) => format!("* {}", factor),
+            Operation::Square => String::from("* old"),
+        };
+        if index > 0 {
+            input.push('\n');
+        }
+        writeln!(
+            input,
+            "Monkey {}:\n  Starting items: {}\n  Operation: new = old {}\n  \
+             Test: divisible by {}\n    If true: throw to monkey {}\n    \
+             If false: throw to monkey {}",
+            index,
+            items.join(", "),
+            operation,
+            monkey.divisor,
+            monkey.targets[0],
+            monkey.targets[1]
+        )
+        .unwrap();
+    }
+    input
+}
+
+/// A hill of at least 40 columns and a quarter as many rows, rising by one step towards `E` from
+/// every square that is not in a pit. `S` is far enough away that the hill starts at `a` between
+/// them, and a shortest path from `S` to `E` avoids the pits.
+fn day12(rng: &mut Rng, size: usize) -> String {
+    let (width, height) = (size.max(40), (size / 4).max(10));
+    let distance =
+        |(x1, y1): (usize, usize), (x2, y2): (usize, usize)| x1.abs_diff(x2) + y1.abs_diff(y2);
+    let (start, end) = loop {
+        let start = (rng.below(width), rng.below(height));
+        let end = (rng.below(width), rng.below(height));
+        if distance(start, end) > 26 {
+            break (start, end);
+        }
+    };
+
+    let mut path = HashSet::from([start]);
+    let mut position = start;
+    while position != end {
+        let step = |from: usize, to: usize| if from < to { from + 1 } else { from - 1 };
+        if position.0 != end.0 && (position.1 == end.1 || rng.chance(1, 2)) {
+            position.0 = step(position.0, end.0);
+        } else {
+            position.1 = step(position.1, end.1);
+        }
+        path.insert(position);
+    }
+
+    let mut input = String::new();
+    for y in 0..height {
+        for x in 0..width {
+            let square = if (x, y) == start {
+                b'S'
+            } else if (x, y) == end {
+                b'E'
+            } else if !path.contains(&(x, y)) && rng.chance(1, 10) {
+                b'a'
+            } else {
+                b'z' - distance((x, y), end).min(25) as u8
+            };
+            input.push(square as char);
+        }
+        input.push('\n');
+    }
+    input
+}
+
+fn packet(rng: &mut Rng, depth: usize) -> Value {
+    Value::List(
+        (0..rng.below(6))
+            .map(|_| {
+                if depth < 4 && rng.chance(1, 3) {
+                    packet(rng, depth + 1)
+                } else {
+                    Value::Integer(rng.below(11) as u32)
+                }
+            })
+            .collect(),
+    )
+}
+
+/// Pairs of packets that are in order or not, none of which is in the same place as a divider
+/// packet.
+fn day13(rng: &mut Rng, size: usize) -> String {
+    let dividers = [2, 6].map(|value| Value::List(vec![Value::List(vec![Value::Integer(value)])]));
+    let mut input = String::new();
+    for pair in 0..size.max(1) {
+        let (left, right) = loop {
+            let (left, right) = (packet(rng, 0), packet(rng, 0));
+            let is_divider = |packet: &Value| {
+                dividers
+                    .iter()
+                    .any(|divider| packet.cmp(divider) == Ordering::Equal)
+            };
+            if left.cmp(&right) != Ordering::Equal && !is_divider(&left) && !is_divider(&right) {
+                break (left, right);
+            }
+        };
+        if pair > 0 {
+            input.push('\n');
+        }
+        writeln!(input, "{}\n{}", left, right).unwrap();
+    }
+    input
+}
+
+/// Whether sand falling from the source at `(500, 0)` past the `rocks` can reach the floor below
+/// all of them. Otherwise it piles up to the source in part 1 and never falls into the abyss.
+fn escapes(rocks: &HashSet<(i64, i64)>) -> bool {
+    let bottom = rocks.iter().map(|rock| rock.1).max().unwrap_or(0);
+    let mut visited = HashSet::from([(500, 0)]);
+    let mut stack = vec![(500, 0)];
+    while let Some((x, y)) = stack.pop() {
+        if y > bottom {
+            return true;
+        }
+        for next in [(x - 1, y + 1), (x, y + 1), (x + 1, y + 1)] {
+            if !rocks.contains(&next) && visited.insert(next) {
+                stack.push(next);
+            }
+        }
+    }
+    false
+}
+
+/// Paths of 2 to 6 points below and around the source of the sand, which leave a way down into
+/// the abyss.
+fn day14(rng: &mut Rng, size: usize) -> String {
+    let (paths, _) = std::iter::repeat_with(|| {
+        let mut rocks = HashSet::new();
+        let paths: Vec<Vec<(i64, i64)>> = (0..size.max(1))
+            .map(|_| {
+                let (mut x, mut y) = (rng.range(460..=540), rng.range(13..=170));
+                let mut points = vec![(x, y)];
+                rocks.insert((x, y));
+                let mut horizontal = rng.chance(1, 2);
+                for _ in 0..rng.range(1..=5) {
+                    let (length, sign) = (rng.range(1..=8), if rng.chance(1, 2) { 1 } else { -1 });
+                    let (dx, dy) = match horizontal {
+                        true => (sign, 0),
+                        false if y - length < 1 => (0, 1),
+                        false => (0, sign),
+                    };
+                    for _ in 0..length {
+                        (x, y) = (x + dx, y + dy);
+                        rocks.insert((x, y));
+                    }
+                    horizontal = !horizontal;
+                    points.push((x, y));
+                }
+                points
+            })
+            .collect();
+        (paths, rocks)
+    })
+    .find(|(_, rocks)| escapes(rocks))
+    .unwrap();
+
+    let mut input = String::new();
+    for points in paths {
+        let points: Vec<String> = points.iter().map(|(x, y)| format!("{},{}", x, y)).collect();
+        writeln!(input, "{}", points.join(" -> ")).unwrap();
+    }
+    input
+}
+
+/// The largest coordinate searched for the distress beacon, as in the puzzle.
+const MAX_COORDINATE: i64 = 4_000_000;
+
+struct Sensor {
+    position: (i64, i64),
+    beacon: (i64, i64),
+}
+
+impl Sensor {
+    fn radius(&self) -> i64 {
+        manhattan(self.position, self.beacon)
+    }
+}
+
+fn manhattan((x1, y1): (i64, i64), (x2, y2): (i64, i64)) -> i64 {
+    (x1 - x2).abs() + (y1 - y2).abs()
+}
+
+/// Sensors that cover every position of the search area of part 2 but one, the distress beacon.
+///
+/// Four sensors at `(±a, ±a)` from the distress beacon with a range of `2a - 1` cover everything
+/// around it up to `2a - 1` in either direction, except for the diagonals beyond `a`. The other
+/// sensors are along the axes beyond `2a`, out of their range. No sensor has any other beacon
+/// as close as its own.
+fn day15(rng: &mut Rng, size: usize) -> String {
+    sensors(rng, size, MAX_COORDINATE)
+}
+
+/// The sensors of day 15 with `count` besides the four around the distress beacon, for a search
+/// area of part 2 up to `max_coordinate`, at least 1, instead of the puzzle's.
+pub fn sensors(rng: &mut Rng, count: usize, max_coordinate: i64) -> String {
+    let distress = (rng.range(0..=max_coordinate), rng.range(0..=max_coordinate));
+    let a = rng.range(max_coordinate / 2 + 1..=max_coordinate);
+    let mut sensors: Vec<Sensor> = [(1, 1), (1, -1), (-1, 1), (-1, -1)]
+        .into_iter()
+        .map(|(sx, sy)| {
+            let position = (distress.0 + sx * a, distress.1 + sy * a);
+            let along = rng.range(0..=2 * a - 1);
+            Sensor {
+                position,
+                beacon: (
+                    position.0 + sx * along,
+                    position.1 + sy * (2 * a - 1 - along),
+                ),
+            }
+        })
+        .collect();
+
+    let consistent = |sensors: &[Sensor], sensor: &Sensor| {
+        sensors.iter().all(|other| {
+            manhattan(other.position, sensor.beacon) > other.radius()
+                && manhattan(sensor.position, other.beacon) > sensor.radius()
+        })
+    };
+    for _ in 0..count {
+        // Sensors that do not fit after some tries are left out.
+        for _ in 0..100 {
+            let along = 2 * a + rng.range(0..=max_coordinate);
+            let across = rng.range(-(along - 2 * a)..=along - 2 * a);
+            let sign = if rng.chance(1, 2) { 1 } else { -1 };
+            let offset = if rng.chance(1, 2) {
+                (sign * along, across)
+            } else {
+                (across, sign * along)
+            };
+            let position = (distress.0 + offset.0, distress.1 + offset.1);
+            let distance = manhattan(position, distress);
+            let radius = rng.range(distance / 2..=distance - 1);
+            // Away from the distress beacon along the axis, which stays out of the range of the
+            // four sensors around it.
+            let beacon = if offset.0.abs() > offset.1.abs() {
+                (position.0 + offset.0.signum() * radius, position.1)
+            } else {
+                (position.0, position.1 + offset.1.signum() * radius)
+            };
+            let sensor = Sensor { position, beacon };
+            if consistent(&sensors, &sensor) {
+                sensors.push(sensor);
+                break;
+            }
+        }
+    }
+    rng.shuffle(&mut sensors);
+
+    let mut input = String::new();
+    for Sensor { position, beacon } in sensors {
+        writeln!(
+            input,
+            "Sensor at x={}, y={}: closest beacon is at x={}, y={}",
+            position.0, position.1, beacon.0, beacon.1
+        )
+        .unwrap();
+    }
+    input
+}
+
+/// Connected tunnels between valves with a nonzero flow rate, at most 63 of them, and twice as
+/// many with none.
+fn day16(rng: &mut Rng, size: usize) -> String {
+    let working = size.clamp(1, 63);
+    let mut names: Vec<String> = (b'A'..=b'Z')
+        .flat_map(|first| (b'A'..=b'Z').map(move |second| [first, second]))
+        .map(|name| String::from_utf8_lossy(&name).into_owned())
+        .filter(|name| name != "AA")
+        .collect();
+    rng.shuffle(&mut names);
+    names.insert(0, String::from("AA"));
+    names.truncate(3 * working);
+
+    let mut rates = vec![0; names.len()];
+    let mut valves: Vec<usize> = (1..names.len()).collect();
+    rng.shuffle(&mut valves);
+    for valve in &valves[..working] {
+        rates[*valve] = rng.range(3..=25);
+    }
+
+    let mut tunnels: Vec<Vec<usize>> = vec![Vec::new(); names.len()];
+    let connect = |tunnels: &mut Vec<Vec<usize>>, from: usize, to: usize| {
+        if from != to && !tunnels[from].contains(&to) {
+            tunnels[from].push(to);
+            tunnels[to].push(from);
+        }
+    };
+    for valve in 1..names.len() {
+        let other = rng.below(valve);
+        connect(&mut tunnels, valve, other);
+    }
+    for _ in 0..names.len() / 2 {
+        let (from, to) = (rng.below(names.len()), rng.below(names.len()));
+        connect(&mut tunnels, from, to);
+    }
+
+    let mut lines: Vec<String> = (0..names.len())
+        .map(|valve| {
+            let neighbours: Vec<&str> = tunnels[valve]
+                .iter()
+                .map(|other| names[*other].as_str())
+                .collect();
+            let tunnels = if neighbours.len() == 1 {
+                "tunnel leads to valve"
+            } else {
+                "tunnels lead to valves"
+            };
+            format!(
+                "Valve {} has flow rate={}; {} {}\n",
+                names[valve],
+                rates[valve],
+                tunnels,
+                neighbours.join(", ")
+            )
+        })
+        .collect();
+    rng.shuffle(&mut lines);
+    lines.concat()
+}
+
+fn day17(rng: &mut Rng, size: usize) -> String {
+    let jets: String = (0..size.max(1))
+        .map(|_| if rng.chance(1, 2) { '<' } else { '>' })
+        .collect();
+    format!("{}\n", jets)
+}
+
+/// A ball of cubes with some missing, which leaves holes and air pockets.
+fn day18(rng: &mut Rng, size: usize) -> String {
+    let size = size.clamp(1, 1024) as i64;
+    let mut input = String::new();
+    for x in 0..size {
+        for y in 0..size {
+            for z in 0..size {
+                // Twice the distance from the center, to stay with integers.
+                let distance = [x, y, z].map(|coordinate| (2 * coordinate - size + 1).pow(2));
+                let inside = distance.iter().sum::<i64>() <= size * size;
+                if inside && rng.chance(3, 4) {
+                    writeln!(input, "{},{},{}", x, y, z).unwrap();
+                }
+            }
+        }
+    }
+    if input.is_empty() {
+        writeln!(input, "{},{},{}", size / 2, size / 2, size / 2).unwrap();
+    }
+    input
+}
+
+fn day19(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for id in 1..=size.max(1) {
+        writeln!(
+            input,
+            "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+             Each obsidian robot costs {} ore and {} clay. \
+             Each geode robot costs {} ore and {} obsidian.",
+            id,
+            rng.range(2..=4),
+            rng.range(2..=4),
+            rng.range(2..=4),
+            rng.range(5..=20),
+            rng.range(2..=4),
+            rng.range(5..=20)
+        )
+        .unwrap();
+    }
+    input
+}
+
+/// At least two numbers, exactly one of which is 0.
+fn day20(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(2);
+    let zero = rng.below(size);
+    let mut input = String::new();
+    for index in 0..size {
+        let number = if index == zero {
+            0
+        } else {
+            let number = rng.range(1..=10_000);
+            if rng.chance(1, 2) {
+                number
+            } else {
+                -number
+            }
+        };
+        writeln!(input, "{}", number).unwrap();
+    }
+    input
+}
+
+/// The names of the monkeys and their jobs.
+#[derive(Default)]
+struct Monkeys {
+    names: HashSet<String>,
+    lines: Vec<String>,
+}
+
+impl Monkeys {
+    /// Adds a monkey with the job given by `name`, and returns its name.
+    fn add(&mut self, rng: &mut Rng, job: impl FnOnce(&str) -> String) -> String {
+        let name = loop {
+            let name: String = (0..4)
+                .map(|_| (b'a' + rng.below(26) as u8) as char)
+                .collect();
+            if name != "root" && name != "humn" && self.names.insert(name.clone()) {
+                break name;
+            }
+        };
+        self.lines.push(job(&name));
+        name
+    }
+
+    /// Adds about `count` monkeys that end up yelling `value`, and returns the name of the last.
+    fn yell(&mut self, rng: &mut Rng, value: i64, count: usize) -> String {
+        if count < 3 && value > 0 {
+            return self.add(rng, |name| format!("{}: {}", name, value));
+        }
+
+        let count = count.saturating_sub(1);
+        let left_count = count / 2 + rng.below(count / 2 + 1);
+        let small_factor = (2..=9).rev().find(|factor| value % factor == 0);
+        let (left, operation, right) = match rng.below(4) {
+            0 if value >= 2 => {
+                let left = rng.range(1..=value - 1);
+                (left, '+', value - left)
+            }
+            1 if small_factor.is_some() => {
+                let factor = small_factor.unwrap();
+                (factor, '*', value / factor)
+            }
+            2 if value.abs() < 1_000_000_000 => {
+                let divisor = rng.range(2..=9);
+                (value * divisor, '/', divisor)
+            }
+            _ => {
+                // Keeps the left side positive, which ends the recursion.
+                let right = rng.range(1..=1000) + (1 - value).max(0);
+                (value + right, '-', right)
+            }
+        };
+        let left = self.yell(rng, left, left_count);
+        let right = self.yell(rng, right, count - left_count);
+        self.add(rng, |name| {
+            format!("{}: {} {} {}", name, left, operation, right)
+        })
+    }
+}
+
+/// About the given number of monkeys in a tree of jobs. Every division is exact, also on the way
+/// from `humn` to `root` with the number that `humn` yells in part 2.
+fn day21(rng: &mut Rng, size: usize) -> String {
+    let mut monkeys = Monkeys::default();
+    let steps = (size / 30).clamp(1, 100);
+    let count = size.saturating_sub(2 * steps + 2) / (steps + 1);
+
+    // The number yelled on the way from `humn`, in part 1 and in part 2.
+    let mut values = [rng.range(1..=1000), rng.range(1..=100_000)];
+    let mut name = String::from("humn");
+    monkeys.lines.push(format!("humn: {}", values[0]));
+    for _ in 0..steps {
+        let common_factor = (2..=9)
+            .rev()
+            .find(|factor| values.iter().all(|value| value % factor == 0));
+        let fits = values.iter().all(|value| value.abs() < 100_000_000_000);
+        let (other, operation, humn_left) = match rng.below(4) {
+            0 if common_factor.is_some() => (common_factor.unwrap(), '/', true),
+            1 if fits => (rng.range(2..=9), '*', rng.chance(1, 2)),
+            2 => (rng.range(1..=1000), '-', rng.chance(1, 2)),
+            _ => (rng.range(1..=1000), '+', rng.chance(1, 2)),
+        };
+        values = values.map(|value| match (operation, humn_left) {
+            ('/', _) => value / other,
+            ('*', _) => value * other,
+            ('-', true) => value - other,
+            ('-', false) => other - value,
+            _ => value + other,
+        });
+        let other = monkeys.yell(rng, other, count);
+        let (left, right) = if humn_left {
+            (name, other)
+        } else {
+            (other, name)
+        };
+        name = monkeys.add(rng, |name| {
+            format!("{}: {} {} {}", name, left, operation, right)
+        });
+    }
+
+    let other = monkeys.yell(rng, values[1], count);
+    let (left, right) = if rng.chance(1, 2) {
+        (name, other)
+    } else {
+        (other, name)
+    };
+    let operation = rng.pick(&['+', '-']);
+    monkeys
+        .lines
+        .push(format!("root: {} {} {}", left, operation, right));
+
+    rng.shuffle(&mut monkeys.lines);
+    monkeys
+        .lines
+        .iter()
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+/// The eleven nets of a cube, as the blocks of their rows.
+const NETS: [&[&str]; 11] = [
+    &["#...", "####", "#..."],
+    &["#...", "####", ".#.."],
+    &["#...", "####", "..#."],
+    &["#...", "####", "...#"],
+    &[".#..", "####", ".#.."],
+    &[".#..", "####", "..#."],
+    &["##..", ".###", ".#.."],
+    &["##..", ".###", "..#."],
+    &["##..", ".###", "...#"],
+    &["##..", ".##.", "..##"],
+    &["###..", "..###"],
+];
+
+/// A net of a cube in any rotation or reflection, with a wall on one in eight tiles, followed by
+/// a path of 40 steps per tile along an edge.
+fn day22(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let net = rng.pick(&NETS);
+    let (transpose, flip_rows, flip_cols) = (rng.chance(1, 2), rng.chance(1, 2), rng.chance(1, 2));
+    let mut blocks: Vec<(usize, usize)> = Vec::new();
+    for (row, line) in net.iter().enumerate() {
+        for (col, block) in line.bytes().enumerate() {
+            if block == b'#' {
+                let (rows, cols) = (net.len() - 1, line.len() - 1);
+                let row = if flip_rows { rows - row } else { row };
+                let col = if flip_cols { cols - col } else { col };
+                blocks.push(if transpose { (col, row) } else { (row, col) });
+            }
+        }
+    }
+    let block_rows = blocks.iter().map(|block| block.0).max().unwrap_or(0) + 1;
+    let block_cols = blocks.iter().map(|block| block.1).max().unwrap_or(0) + 1;
+
+    let mut input = String::new();
+    for row in 0..block_rows * size {
+        let mut line = String::new();
+        for col in 0..block_cols * size {
+            if blocks.contains(&(row / size, col / size)) {
+                // The path starts on the first open tile, which the solutions expect in the
+                // corner of the first face.
+                let start = row == 0 && line.trim_start().is_empty();
+                line.push(if !start && rng.chance(1, 8) { '#' } else { '.' });
+            } else {
+                line.push(' ');
+            }
+        }
+        writeln!(input, "{}", line.trim_end()).unwrap();
+    }
+
+    input.push('\n');
+    for step in 0..40 * size {
+        if step > 0 {
+            input.push(*rng.pick(&['L', 'R']));
+        }
+        write!(input, "{}", rng.range(1..=50)).unwrap();
+    }
+    input.push('\n');
+    input
+}
+
+/// Elves on about half of the tiles of a square grove.
+fn day23(rng: &mut Rng, size: usize) -> String {
+    let size = size.max(1);
+    let mut tiles: Vec<Vec<char>> = (0..size)
+        .map(|_| {
+            (0..size)
+                .map(|_| if rng.chance(1, 2) { '#' } else { '.' })
+                .collect()
+        })
+        .collect();
+    tiles[size / 2][size / 2] = '#';
+    tiles
+        .iter()
+        .map(|row| format!("{}\n", row.iter().collect::<String>()))
+        .collect()
+}
+
+/// A valley of the given columns and a fifth as many rows, with blizzards on three in five tiles,
+/// that the expedition can cross there, back and there again. None of the blizzards moves up or
+/// down in the columns of the entrance and the exit.
+fn day24(rng: &mut Rng, size: usize) -> String {
+    let cols = size.max(1);
+    let rows = (cols / 5).max(1);
+    loop {
+        let mut input = format!("#.{}\n", "#".repeat(cols));
+        for _ in 0..rows {
+            input.push('#');
+            for col in 0..cols {
+                let directions: &[char] = if col == 0 || col == cols - 1 {
+                    &['<', '>']
+                } else {
+                    &['<', '>', '^', 'v']
+                };
+                input.push(if rng.chance(3, 5) {
+                    *rng.pick(directions)
+                } else {
+                    '.'
+                });
+            }
+            input.push_str("#\n");
+        }
+        writeln!(input, "{}.#", "#".repeat(cols)).unwrap();
+        if day24::part2(&day24::parse(input.as_bytes()).unwrap()).is_some() {
+            return input;
+        }
+    }
+}
+
+/// SNAFU numbers of 1 to 20 digits, whose sum fits into 64 bits.
+fn day25(rng: &mut Rng, size: usize) -> String {
+    let mut input = String::new();
+    for _ in 0..size.max(1) {
+        input.push(*rng.pick(&['1', '2']));
+        for _ in 0..rng.below(20) {
+            input.push(*rng.pick(&['=', '-', '0', '1', '2']));
+        }
+        input.push('\n');
+    }
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Answer;
+    use crate::{day, run_day};
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(7);
+        let numbers: Vec<i64> = (0..1000).map(|_| rng.range(-3..=3)).collect();
+        assert!(numbers.iter().all(|number| (-3..=3).contains(number)));
+        assert!((-3..=3).all(|value| numbers.contains(&value)));
+        assert_eq!(Rng::new(7).range(i64::MIN..=i64::MAX), {
+            let mut rng = Rng::new(7);
+            rng.next_u64() as i64 ^ i64::MIN
+        });
+
+        let mut items: Vec<usize> = (0..10).collect();
+        rng.shuffle(&mut items);
+        items.sort_unstable();
+        assert_eq!(items, (0..10).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_reproducible() {
+        for generator in GENERATORS {
+            let input = generator.generate(3, Some(20));
+            assert_eq!(input, generator.generate(3, Some(20)));
+            assert_ne!(
+                input,
+                generator.generate(4, Some(20)),
+                "day {}",
+                generator.day
+            );
+        }
+    }
+
+    #[test]
+    fn test_every_day() {
+        let days: Vec<u8> = GENERATORS.iter().map(|generator| generator.day).collect();
+        assert_eq!(days, (1..=crate::NUM_DAYS).collect::<Vec<_>>());
+        assert!(generator(26).is_none());
+    }
+
+    /// Solves generated inputs of `size` for `seeds`, which must neither fail nor panic.
+    fn assert_valid(day: u8, size: usize, seeds: std::ops::Range<u64>) {
+        for seed in seeds {
+            let input = generator(day).unwrap().generate(seed, Some(size));
+            if let Err(error) = run_day(day, input.as_bytes()) {
+                panic!("day {}, seed {}: {}\n{}", day, seed, error, input);
+            }
+        }
+    }
+
+    #[test]
+    fn test_valid_inputs() {
+        for (day, size) in [
+            (1, 50),
+            (2, 100),
+            (3, 20),
+            (4, 100),
+            (5, 50),
+            (6, 100),
+            (7, 50),
+            (8, 20),
+            (9, 100),
+            (10, 240),
+            (11, 8),
+            (12, 40),
+            (13, 50),
+            (14, 20),
+            (16, 6),
+            (17, 100),
+            (18, 8),
+            (19, 2),
+            (20, 100),
+            (21, 200),
+            (22, 4),
+            (23, 10),
+            (24, 20),
+            (25, 50),
+        ] {
+            assert_valid(day, size, 0..5);
+        }
+        assert_valid(15, 10, 0..1);
+    }
+
+    #[test]
+    fn test_smallest_inputs() {
+        for generator in GENERATORS.iter().filter(|generator| generator.day != 15) {
+            for size in [0, 1] {
+                let input = generator.generate(0, Some(size));
+                let parsed = day(generator.day).unwrap().parse(input.as_bytes());
+                assert!(parsed.is_ok(), "day {}, size {}", generator.day, size);
+            }
+        }
+    }
+
+    #[test]
+    fn test_day03() {
+        let input = generator(3).unwrap().generate(1, Some(10));
+        let rucksacks: Vec<&str> = input.lines().collect();
+        for rucksack in &rucksacks {
+            let (first, second) = rucksack.split_at(rucksack.len() / 2);
+            let shared: HashSet<char> = first
+                .chars()
+                .filter(|item| second.contains(*item))
+                .collect();
+            assert_eq!(shared.len(), 1, "{}", rucksack);
+        }
+        for group in rucksacks.chunks(3) {
+            let badges: HashSet<char> = group[0]
+                .chars()
+                .filter(|item| group[1].contains(*item) && group[2].contains(*item))
+                .collect();
+            assert_eq!(badges.len(), 1);
+        }
+    }
+
+    #[test]
+    fn test_day15() {
+        use crate::day15;
+
+        let input = generator(15).unwrap().generate(2, Some(20));
+        let sensors = day15::parse(&input).unwrap();
+        assert!(sensors.len() > 4);
+        for sensor in &sensors {
+            let radius = sensor
+                .position()
+                .manhattan(sensor.nearest_beacon_position());
+            for other in &sensors {
+                let beacon = other.nearest_beacon_position();
+                if beacon != sensor.nearest_beacon_position() {
+                    assert!(sensor.position().manhattan(beacon) > radius);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_day21() {
+        // With the number of part 2, both sides of `root` are equal, so their difference is 0.
+        let input = generator(21).unwrap().generate(5, Some(300));
+        let (_, humn) = run_day(21, input.as_bytes()).unwrap();
+        let changed: String = input
+            .lines()
+            .map(|line| {
+                if line.starts_with("humn: ") {
+                    format!("humn: {}\n", humn)
+                } else if line.starts_with("root: ") {
+                    format!("{}\n", line.replace(" + ", " - "))
+                } else {
+                    format!("{}\n", line)
+                }
+            })
+            .collect();
+        let (part1, _) = run_day(21, changed.as_bytes()).unwrap();
+        assert_eq!(part1, Answer::Integer(0));
+    }
+}
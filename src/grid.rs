@@ -0,0 +1,358 @@
+use crate::error::ParseError;
+use std::ops::{Index, IndexMut};
+
+/// Offsets of the four orthogonal neighbours, in reading order.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets of the eight orthogonal and diagonal neighbours, in reading order.
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row, addressed by `(x, y)` with `x` the column.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Grid2D<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid2D<T> {
+    /// Creates a grid from its cells in row order, or returns `None` if their number does not
+    /// match the size.
+    pub fn from_cells(width: usize, height: usize, cells: Vec<T>) -> Option<Self> {
+        (cells.len() == width * height).then_some(Grid2D {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses a grid with one row per line, converting each byte with `cell`.
+    ///
+    /// Rows of a different length than the first are reported as expecting a row of
+    /// `<width> <cells>`, and bytes rejected by `cell` as expecting `expected`.
+    pub fn parse(
+        input: &[u8],
+        cells: &str,
+        expected: &str,
+        mut cell: impl FnMut(u8) -> Option<T>,
+    ) -> Result<Self, ParseError> {
+        let rows: Vec<&[u8]> = input
+            .trim_ascii_end()
+            .split(|byte| *byte == b'\n')
+            .collect();
+        let width = rows[0].len();
+
+        let mut grid = Vec::with_capacity(width * rows.len());
+        for row in &rows {
+            if row.len() != width {
+                let expected = format!("a row of {} {}", width, cells);
+                return Err(ParseError::at(input, row, expected));
+            }
+            for index in 0..width {
+                match cell(row[index]) {
+                    Some(value) => grid.push(value),
+                    None => return Err(ParseError::at(input, &row[index..=index], expected)),
+                }
+            }
+        }
+
+        Ok(Grid2D {
+            width,
+            height: rows.len(),
+            cells: grid,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        (x < self.width && y < self.height).then(|| &self.cells[y * self.width + x])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        (x < self.width && y < self.height).then(|| &mut self.cells[y * self.width + x])
+    }
+
+    /// Replaces the cell at `(x, y)` and returns the old value, or `None` if it is outside.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Moves `(x, y)` by `(dx, dy)`, returning `None` if the result is outside the grid.
+    pub fn offset(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> Option<(usize, usize)> {
+        let (x, y) = (x.checked_add_signed(dx)?, y.checked_add_signed(dy)?);
+        (x < self.width && y < self.height).then_some((x, y))
+    }
+
+    /// The positions of the four orthogonal neighbours of `(x, y)` inside the grid.
+    pub fn neighbours_4(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS_4
+            .into_iter()
+            .filter_map(move |offset| self.offset(position, offset))
+    }
+
+    /// The positions of the eight orthogonal and diagonal neighbours of `(x, y)` inside the grid.
+    pub fn neighbours_8(
+        &self,
+        position: (usize, usize),
+    ) -> impl Iterator<Item = (usize, usize)> + '_ {
+        NEIGHBOURS_8
+            .into_iter()
+            .filter_map(move |offset| self.offset(position, offset))
+    }
+
+    /// The number of steps from `(x, y)` in direction `(dx, dy)` before leaving the grid.
+    fn steps_to_edge(&self, (x, y): (usize, usize), (dx, dy): (isize, isize)) -> usize {
+        let steps = |position: usize, delta: isize, size: usize| match delta {
+            0 => usize::MAX,
+            1.. => (size - 1 - position) / delta as usize,
+            _ => position / delta.unsigned_abs(),
+        };
+        if x < self.width && y < self.height && (dx, dy) != (0, 0) {
+            steps(x, dx, self.width).min(steps(y, dy, self.height))
+        } else {
+            0
+        }
+    }
+
+    /// The positions reached by repeatedly stepping from `start` in `direction`, excluding
+    /// `start`, up to the edge of the grid.
+    pub fn ray(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> impl Iterator<Item = (usize, usize)> {
+        (1..=self.steps_to_edge((x, y), (dx, dy))).map(move |step| {
+            let step = step as isize;
+            (
+                x.wrapping_add_signed(dx * step),
+                y.wrapping_add_signed(dy * step),
+            )
+        })
+    }
+
+    /// The cells along [`Grid2D::ray`], for loops that do not need the positions.
+    pub fn ray_cells(
+        &self,
+        (x, y): (usize, usize),
+        (dx, dy): (isize, isize),
+    ) -> impl Iterator<Item = &T> {
+        let start = y * self.width + x;
+        let stride = dy * self.width as isize + dx;
+        (1..=self.steps_to_edge((x, y), (dx, dy)))
+            .map(move |step| &self.cells[start.wrapping_add_signed(stride * step as isize)])
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        &self.cells[y * self.width..(y + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> {
+        self.cells.chunks_exact(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl DoubleEndedIterator<Item = &T> + ExactSizeIterator {
+        assert!(x < self.width, "column {} outside of the grid", x);
+        self.cells.iter().skip(x).step_by(self.width)
+    }
+
+    /// All cells in reading order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    /// All positions in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// The first position in reading order whose cell matches `predicate`.
+    pub fn position(&self, predicate: impl Fn(&T) -> bool) -> Option<(usize, usize)> {
+        let index = self.cells.iter().position(predicate)?;
+        Some((index % self.width, index / self.width))
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid2D<U> {
+        Grid2D {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    /// Renders the grid with one line per row, ending every line with a newline.
+    pub fn render(&self, mut f: impl FnMut(&T) -> char) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            text.extend(row.iter().map(&mut f));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T: Clone> Grid2D<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self {
+        Grid2D {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Mirrors the grid at its main diagonal, so rows become columns.
+    pub fn transpose(&self) -> Self {
+        let cells = (0..self.width)
+            .flat_map(|x| self.column(x).cloned())
+            .collect();
+        Grid2D {
+            width: self.height,
+            height: self.width,
+            cells,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid2D<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) outside of the grid",
+            x,
+            y
+        );
+        &self.cells[y * self.width + x]
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid2D<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        assert!(
+            x < self.width && y < self.height,
+            "({}, {}) outside of the grid",
+            x,
+            y
+        );
+        &mut self.cells[y * self.width + x]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &[u8]) -> Result<Grid2D<u8>, ParseError> {
+        Grid2D::parse(input, "digits", "a digit", |byte| {
+            byte.is_ascii_digit().then(|| byte - b'0')
+        })
+    }
+
+    #[test]
+    fn test_parse() {
+        let grid = digits(b"123\n456\n").unwrap();
+        assert_eq!((grid.width(), grid.height()), (3, 2));
+        assert_eq!(grid[(2, 1)], 6);
+        assert_eq!(grid.get(1, 0), Some(&2));
+        assert_eq!(grid.get(3, 0), None);
+        assert_eq!(grid.get(0, 2), None);
+
+        let error = digits(b"123\n45\n").unwrap_err();
+        assert_eq!(
+            (error.line, error.expected.as_str()),
+            (2, "a row of 3 digits")
+        );
+        let error = digits(b"123\n4.6\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 2, "."));
+    }
+
+    #[test]
+    fn test_access() {
+        let mut grid = Grid2D::new(3, 2, '.');
+        assert_eq!(grid.set(1, 1, '#'), Some('.'));
+        assert_eq!(grid.set(3, 1, '#'), None);
+        *grid.get_mut(0, 0).unwrap() = '@';
+        grid[(2, 0)] = '#';
+        assert_eq!(grid.render(|cell| *cell), "@.#\n.#.\n");
+        assert_eq!(grid.position(|cell| *cell == '#'), Some((2, 0)));
+        assert_eq!(Grid2D::from_cells(2, 2, vec![1, 2, 3]), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_index_outside() {
+        let grid = Grid2D::new(3, 2, 0);
+        let _ = grid[(3, 0)];
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = Grid2D::new(3, 3, 0);
+        let corner: Vec<_> = grid.neighbours_4((0, 0)).collect();
+        assert_eq!(corner, [(1, 0), (0, 1)]);
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        let edge: Vec<_> = grid.neighbours_8((2, 1)).collect();
+        assert_eq!(edge, [(1, 0), (2, 0), (1, 1), (1, 2), (2, 2)]);
+    }
+
+    #[test]
+    fn test_lines() {
+        let grid = digits(b"123\n456\n").unwrap();
+        assert_eq!(grid.row(1), [4, 5, 6]);
+        assert_eq!(grid.rows().next_back(), Some(&[4, 5, 6][..]));
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), [2, 5]);
+        assert_eq!(grid.positions().nth(4), Some((1, 1)));
+        assert_eq!(grid.iter().next_back(), Some(&6));
+
+        let ray: Vec<_> = grid.ray((0, 0), (1, 0)).collect();
+        assert_eq!(ray, [(1, 0), (2, 0)]);
+        let ray: Vec<_> = grid.ray((0, 0), (1, 1)).collect();
+        assert_eq!(ray, [(1, 1)]);
+        assert_eq!(grid.ray((0, 1), (-1, 0)).count(), 0);
+        let ray: Vec<_> = grid.ray((2, 1), (-2, -1)).collect();
+        assert_eq!(ray, [(0, 0)]);
+        assert_eq!(grid.ray((1, 1), (0, 0)).count(), 0);
+        assert_eq!(grid.ray((3, 0), (1, 0)).count(), 0);
+        let cells: Vec<_> = grid.ray_cells((2, 1), (-1, 0)).copied().collect();
+        assert_eq!(cells, [5, 4]);
+    }
+
+    #[test]
+    fn test_transpose() {
+        let grid = digits(b"123\n456\n").unwrap();
+        let transposed = grid.transpose();
+        assert_eq!((transposed.width(), transposed.height()), (2, 3));
+        assert_eq!(
+            transposed.render(|digit| (b'0' + digit) as char),
+            "14\n25\n36\n"
+        );
+        assert_eq!(transposed.transpose(), grid);
+        assert_eq!(grid.map(|digit| digit * 2)[(2, 1)], 12);
+    }
+}
@@ -0,0 +1,208 @@
+use std::borrow::Cow;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Source {
+    /// `inputs/dayNN.txt`, or the input embedded at compile time with the `embed` feature.
+    Default,
+    /// `examples/dayNN.txt`, the example from the puzzle description.
+    Example,
+    File(PathBuf),
+    Stdin,
+}
+
+impl Source {
+    /// Interprets `-` as stdin and anything else as a file path.
+    pub fn from_arg(arg: &str) -> Self {
+        if arg == "-" {
+            Source::Stdin
+        } else {
+            Source::File(PathBuf::from(arg))
+        }
+    }
+
+    pub fn describe(&self, day: u8) -> String {
+        match self {
+            Source::Default if cfg!(feature = "embed") => format!("embedded input of day {}", day),
+            Source::Default => default_path(day).display().to_string(),
+            Source::Example => example_path(day).display().to_string(),
+            Source::File(path) => path.display().to_string(),
+            Source::Stdin => String::from("<stdin>"),
+        }
+    }
+
+    /// The name under which answers for this input are stored, see [`crate::answers`].
+    pub fn name(&self, day: u8) -> String {
+        match self {
+            Source::Default => default_path(day).display().to_string(),
+            Source::Example => example_path(day).display().to_string(),
+            Source::File(path) => path.display().to_string(),
+            Source::Stdin => String::from("-"),
+        }
+    }
+}
+
+pub fn default_path(day: u8) -> PathBuf {
+    Path::new("inputs").join(format!("day{:02}.txt", day))
+}
+
+pub fn example_path(day: u8) -> PathBuf {
+    Path::new("examples").join(format!("day{:02}.txt", day))
+}
+
+/// Reads the input of `day` from `source`, normalized with [`normalize`].
+pub fn load(day: u8, source: &Source) -> std::io::Result<Cow<'static, [u8]>> {
+    read(day, source).map(|input| match input {
+        Cow::Borrowed(input) => normalize(input),
+        Cow::Owned(input) => match normalize(&input) {
+            Cow::Borrowed(_) => Cow::Owned(input),
+            Cow::Owned(normalized) => Cow::Owned(normalized),
+        },
+    })
+}
+
+/// Removes what editors and operating systems may add when saving an input: a UTF-8 byte order
+/// mark, `\r\n` line endings, whitespace at the end of lines and blank lines at the end. The
+/// result ends with a single newline unless it is empty, so parsers only have to handle `\n`.
+pub fn normalize(input: &[u8]) -> Cow<'_, [u8]> {
+    let content = input
+        .strip_prefix(b"\xEF\xBB\xBF")
+        .unwrap_or(input)
+        .trim_ascii_end();
+    let mut normalized = Vec::with_capacity(content.len() + 1);
+    if !content.is_empty() {
+        for line in content.split(|byte| *byte == b'\n') {
+            normalized.extend_from_slice(line.trim_ascii_end());
+            normalized.push(b'\n');
+        }
+    }
+
+    if normalized == input {
+        Cow::Borrowed(input)
+    } else {
+        Cow::Owned(normalized)
+    }
+}
+
+fn read(day: u8, source: &Source) -> std::io::Result<Cow<'static, [u8]>> {
+    match source {
+        #[cfg(feature = "embed")]
+        Source::Default => Ok(Cow::Borrowed(embedded(day))),
+        #[cfg(not(feature = "embed"))]
+        Source::Default => std::fs::read(default_path(day)).map(Cow::Owned),
+        Source::Example => std::fs::read(example_path(day)).map(Cow::Owned),
+        Source::File(path) => std::fs::read(path).map(Cow::Owned),
+        Source::Stdin => {
+            let mut bytes = Vec::new();
+            std::io::stdin().read_to_end(&mut bytes)?;
+            Ok(Cow::Owned(bytes))
+        }
+    }
+}
+
+#[cfg(feature = "embed")]
+fn embedded(day: u8) -> &'static [u8] {
+    match day {
+        1 => include_bytes!("../inputs/day01.txt"),
+        2 => include_bytes!("../inputs/day02.txt"),
+        3 => include_bytes!("../inputs/day03.txt"),
+        4 => include_bytes!("../inputs/day04.txt"),
+        5 => include_bytes!("../inputs/day05.txt"),
+        6 => include_bytes!("../inputs/day06.txt"),
+        7 => include_bytes!("../inputs/day07.txt"),
+        8 => include_bytes!("../inputs/day08.txt"),
+        9 => include_bytes!("../inputs/day09.txt"),
+        10 => include_bytes!("../inputs/day10.txt"),
+        11 => include_bytes!("../inputs/day11.txt"),
+        12 => include_bytes!("../inputs/day12.txt"),
+        13 => include_bytes!("../inputs/day13.txt"),
+        14 => include_bytes!("../inputs/day14.txt"),
+        15 => include_bytes!("../inputs/day15.txt"),
+        16 => include_bytes!("../inputs/day16.txt"),
+        17 => include_bytes!("../inputs/day17.txt"),
+        18 => include_bytes!("../inputs/day18.txt"),
+        19 => include_bytes!("../inputs/day19.txt"),
+        20 => include_bytes!("../inputs/day20.txt"),
+        21 => include_bytes!("../inputs/day21.txt"),
+        22 => include_bytes!("../inputs/day22.txt"),
+        23 => include_bytes!("../inputs/day23.txt"),
+        24 => include_bytes!("../inputs/day24.txt"),
+        25 => include_bytes!("../inputs/day25.txt"),
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The input as saved by other editors and systems: with CRLF line endings, a byte order mark,
+    /// trailing whitespace, without or with extra final newlines, and combinations of these.
+    fn variants(input: &[u8]) -> Vec<Vec<u8>> {
+        let crlf = String::from_utf8_lossy(input)
+            .replace('\n', "\r\n")
+            .into_bytes();
+        let trailing_spaces = String::from_utf8_lossy(input)
+            .replace('\n', " \t\n")
+            .into_bytes();
+        vec![
+            [b"\xEF\xBB\xBF", input].concat(),
+            trailing_spaces,
+            input.trim_ascii_end().to_vec(),
+            [input, b"\n\n"].concat(),
+            [b"\xEF\xBB\xBF", crlf.trim_ascii_end()].concat(),
+            crlf,
+        ]
+    }
+
+    #[test]
+    fn test_variants() {
+        // The raw variants are passed on, so that `run_day` has to normalize them itself.
+        for day in crate::DAYS {
+            let example = std::fs::read(example_path(day.number)).unwrap();
+            let expected = crate::run_day(day.number, &example).unwrap();
+            for variant in variants(&example) {
+                assert_eq!(
+                    crate::run_day(day.number, &variant).as_ref(),
+                    Ok(&expected),
+                    "day {} with input {:?}",
+                    day.number,
+                    String::from_utf8_lossy(&variant)
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(b"1\n2\n"), Cow::Borrowed(b"1\n2\n"));
+        assert!(matches!(normalize(b"1\n2\n"), Cow::Borrowed(_)));
+        assert_eq!(normalize(b"\xEF\xBB\xBF1\r\n2\r\n").as_ref(), b"1\n2\n");
+        assert_eq!(normalize(b"1 \t\n\n2  \n\n\n").as_ref(), b"1\n\n2\n");
+        assert_eq!(normalize(b"  [D]    \n 1 ").as_ref(), b"  [D]\n 1\n");
+        assert_eq!(normalize(b"<>><").as_ref(), b"<>><\n");
+        assert_eq!(normalize(b"").as_ref(), b"");
+        assert_eq!(normalize(b"\xEF\xBB\xBF\r\n").as_ref(), b"");
+    }
+
+    #[test]
+    fn test_default_path() {
+        assert_eq!(default_path(7), Path::new("inputs/day07.txt"))
+    }
+
+    #[test]
+    fn test_from_arg() {
+        assert_eq!(Source::from_arg("-"), Source::Stdin);
+        assert_eq!(
+            Source::from_arg("my_input.txt"),
+            Source::File(PathBuf::from("my_input.txt"))
+        );
+    }
+
+    #[test]
+    fn test_load_file() {
+        let input = load(1, &Source::File(default_path(1))).unwrap();
+        assert_eq!(input, load(1, &Source::Default).unwrap())
+    }
+}
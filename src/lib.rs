@@ -1,118 +1,152 @@
-#![feature(test)]
-#![feature(byte_slice_trim_ascii)]
-#![feature(iter_intersperse)]
-
-mod day01;
-mod day02;
-mod day03;
-mod day04;
-mod day05;
-mod day06;
-mod day07;
-mod day08;
-mod day09;
-mod day10;
-mod day11;
-mod day12;
-mod day13;
-mod day14;
-mod day15;
-mod day16;
-mod day17;
-mod day18;
-mod day19;
-mod day20;
-mod day21;
-mod day22;
-mod day23;
-mod day24;
-mod day25;
-
-pub fn run_all() {
-    println!("{}", day01::part1(day01::INPUT));
-    println!("{}", day01::part2(day01::INPUT));
-
-    println!("{}", day02::part1(day02::INPUT));
-    println!("{}", day02::part2(day02::INPUT));
-
-    println!("{}", day03::part1(day03::INPUT));
-    println!("{}", day03::part2(day03::INPUT));
-
-    println!("{}", day04::part1(day04::INPUT));
-    println!("{}", day04::part2(day04::INPUT));
-
-    println!("{}", std::str::from_utf8(&day05::part1(day05::INPUT)).unwrap());
-    println!("{}", std::str::from_utf8(&day05::part2(day05::INPUT)).unwrap());
-
-    println!("{}", day06::part1(day06::INPUT));
-    println!("{}", day06::part2(day06::INPUT));
-
-    println!("{}", day07::part1(day07::INPUT));
-    println!("{}", day07::part2(day07::INPUT));
-
-    println!("{}", day08::part1(day08::INPUT));
-    println!("{}", day08::part2(day08::INPUT));
-
-    println!("{}", day09::part1(day09::INPUT));
-    println!("{}", day09::part2(day09::INPUT));
-
-    println!("{}", day10::part1(day10::INPUT));
-    println!("{}", day10::part2(day10::INPUT));
-
-    println!("{}", day11::part1());
-    println!("{}", day11::part2());
-
-    println!("{}", day12::part1(day12::INPUT));
-    println!("{}", day12::part2(day12::INPUT));
-
-    println!("{}", day13::part1(day13::INPUT));
-    println!("{}", day13::part2(day13::INPUT));
-
-    println!("{}", day14::part1(day14::INPUT));
-    println!("{}", day14::part2(day14::INPUT));
-
-    println!("{}", day15::part1(day15::INPUT));
-    println!("{}", day15::part2(day15::INPUT));
-
-    println!("{}", day16::part1(day16::INPUT));
-    println!("{}", day16::part2(day16::INPUT));
-
-    println!("{}", day17::part1(day17::INPUT));
-    println!("{}", day17::part2(day17::INPUT));
-
-    println!("{}", day18::part1(day18::INPUT));
-    println!("{}", day18::part2(day18::INPUT));
-
-    println!("{}", day19::part1(day19::INPUT));
-    println!("{}", day19::part2(day19::INPUT));
-
-    println!("{}", day20::part1(day20::INPUT));
-    println!("{}", day20::part2(day20::INPUT));
-
-    println!("{}", day21::part1(day21::INPUT));
-    println!("{}", day21::part2(day21::INPUT));
-
-    println!("{}", day22::part1(day22::INPUT));
-    println!("{}", day22::part2(day22::INPUT));
-
-    println!("{}", day23::part1(day23::INPUT));
-    println!("{}", day23::part2(day23::INPUT));
+pub mod day01;
+pub mod day02;
+pub mod day03;
+pub mod day04;
+pub mod day05;
+pub mod day06;
+pub mod day07;
+pub mod day08;
+pub mod day09;
+pub mod day10;
+pub mod day11;
+pub mod day12;
+pub mod day13;
+pub mod day14;
+pub mod day15;
+pub mod day16;
+pub mod day17;
+pub mod day18;
+pub mod day19;
+pub mod day20;
+pub mod day21;
+pub mod day22;
+pub mod day23;
+pub mod day24;
+pub mod day25;
+
+pub mod answers;
+pub mod bench;
+pub mod cli;
+pub mod client;
+pub mod cycle;
+#[cfg(test)]
+mod differential;
+pub mod error;
+pub mod fetch;
+pub mod gen;
+pub mod grid;
+pub mod input;
+pub mod params;
+pub mod parse;
+pub mod point;
+#[cfg(test)]
+mod reference;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod search;
+pub mod solution;
+pub mod submit;
+
+use error::Error;
+use input::Source;
+use runner::{Options, PartResult};
+use solution::{Answer, Day};
+
+pub const NUM_DAYS: u8 = 25;
+
+/// All implemented days, ordered by number.
+pub static DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1, "Calorie Counting"),
+    Day::new::<day02::Day02>(2, "Rock Paper Scissors"),
+    Day::new::<day03::Day03>(3, "Rucksack Reorganization"),
+    Day::new::<day04::Day04>(4, "Camp Cleanup"),
+    Day::new::<day05::Day05>(5, "Supply Stacks"),
+    Day::new::<day06::Day06>(6, "Tuning Trouble"),
+    Day::new::<day07::Day07>(7, "No Space Left On Device"),
+    Day::new::<day08::Day08>(8, "Treetop Tree House"),
+    Day::new::<day09::Day09>(9, "Rope Bridge"),
+    Day::new::<day10::Day10>(10, "Cathode-Ray Tube"),
+    Day::new::<day11::Day11>(11, "Monkey in the Middle"),
+    Day::new::<day12::Day12>(12, "Hill Climbing Algorithm"),
+    Day::new::<day13::Day13>(13, "Distress Signal"),
+    Day::new::<day14::Day14>(14, "Regolith Reservoir"),
+    Day::new::<day15::Day15>(15, "Beacon Exclusion Zone"),
+    Day::new::<day16::Day16>(16, "Proboscidea Volcanium"),
+    Day::new::<day17::Day17>(17, "Pyroclastic Flow"),
+    Day::new::<day18::Day18>(18, "Boiling Boulders"),
+    Day::new::<day19::Day19>(19, "Not Enough Minerals"),
+    Day::new::<day20::Day20>(20, "Grove Positioning System"),
+    Day::new::<day21::Day21>(21, "Monkey Math"),
+    Day::new::<day22::Day22>(22, "Monkey Map"),
+    Day::new::<day23::Day23>(23, "Unstable Diffusion"),
+    Day::new::<day24::Day24>(24, "Blizzard Basin"),
+    Day::new::<day25::Day25>(25, "Full of Hot Air"),
+];
+
+pub fn day(number: u8) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
 
-    println!("{}", day24::part1(day24::INPUT));
-    println!("{}", day24::part2(day24::INPUT));
+/// Parses `input` and solves both parts of `day` with the parameters of the actual puzzle, on the
+/// calling thread and without printing. The input is normalized with [`input::normalize`] first.
+///
+/// Panics of the solution are not caught; [`runner::run_days`] isolates them instead.
+pub fn run_day(day: u8, input: &[u8]) -> Result<(Answer, Answer), Error> {
+    let day = self::day(day).ok_or(Error::UnknownDay(day))?;
+    let parsed = day.parse(&input::normalize(input))?;
+    let params = day.default_params();
+    Ok((
+        day.solve(&parsed, &params, 1),
+        day.solve(&parsed, &params, 2),
+    ))
+}
 
-    println!("{}", std::str::from_utf8(&day25::part1(day25::INPUT)).unwrap());
+/// Solves all days and prints the answers, solving up to `jobs` days at the same time. A day
+/// that panics is reported as such without stopping the others, and the parts that failed are
+/// summarized at the end. Returns the results of all parts.
+pub fn run_all(jobs: usize) -> Vec<PartResult> {
+    let days: Vec<u8> = (1..=NUM_DAYS).collect();
+    let options = Options {
+        jobs,
+        ..Options::default()
+    };
+    let results = runner::run_days(&days, &[1, 2], &Source::Default, &options, |result| {
+        if let Some(line) = report::text_line(result) {
+            println!("{}", line)
+        }
+    });
+    if let Some(failures) = report::failures(&results) {
+        println!("{}", failures)
+    }
+    results
 }
 
 #[cfg(test)]
 mod tests {
-    extern crate test;
-
     use super::*;
-    use test::Bencher;
+    use crate::bench::bench;
+
+    #[test]
+    fn test_run_day() {
+        let input = b"1000\n2000\n\n4000\n\n5000\n6000\n";
+        assert_eq!(
+            run_day(1, input),
+            Ok((Answer::Integer(11000), Answer::Integer(18000)))
+        );
+        assert_eq!(run_day(26, input), Err(Error::UnknownDay(26)));
+        let error = run_day(1, b"1000\nabc\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "invalid input: line 2, column 1: expected a number of calories, found `abc`"
+        );
+    }
 
-    #[bench]
-    fn bench_all(b: &mut Bencher) {
-        b.iter(|| run_all())
+    #[test]
+    #[ignore]
+    fn bench_all() {
+        bench("all", || run_all(1));
+        let jobs = runner::available_jobs();
+        bench(&format!("all ({} jobs)", jobs), || run_all(jobs))
     }
 }
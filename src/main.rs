@@ -1,11 +1,312 @@
+use aoc_y2022::answers::{Answers, Summary};
+use aoc_y2022::bench::bench_day;
+use aoc_y2022::cli::{parse_args, Command, USAGE};
+use aoc_y2022::client::Client;
+use aoc_y2022::fetch::{fetch, Outcome};
+use aoc_y2022::input::Source;
+use aoc_y2022::params::Overrides;
+use aoc_y2022::report::{self, Format};
+use aoc_y2022::runner::{run_days, Options, Status};
+use aoc_y2022::scaffold;
+use aoc_y2022::submit::{submit, Response, Submissions};
+use aoc_y2022::{gen, input, DAYS};
+use std::path::Path;
+use std::process::ExitCode;
 use std::time::Instant;
-use aoc_y2022::run_all;
 
-fn main() {
-    let start = Instant::now();
+fn main() -> ExitCode {
+    let command = match parse_args(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(message) => {
+            eprintln!("error: {}\n\n{}", message, USAGE);
+            return ExitCode::FAILURE;
+        }
+    };
 
-    run_all();
+    match command {
+        Command::Run {
+            days,
+            parts,
+            source,
+            format,
+            mut options,
+            params,
+            timings,
+        } => {
+            options.params = match Overrides::load(&params) {
+                Ok(overrides) => overrides.merge(options.params),
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    return ExitCode::FAILURE;
+                }
+            };
+            let start = Instant::now();
 
-    let duration = start.elapsed();
-    println!("Time elapsed: {:?}", duration);
-}
\ No newline at end of file
+            let results = run_days(&days, &parts, &source, &options, |result| {
+                if format == Format::Text {
+                    if let Some(line) = report::text_line(result) {
+                        println!("{}", line);
+                    }
+                }
+            });
+
+            match format {
+                Format::Text => println!("Time elapsed: {:?}", start.elapsed()),
+                Format::Json => println!("{}", report::json(&results)),
+                Format::Csv => print!("{}", report::csv(&results)),
+            }
+
+            if timings {
+                // Keep stdout machine-readable for the structured formats.
+                match format {
+                    Format::Text => print!("\n{}", report::timings(&results)),
+                    Format::Json | Format::Csv => eprint!("{}", report::timings(&results)),
+                }
+            }
+
+            if let Some(failures) = report::failures(&results) {
+                eprintln!("error: {}", failures);
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Verify {
+            days,
+            parts,
+            source,
+            answers,
+            mut options,
+            params,
+        } => {
+            let answers = match Answers::load(&answers) {
+                Ok(answers) => answers,
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    return ExitCode::FAILURE;
+                }
+            };
+            options.params = match Overrides::load(&params) {
+                Ok(overrides) => overrides.merge(options.params),
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    return ExitCode::FAILURE;
+                }
+            };
+
+            let mut summary = Summary::default();
+            run_days(&days, &parts, &source, &options, |result| {
+                if let Some(verdict) = answers.check(&source.name(result.day), result) {
+                    summary.add(&verdict);
+                    println!("{}", report::verify_line(result, &verdict));
+                }
+            });
+            println!("{}", summary);
+
+            if summary.failed > 0 {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Bench {
+            days,
+            parts,
+            source,
+            config,
+            overrides,
+            params,
+        } => {
+            let overrides = match Overrides::load(&params) {
+                Ok(file) => file.merge(overrides),
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    return ExitCode::FAILURE;
+                }
+            };
+            for day in days.iter().filter_map(|number| aoc_y2022::day(*number)) {
+                let params = match day.params(&overrides.get(&source.name(day.number), day.number))
+                {
+                    Ok(params) => params,
+                    Err(message) => {
+                        eprintln!(
+                            "error: invalid parameters for day {}: {}",
+                            day.number, message
+                        );
+                        continue;
+                    }
+                };
+                let input = match input::load(day.number, &source) {
+                    Ok(input) => input,
+                    Err(err) => {
+                        eprintln!(
+                            "error: cannot read {}: {}",
+                            source.describe(day.number),
+                            err
+                        );
+                        continue;
+                    }
+                };
+                match bench_day(day, &input, &params, &parts, &config) {
+                    Ok(summaries) => summaries.iter().for_each(|summary| println!("{}", summary)),
+                    Err(err) => eprintln!("error: invalid input for day {}: {}", day.number, err),
+                }
+            }
+        }
+        Command::Fetch {
+            days,
+            remote,
+            cache,
+            force,
+        } => {
+            let mut failed = false;
+            for day in days {
+                let target = input::default_path(day);
+                match fetch(&remote, &cache, day, &target, force) {
+                    Ok(Outcome::Downloaded(size)) => {
+                        println!(
+                            "Downloaded day {} to {} ({} bytes)",
+                            day,
+                            target.display(),
+                            size
+                        )
+                    }
+                    Ok(Outcome::Cached(size)) => println!(
+                        "Copied day {} from the cache to {} ({} bytes)",
+                        day,
+                        target.display(),
+                        size
+                    ),
+                    Err(message) => {
+                        eprintln!("error: {}", message);
+                        failed = true;
+                    }
+                }
+            }
+
+            if failed {
+                return ExitCode::FAILURE;
+            }
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            remote,
+            submissions,
+        } => {
+            let answer = match answer {
+                Some(answer) => Ok(answer),
+                None => solve(day, part),
+            };
+            let response = answer.and_then(|answer| {
+                let mut submissions = Submissions::load(&submissions)?;
+                println!("Submitting {} for day {}, part {}", answer, day, part);
+                submit(&remote, &mut submissions, day, part, &answer)
+            });
+
+            match response {
+                Ok(Response::Correct) => println!("{}", Response::Correct),
+                Ok(response) => {
+                    println!("{}", response);
+                    return ExitCode::FAILURE;
+                }
+                Err(message) => {
+                    eprintln!("error: {}", message);
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+        Command::New {
+            day,
+            title,
+            remote,
+            cache,
+        } => {
+            let online = remote.session.exists();
+            let title = match title {
+                Some(title) => title,
+                None if online => Client::connect(&remote)
+                    .and_then(|client| client.title(day))
+                    .unwrap_or_else(|message| {
+                        eprintln!("warning: {}", message);
+                        format!("Day {}", day)
+                    }),
+                None => format!("Day {}", day),
+            };
+
+            if let Err(message) = scaffold::create(Path::new("."), day, &title) {
+                eprintln!("error: {}", message);
+                return ExitCode::FAILURE;
+            }
+            println!(
+                "Created {} for day {}: {}",
+                scaffold::module_path(Path::new(""), day).display(),
+                day,
+                title
+            );
+
+            let target = input::default_path(day);
+            if online {
+                match fetch(&remote, &cache, day, &target, false) {
+                    Ok(_) => println!("Downloaded the input to {}", target.display()),
+                    Err(message) => eprintln!("warning: {}", message),
+                }
+            } else {
+                println!(
+                    "No session in {}, run `fetch --day {}` to download the input",
+                    remote.session.display(),
+                    day
+                );
+            }
+        }
+        Command::Gen { day, seed, size } => match gen::generator(day) {
+            Some(generator) => print!("{}", generator.generate(seed, size)),
+            None => {
+                eprintln!("error: there is no generator for day {}", day);
+                return ExitCode::FAILURE;
+            }
+        },
+        Command::List => {
+            for day in DAYS.iter() {
+                let params: Vec<String> = day
+                    .default_values()
+                    .iter()
+                    .map(|(name, value)| format!("{}={}", name, value))
+                    .collect();
+                if params.is_empty() {
+                    println!("Day {:02}: {}", day.number, day.title);
+                } else {
+                    println!(
+                        "Day {:02}: {} ({})",
+                        day.number,
+                        day.title,
+                        params.join(", ")
+                    );
+                }
+            }
+        }
+        Command::Help => println!("{}", USAGE),
+    }
+
+    ExitCode::SUCCESS
+}
+
+/// Solves one part on the default input and returns the answer as it is submitted.
+fn solve(day: u8, part: u8) -> Result<String, String> {
+    let results = run_days(
+        &[day],
+        &[part],
+        &Source::Default,
+        &Options::default(),
+        |_| {},
+    );
+    let result = results
+        .first()
+        .ok_or_else(|| format!("there is no day {}", day))?;
+
+    match (&result.status, aoc_y2022::submit::encode(&result.answer)) {
+        (Status::Ok, Some(answer)) => Ok(answer),
+        (Status::Ok, None) => Err(format!(
+            "the answer of day {}, part {} cannot be submitted automatically, pass it with --answer",
+            day, part
+        )),
+        _ => Err(report::text_line(result).unwrap_or_default()),
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::ParseError;
+use std::path::{Path, PathBuf};
+
+pub fn default_path() -> PathBuf {
+    PathBuf::from("params.txt")
+}
+
+/// Parameter values that replace the defaults of a day, see [`crate::solution::Params`].
+///
+/// The params file has one value per line, `<input file> <day> <name> <value>`, like the answers
+/// file, so that e.g. the examples can be solved with the sizes from the puzzle description.
+/// Empty lines and lines starting with `#` are ignored. Values from the command line apply to
+/// every input and take precedence.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Overrides {
+    inputs: Vec<(String, u8, String, String)>,
+    all: Vec<(String, String)>,
+}
+
+impl Overrides {
+    pub fn parse(text: &str) -> Result<Self, ParseError> {
+        let mut inputs = Vec::new();
+
+        for line in text.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let mut fields = line.splitn(4, ' ');
+            let mut field = |expected: &str| {
+                fields
+                    .next()
+                    .filter(|field| !field.is_empty())
+                    .ok_or_else(|| ParseError::end_of(text.as_bytes(), line.as_bytes(), expected))
+            };
+            let input = field("an input file")?;
+            let day = field("a day")?;
+            let name = field("a parameter name")?;
+            let value = field("a value")?;
+
+            let day = match day.parse() {
+                Ok(day) if (1..=crate::NUM_DAYS).contains(&day) => day,
+                _ => {
+                    let expected = "a day from 1 to 25";
+                    return Err(ParseError::at(text.as_bytes(), day.as_bytes(), expected));
+                }
+            };
+
+            inputs.push((
+                input.to_string(),
+                day,
+                name.to_string(),
+                value.trim_end().to_string(),
+            ));
+        }
+
+        Ok(Overrides {
+            inputs,
+            all: Vec::new(),
+        })
+    }
+
+    /// Reads a params file. The default file is optional, as it is only needed to change
+    /// parameters.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        if path == default_path() && !path.exists() {
+            return Ok(Self::default());
+        }
+        let text = std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))?;
+        Self::parse(&text).map_err(|err| format!("invalid params file {}: {}", path.display(), err))
+    }
+
+    /// Sets the parameter `name` of every day and input, as given on the command line.
+    pub fn set(&mut self, name: &str, value: &str) {
+        self.all.push((name.to_string(), value.to_string()));
+    }
+
+    /// Adds the values of `other`, which take precedence over those already set.
+    pub fn merge(mut self, other: Overrides) -> Self {
+        self.inputs.extend(other.inputs);
+        self.all.extend(other.all);
+        self
+    }
+
+    /// The `(name, value)` pairs for `day` on `input`, in the order they are to be applied.
+    pub fn get(&self, input: &str, day: u8) -> Vec<(String, String)> {
+        self.inputs
+            .iter()
+            .filter(|entry| entry.0 == input && entry.1 == day)
+            .map(|(_, _, name, value)| (name.clone(), value.clone()))
+            .chain(self.all.iter().cloned())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: &str = "\
+# input day name value
+examples/day15.txt 15 row 10
+examples/day15.txt 15 max_coordinate 20
+
+inputs/day20.txt 20 decryption_key 1
+";
+
+    fn values(values: &[(&str, &str)]) -> Vec<(String, String)> {
+        values
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse() {
+        let overrides = Overrides::parse(PARAMS).unwrap();
+        assert_eq!(
+            overrides.get("examples/day15.txt", 15),
+            values(&[("row", "10"), ("max_coordinate", "20")])
+        );
+        assert_eq!(overrides.get("inputs/day15.txt", 15), values(&[]));
+        assert_eq!(overrides.get("examples/day15.txt", 14), values(&[]));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = Overrides::parse("a.txt 15 row 10\na.txt 0 row 10\n").unwrap_err();
+        assert_eq!((error.line, error.column, error.text.as_str()), (2, 7, "0"));
+        let error = Overrides::parse("a.txt 15 row\n").unwrap_err();
+        assert_eq!((error.line, error.column), (1, 13));
+    }
+
+    #[test]
+    fn test_merge() {
+        let mut cli = Overrides::default();
+        cli.set("row", "11");
+        let overrides = Overrides::parse(PARAMS).unwrap().merge(cli);
+        assert_eq!(
+            overrides.get("examples/day15.txt", 15),
+            values(&[("row", "10"), ("max_coordinate", "20"), ("row", "11")])
+        );
+        assert_eq!(
+            overrides.get("inputs/day01.txt", 1),
+            values(&[("row", "11")])
+        );
+    }
+
+    #[test]
+    fn test_load_default() {
+        let overrides = Overrides::load(&default_path()).unwrap();
+        assert_eq!(
+            overrides.get("examples/day15.txt", 15),
+            values(&[("row", "10"), ("max_coordinate", "20")])
+        );
+        assert!(Overrides::load(Path::new("missing-params.txt")).is_err());
+    }
+}
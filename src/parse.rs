@@ -0,0 +1,403 @@
+use crate::error::ParseError;
+
+/// Integer types that can be parsed from ASCII digits.
+pub trait Integer: Copy + 'static {
+    /// Whether a leading `-` is accepted.
+    const SIGNED: bool;
+
+    /// Parses decimal digits, with an optional leading `-` for signed types. Returns `None` for
+    /// empty input, any other byte, and values that do not fit into the type.
+    fn from_ascii(bytes: &[u8]) -> Option<Self>;
+}
+
+/// Powers of ten up to the largest one that fits into the type. Scaling each digit by its power
+/// is faster than multiplying the value by ten for every digit, which forms one long chain.
+macro_rules! powers_of_ten {
+    ($t:ty) => {{
+        const DIGITS: usize = <$t>::MAX.ilog10() as usize + 1;
+        let mut powers = [1; DIGITS];
+        let mut index = 1;
+        while index < DIGITS {
+            powers[index] = powers[index - 1] * 10;
+            index += 1;
+        }
+        powers
+    }};
+}
+
+/// The value of `digits` with fewer digits than the largest value of the type, which cannot
+/// overflow, or `None` if any byte is not a digit. All digits are checked at once at the end,
+/// which keeps the loop free of branches.
+macro_rules! short_digits {
+    ($t:ty, $digits:expr, $powers:expr) => {{
+        let mut value: $t = 0;
+        let mut valid = true;
+        for (index, byte) in $digits.iter().rev().enumerate() {
+            let digit = byte.wrapping_sub(b'0');
+            valid &= digit < 10;
+            value = value.wrapping_add((digit as $t).wrapping_mul($powers[index]));
+        }
+        valid.then_some(value)
+    }};
+}
+
+macro_rules! impl_unsigned {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const SIGNED: bool = false;
+
+            // Without inlining, parsing the short numbers of the puzzles takes several times as
+            // long for the wider types.
+            #[inline(always)]
+            fn from_ascii(bytes: &[u8]) -> Option<Self> {
+                const POWERS_OF_TEN: [$t; <$t>::MAX.ilog10() as usize + 1] = powers_of_ten!($t);
+
+                // One comparison rules out both empty input and the longest one.
+                if bytes.len().wrapping_sub(1) < POWERS_OF_TEN.len() - 1 {
+                    return short_digits!($t, bytes, POWERS_OF_TEN);
+                }
+                if bytes.len() != POWERS_OF_TEN.len() {
+                    return None;
+                }
+                bytes
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .try_fold(0 as $t, |value, (index, byte)| {
+                        let digit = byte.checked_sub(b'0').filter(|digit| *digit < 10)?;
+                        value.checked_add((digit as $t).checked_mul(POWERS_OF_TEN[index])?)
+                    })
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl Integer for $t {
+            const SIGNED: bool = true;
+
+            // Without inlining, parsing the short numbers of the puzzles takes several times as
+            // long for the wider types.
+            #[inline(always)]
+            fn from_ascii(bytes: &[u8]) -> Option<Self> {
+                const POWERS_OF_TEN: [$t; <$t>::MAX.ilog10() as usize + 1] = powers_of_ten!($t);
+
+                let (sign, digits) = match bytes {
+                    [b'-', digits @ ..] => (-1, digits),
+                    digits => (1, digits),
+                };
+                // One comparison rules out both empty input and the longest one.
+                if digits.len().wrapping_sub(1) < POWERS_OF_TEN.len() - 1 {
+                    return short_digits!($t, digits, POWERS_OF_TEN).map(|value| sign * value);
+                }
+                if digits.len() != POWERS_OF_TEN.len() {
+                    return None;
+                }
+                // Negative values are accumulated below zero, so that `MIN` can be parsed.
+                digits
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .try_fold(0 as $t, |value, (index, byte)| {
+                        let digit = byte.checked_sub(b'0').filter(|digit| *digit < 10)?;
+                        value.checked_add((sign * digit as $t).checked_mul(POWERS_OF_TEN[index])?)
+                    })
+            }
+        }
+    )*};
+}
+
+impl_unsigned!(u8, u16, u32, u64, u128, usize);
+impl_signed!(i8, i16, i32, i64, i128, isize);
+
+/// Parses `bytes` as an integer, see [`Integer::from_ascii`].
+#[inline]
+pub fn integer<T: Integer>(bytes: &[u8]) -> Option<T> {
+    T::from_ascii(bytes)
+}
+
+/// Splits `line` into its integers, skipping all other text.
+///
+/// For signed types, a `-` directly before the digits is part of the integer unless it follows
+/// a digit, so that ranges like `2-4` are still split into `2` and `4`.
+pub fn integer_tokens<T: Integer>(line: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let mut position = 0;
+    std::iter::from_fn(move || {
+        let start = position + line[position..].iter().position(u8::is_ascii_digit)?;
+        let end = line[start..]
+            .iter()
+            .position(|byte| !byte.is_ascii_digit())
+            .map_or(line.len(), |length| start + length);
+        position = end;
+
+        let negative = T::SIGNED
+            && start > 0
+            && line[start - 1] == b'-'
+            && (start < 2 || !line[start - 2].is_ascii_digit());
+        Some(&line[start - negative as usize..end])
+    })
+}
+
+/// Parses `token`, a slice of `input`, as an integer, or returns an error pointing at it.
+pub fn integer_at<T: Integer>(input: &[u8], token: &[u8]) -> Result<T, ParseError> {
+    integer(token).ok_or_else(|| {
+        let expected = format!("a number of type `{}`", std::any::type_name::<T>());
+        ParseError::at(input, token, expected)
+    })
+}
+
+/// Parses all integers of `line`, a slice of `input`, as found by [`integer_tokens`].
+pub fn integers<'a, T: Integer>(
+    input: &'a [u8],
+    line: &'a [u8],
+) -> impl Iterator<Item = Result<T, ParseError>> + 'a {
+    integer_tokens::<T>(line).map(move |token| integer_at(input, token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::bench;
+    use std::hint::black_box;
+
+    #[test]
+    fn test_unsigned() {
+        assert_eq!(integer::<u8>(b"0"), Some(0));
+        assert_eq!(integer::<u8>(b"255"), Some(255));
+        assert_eq!(integer::<u8>(b"256"), None);
+        assert_eq!(integer::<u8>(b"1000"), None);
+        assert_eq!(integer::<u32>(b"4294967295"), Some(u32::MAX));
+        assert_eq!(integer::<u32>(b"4294967296"), None);
+        assert_eq!(integer::<u64>(b"18446744073709551615"), Some(u64::MAX));
+        assert_eq!(integer::<u128>(b"1".repeat(40).as_slice()), None);
+        assert_eq!(integer::<usize>(b""), None);
+        assert_eq!(integer::<usize>(b"-1"), None);
+        assert_eq!(integer::<usize>(b"+1"), None);
+        assert_eq!(integer::<usize>(b"1 "), None);
+        assert_eq!(integer::<usize>(b"1a"), None);
+        assert_eq!(integer::<usize>(b"/"), None);
+        assert_eq!(integer::<usize>(b":"), None);
+    }
+
+    #[test]
+    fn test_signed() {
+        assert_eq!(integer::<i8>(b"127"), Some(127));
+        assert_eq!(integer::<i8>(b"-128"), Some(-128));
+        assert_eq!(integer::<i8>(b"128"), None);
+        assert_eq!(integer::<i8>(b"-129"), None);
+        assert_eq!(integer::<i8>(b"-0"), Some(0));
+        assert_eq!(integer::<i8>(b"-"), None);
+        assert_eq!(integer::<i8>(b"--1"), None);
+        assert_eq!(integer::<i8>(b"1-"), None);
+        assert_eq!(integer::<i64>(b"-9223372036854775808"), Some(i64::MIN));
+        assert_eq!(integer::<i64>(b"9223372036854775808"), None);
+        assert_eq!(integer::<i128>(b"-42"), Some(-42));
+    }
+
+    #[test]
+    fn test_integers() {
+        let input = b"Sensor at x=2, y=-18: closest beacon is at x=-2, y=15\n2-4,6-8\n";
+        let mut lines = input.split(|byte| *byte == b'\n');
+        let (sensor, ranges) = (lines.next().unwrap(), lines.next().unwrap());
+
+        let values: Result<Vec<i32>, _> = integers(input, sensor).collect();
+        assert_eq!(values, Ok(vec![2, -18, -2, 15]));
+        let values: Result<Vec<i32>, _> = integers(input, ranges).collect();
+        assert_eq!(values, Ok(vec![2, 4, 6, 8]));
+        let values: Result<Vec<u32>, _> = integers(input, sensor).collect();
+        assert_eq!(values, Ok(vec![2, 18, 2, 15]));
+        assert_eq!(integers::<u8>(input, b"no numbers").count(), 0);
+
+        let input = b"move 3 from 300 to 1";
+        let error = integers::<u8>(input, input).nth(1).unwrap().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 13: expected a number of type `u8`, found `300`"
+        );
+    }
+
+    #[test]
+    fn test_integer_at() {
+        let input = b"12 -3 x";
+        assert_eq!(integer_at::<u8>(input, &input[0..2]), Ok(12));
+        assert_eq!(integer_at::<i8>(input, &input[3..5]), Ok(-3));
+        assert_eq!(integer_at::<u8>(input, &input[3..5]).unwrap_err().column, 4);
+        assert!(integer_at::<u8>(input, &input[6..]).is_err());
+    }
+
+    // The parsers that days 1, 4, 10 and 18 carried their own copies of, as they were: without
+    // any checks of the digits or of overflow.
+
+    mod day01 {
+        const POWERS_OF_TEN: [u32; 10] = [
+            1,
+            10,
+            100,
+            1000,
+            10_000,
+            100_000,
+            1_000_000,
+            10_000_000,
+            100_000_000,
+            1_000_000_000,
+        ];
+
+        pub fn u32_from_bytes(bytes: &[u8]) -> u32 {
+            bytes.iter().rev().enumerate().fold(0, |acc, (ix, x)| {
+                acc + (x - b'0') as u32 * POWERS_OF_TEN[ix]
+            })
+        }
+    }
+
+    mod day04 {
+        const POWERS_OF_TEN: [u8; 3] = [1, 10, 100];
+
+        pub fn u8_from_bytes(bytes: &[u8]) -> u8 {
+            bytes
+                .iter()
+                .rev()
+                .enumerate()
+                .fold(0, |acc, (ix, x)| acc + (x - b'0') * POWERS_OF_TEN[ix])
+        }
+    }
+
+    mod day10 {
+        const POWERS_OF_TEN: [i8; 3] = [1, 10, 100];
+
+        pub fn i8_from_bytes(bytes: &[u8]) -> i8 {
+            if bytes[0] == b'-' {
+                bytes
+                    .iter()
+                    .skip(1)
+                    .rev()
+                    .enumerate()
+                    .fold(0, |acc, (ix, x)| acc + (48 - *x as i8) * POWERS_OF_TEN[ix])
+            } else {
+                bytes
+                    .iter()
+                    .rev()
+                    .enumerate()
+                    .fold(0, |acc, (ix, x)| acc + (*x as i8 - 48) * POWERS_OF_TEN[ix])
+            }
+        }
+    }
+
+    mod day18 {
+        const POWERS_OF_TEN: [usize; 3] = [1, 10, 100];
+
+        pub fn usize_from_bytes(bytes: &[u8]) -> usize {
+            bytes
+                .iter()
+                .rev()
+                .enumerate()
+                .fold(0, |acc, (index, byte)| {
+                    acc + (byte - b'0') as usize * POWERS_OF_TEN[index]
+                })
+        }
+    }
+
+    /// The numbers of the inputs of days 1, 4, 10 and 18, which the copies were used for.
+    struct Numbers {
+        calories: Vec<&'static [u8]>,
+        sections: Vec<&'static [u8]>,
+        values: Vec<&'static [u8]>,
+        coordinates: Vec<&'static [u8]>,
+    }
+
+    impl Numbers {
+        fn new() -> Self {
+            let split = |input: &'static [u8], separators: &'static [u8]| {
+                input
+                    .split(|byte| separators.contains(byte))
+                    .filter(|number| !number.is_empty())
+                    .collect()
+            };
+            let values = include_bytes!("../inputs/day10.txt")
+                .split(|byte| *byte == b'\n')
+                .filter_map(|line| line.strip_prefix(b"addx "))
+                .collect();
+            Numbers {
+                calories: split(include_bytes!("../inputs/day01.txt"), b"\n"),
+                sections: split(include_bytes!("../inputs/day04.txt"), b"\n,-"),
+                values,
+                coordinates: split(include_bytes!("../inputs/day18.txt"), b"\n,"),
+            }
+        }
+    }
+
+    /// Adds up `numbers` after parsing them with `parse`.
+    fn sum<T: Into<i64>>(numbers: &[&[u8]], parse: impl Fn(&[u8]) -> T) -> i64 {
+        numbers
+            .iter()
+            .map(|number| parse(black_box(number)).into())
+            .fold(0, i64::wrapping_add)
+    }
+
+    #[test]
+    fn test_copies() {
+        let numbers = Numbers::new();
+        assert!(numbers.values.iter().any(|value| value.starts_with(b"-")));
+        assert_eq!(
+            sum(&numbers.calories, day01::u32_from_bytes),
+            sum(&numbers.calories, |number| integer::<u32>(number).unwrap())
+        );
+        assert_eq!(
+            sum(&numbers.sections, day04::u8_from_bytes),
+            sum(&numbers.sections, |number| integer::<u8>(number).unwrap())
+        );
+        assert_eq!(
+            sum(&numbers.values, day10::i8_from_bytes),
+            sum(&numbers.values, |number| integer::<i8>(number).unwrap())
+        );
+        assert_eq!(
+            sum(
+                &numbers.coordinates,
+                |number| day18::usize_from_bytes(number) as i64
+            ),
+            sum(&numbers.coordinates, |number| {
+                integer::<usize>(number).unwrap() as i64
+            })
+        );
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_integer() {
+        let numbers = Numbers::new();
+        bench("parse::integer::<u32>", || {
+            sum(&numbers.calories, |number| integer::<u32>(number).unwrap())
+        });
+        bench("parse::integer::<u8>", || {
+            sum(&numbers.sections, |number| integer::<u8>(number).unwrap())
+        });
+        bench("parse::integer::<i8>", || {
+            sum(&numbers.values, |number| integer::<i8>(number).unwrap())
+        });
+        bench("parse::integer::<usize>", || {
+            sum(&numbers.coordinates, |number| {
+                integer::<usize>(number).unwrap() as i64
+            })
+        });
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_copies() {
+        let numbers = Numbers::new();
+        bench("day01::u32_from_bytes", || {
+            sum(&numbers.calories, day01::u32_from_bytes)
+        });
+        bench("day04::u8_from_bytes", || {
+            sum(&numbers.sections, day04::u8_from_bytes)
+        });
+        bench("day10::i8_from_bytes", || {
+            sum(&numbers.values, day10::i8_from_bytes)
+        });
+        bench("day18::usize_from_bytes", || {
+            sum(&numbers.coordinates, |number| {
+                day18::usize_from_bytes(number) as i64
+            })
+        });
+    }
+}
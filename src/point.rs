@@ -0,0 +1,470 @@
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// Integer types usable as coordinates.
+pub trait Coordinate:
+    Copy + Ord + Add<Output = Self> + Sub<Output = Self> + Mul<Output = Self> + fmt::Debug
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+
+    /// The distance between two coordinates.
+    ///
+    /// # Panics
+    ///
+    /// If the distance does not fit into the type, e.g. from `i32::MIN` to `0`.
+    fn abs_diff(self, other: Self) -> Self;
+}
+
+/// Coordinates that can be negative, which is needed for offsets and rotations.
+pub trait Signed: Coordinate + Neg<Output = Self> {
+    fn signum(self) -> Self;
+}
+
+macro_rules! impl_coordinate {
+    ($($t:ty),*) => {$(
+        impl Coordinate for $t {
+            const ZERO: Self = 0;
+            const ONE: Self = 1;
+
+            #[inline]
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+
+            #[inline]
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+
+            #[inline]
+            fn abs_diff(self, other: Self) -> Self {
+                let distance = <$t>::abs_diff(self, other);
+                <$t>::try_from(distance).unwrap_or_else(|_| {
+                    panic!(
+                        "distance {} from {} to {} does not fit into {}",
+                        distance,
+                        self,
+                        other,
+                        stringify!($t)
+                    )
+                })
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_signed {
+    ($($t:ty),*) => {$(
+        impl Signed for $t {
+            #[inline]
+            fn signum(self) -> Self {
+                <$t>::signum(self)
+            }
+        }
+    )*};
+}
+
+impl_coordinate!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+impl_signed!(i8, i16, i32, i64, isize);
+
+/// One of the four orthogonal directions, with `y` growing downwards as in the puzzle inputs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Direction {
+    /// All directions, clockwise from up.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    pub fn turn_right(self) -> Self {
+        Self::ALL[(self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(self) -> Self {
+        Self::ALL[(self as usize + 3) % 4]
+    }
+
+    pub fn reverse(self) -> Self {
+        Self::ALL[(self as usize + 2) % 4]
+    }
+
+    /// The vector of one step in this direction.
+    #[inline]
+    pub fn offset<T: Signed>(self) -> Point2<T> {
+        match self {
+            Direction::Up => Point2::new(T::ZERO, -T::ONE),
+            Direction::Right => Point2::new(T::ONE, T::ZERO),
+            Direction::Down => Point2::new(T::ZERO, T::ONE),
+            Direction::Left => Point2::new(-T::ONE, T::ZERO),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point2<T> {
+    pub x: T,
+    pub y: T,
+}
+
+impl<T> Point2<T> {
+    pub const fn new(x: T, y: T) -> Self {
+        Point2 { x, y }
+    }
+}
+
+impl<T: Coordinate> Point2<T> {
+    pub const ORIGIN: Self = Point2::new(T::ZERO, T::ZERO);
+
+    /// # Panics
+    ///
+    /// If the distance does not fit into `T`.
+    #[inline]
+    pub fn manhattan(self, other: Self) -> T {
+        self.x
+            .abs_diff(other.x)
+            .checked_add(self.y.abs_diff(other.y))
+            .unwrap_or_else(|| panic!("distance from {:?} to {:?} overflows", self, other))
+    }
+
+    #[inline]
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x.abs_diff(other.x).max(self.y.abs_diff(other.y))
+    }
+
+    /// The neighbour in `direction`, or `None` if it is not representable, e.g. below zero for
+    /// unsigned coordinates.
+    #[inline]
+    pub fn step(self, direction: Direction) -> Option<Self> {
+        let Point2 { x, y } = self;
+        match direction {
+            Direction::Up => Some(Point2::new(x, y.checked_sub(T::ONE)?)),
+            Direction::Right => Some(Point2::new(x.checked_add(T::ONE)?, y)),
+            Direction::Down => Some(Point2::new(x, y.checked_add(T::ONE)?)),
+            Direction::Left => Some(Point2::new(x.checked_sub(T::ONE)?, y)),
+        }
+    }
+
+    /// The orthogonal neighbours, clockwise from up.
+    #[inline]
+    pub fn neighbours_4(self) -> impl Iterator<Item = Self> {
+        Direction::ALL
+            .into_iter()
+            .filter_map(move |direction| self.step(direction))
+    }
+
+    /// The orthogonal and diagonal neighbours, clockwise from up.
+    #[inline]
+    pub fn neighbours_8(self) -> impl Iterator<Item = Self> {
+        Direction::ALL.into_iter().flat_map(move |direction| {
+            let side = self.step(direction);
+            let corner = side.and_then(|side| side.step(direction.turn_right()));
+            [side, corner].into_iter().flatten()
+        })
+    }
+}
+
+impl<T: Signed> Point2<T> {
+    /// Rotates by a quarter turn around the origin, clockwise as seen with `y` growing downwards.
+    pub fn rotate_right(self) -> Self {
+        Point2::new(-self.y, self.x)
+    }
+
+    /// Rotates by a quarter turn around the origin, counterclockwise as seen with `y` growing
+    /// downwards.
+    pub fn rotate_left(self) -> Self {
+        Point2::new(self.y, -self.x)
+    }
+
+    /// The signs of the components, i.e. the step of at most one in each axis towards `self`.
+    pub fn signum(self) -> Self {
+        Point2::new(self.x.signum(), self.y.signum())
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point2<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point2::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point2<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point2::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point2<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point2::new(-self.x, -self.y)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point2<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Point2::new(self.x * factor, self.y * factor)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point2<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point2<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point2<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Point3<T> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T> Point3<T> {
+    pub const fn new(x: T, y: T, z: T) -> Self {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: Coordinate> Point3<T> {
+    pub const ORIGIN: Self = Point3::new(T::ZERO, T::ZERO, T::ZERO);
+
+    /// # Panics
+    ///
+    /// If the distance does not fit into `T`.
+    #[inline]
+    pub fn manhattan(self, other: Self) -> T {
+        self.x
+            .abs_diff(other.x)
+            .checked_add(self.y.abs_diff(other.y))
+            .and_then(|distance| distance.checked_add(self.z.abs_diff(other.z)))
+            .unwrap_or_else(|| panic!("distance from {:?} to {:?} overflows", self, other))
+    }
+
+    #[inline]
+    pub fn chebyshev(self, other: Self) -> T {
+        self.x
+            .abs_diff(other.x)
+            .max(self.y.abs_diff(other.y))
+            .max(self.z.abs_diff(other.z))
+    }
+
+    /// The neighbours sharing a face, in the order -x, +x, -y, +y, -z, +z, without those that
+    /// are not representable.
+    #[inline]
+    pub fn neighbours_6(self) -> impl Iterator<Item = Self> {
+        let Point3 { x, y, z } = self;
+        let one = T::ONE;
+        [
+            x.checked_sub(one).map(|x| Point3::new(x, y, z)),
+            x.checked_add(one).map(|x| Point3::new(x, y, z)),
+            y.checked_sub(one).map(|y| Point3::new(x, y, z)),
+            y.checked_add(one).map(|y| Point3::new(x, y, z)),
+            z.checked_sub(one).map(|z| Point3::new(x, y, z)),
+            z.checked_add(one).map(|z| Point3::new(x, y, z)),
+        ]
+        .into_iter()
+        .flatten()
+    }
+}
+
+impl<T: Signed> Point3<T> {
+    pub fn cross(self, other: Self) -> Self {
+        Point3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    pub fn dot(self, other: Self) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// Rotates by a quarter turn around `axis`, a unit vector along one of the axes,
+    /// counterclockwise when looking from the tip of `axis` towards the origin.
+    pub fn rotate_around(self, axis: Self) -> Self {
+        // Rodrigues' formula for a right angle: v' = k (k·v) + k × v
+        axis * axis.dot(self) + axis.cross(self)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point3<T> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        Point3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Sub<Output = T>> Sub for Point3<T> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        Point3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Neg<Output = T>> Neg for Point3<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self {
+        Point3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point3<T> {
+    type Output = Self;
+
+    fn mul(self, factor: T) -> Self {
+        Point3::new(self.x * factor, self.y * factor, self.z * factor)
+    }
+}
+
+impl<T: Copy + Add<Output = T>> AddAssign for Point3<T> {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Copy + Sub<Output = T>> SubAssign for Point3<T> {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_arithmetic() {
+        let (a, b) = (Point2::new(3, -1), Point2::new(-2, 5));
+        assert_eq!(a + b, Point2::new(1, 4));
+        assert_eq!(a - b, Point2::new(5, -6));
+        assert_eq!(-a, Point2::new(-3, 1));
+        assert_eq!(a * 2, Point2::new(6, -2));
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        assert_eq!((a - b).signum(), Point2::new(1, -1));
+        assert_eq!(a.to_string(), "(3, -1)");
+
+        let p = Point3::new(1, 2, 3);
+        assert_eq!(p + p * 2 - Point3::new(3, 6, 9), Point3::ORIGIN);
+        assert_eq!(-p, Point3::new(-1, -2, -3));
+    }
+
+    #[test]
+    fn test_distance() {
+        let (a, b) = (Point2::new(3_i32, -1), Point2::new(-2, 5));
+        assert_eq!(a.manhattan(b), 11);
+        assert_eq!(a.chebyshev(b), 6);
+        let (a, b) = (Point2::new(3_u8, 1), Point2::new(1, 5));
+        assert_eq!((a.manhattan(b), b.manhattan(a)), (6, 6));
+        let (a, b) = (Point3::new(1_usize, 2, 3), Point3::new(3, 2, 0));
+        assert_eq!((a.manhattan(b), a.chebyshev(b)), (5, 3));
+
+        let (a, b) = (Point2::new(i32::MIN, 0), Point2::new(-1, i32::MAX));
+        assert_eq!((a.chebyshev(b), b.chebyshev(a)), (i32::MAX, i32::MAX));
+        assert_eq!(a.manhattan(Point2::new(-1, 0)), i32::MAX);
+        let (a, b) = (Point3::new(i64::MAX, 0, 0), Point3::new(0, i64::MIN + 1, 0));
+        assert_eq!(a.chebyshev(b), i64::MAX);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit into i32")]
+    fn test_distance_overflow() {
+        Point2::new(i32::MIN, 0).chebyshev(Point2::new(i32::MAX, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "overflows")]
+    fn test_manhattan_overflow() {
+        Point3::new(i8::MIN + 1, 0, 0).manhattan(Point3::new(0, 1, 0));
+    }
+
+    #[test]
+    fn test_directions() {
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Up.turn_left(), Direction::Left);
+        assert_eq!(Direction::Left.reverse(), Direction::Right);
+        for direction in Direction::ALL {
+            let offset = direction.offset::<i32>();
+            assert_eq!(offset.rotate_right(), direction.turn_right().offset());
+            assert_eq!(offset.rotate_left(), direction.turn_left().offset());
+            assert_eq!(
+                Point2::new(4, 4).step(direction),
+                Some(Point2::new(4, 4) + offset)
+            );
+        }
+        assert_eq!(Point2::new(0_usize, 3).step(Direction::Left), None);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let corner: Vec<_> = Point2::new(0_usize, 0).neighbours_4().collect();
+        assert_eq!(corner, [Point2::new(1, 0), Point2::new(0, 1)]);
+        let around: Vec<_> = Point2::new(0_i8, 0).neighbours_8().collect();
+        assert_eq!(around.len(), 8);
+        assert!(around.iter().all(|p| p.chebyshev(Point2::ORIGIN) == 1));
+        assert_eq!(Point2::new(0_usize, 0).neighbours_8().count(), 3);
+
+        assert_eq!(Point3::new(1_i16, 1, 1).neighbours_6().count(), 6);
+        assert_eq!(Point3::new(0_u8, 4, 0).neighbours_6().count(), 4);
+    }
+
+    #[test]
+    fn test_rotation_3d() {
+        let (x, y, z) = (
+            Point3::new(1, 0, 0),
+            Point3::new(0, 1, 0),
+            Point3::new(0, 0, 1),
+        );
+        assert_eq!(x.cross(y), z);
+        assert_eq!(x.rotate_around(z), y);
+        assert_eq!(y.rotate_around(x), z);
+        assert_eq!(z.rotate_around(y), x);
+        assert_eq!(z.rotate_around(z), z);
+        let p = Point3::new(2, -3, 5);
+        assert_eq!((0..4).fold(p, |p, _| p.rotate_around(y)), p);
+    }
+}
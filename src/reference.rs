@@ -0,0 +1,1386 @@
+//! Slow but straightforward solutions of every day, written from the puzzle descriptions with no
+//! tricks beyond the obvious, to check the optimized solutions against on small generated inputs.
+//!
+//! They panic on inputs that the puzzle rules out, e.g. a day 22 map that does not fold into a
+//! cube, which lets [`crate::differential`] tell valid inputs from invalid ones. Inputs that merely
+//! have no answer, e.g. a valley without a way through, give [`Answer::None`] like the solutions.
+//! A day that `new` just created has no reference solution yet, and is skipped until it is written.
+
+use crate::gen::{self, Rng};
+use crate::solution::{Answer, DayParams};
+use crate::{day07, day13, day15, day16, day17, day19, day20};
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// The reference solution of a day, and the inputs and parameters to compare it on.
+pub struct Reference {
+    pub day: u8,
+    /// Size of the largest generated inputs, small enough for the reference solution.
+    pub size: usize,
+    /// Parameters for both solutions where the puzzle's values take too long here.
+    pub params: &'static [(&'static str, &'static str)],
+    /// Generates inputs valid for `params`, if the generator of the day does not.
+    generate: Option<fn(&mut Rng, usize) -> String>,
+    solve: Option<fn(&str, &DayParams) -> [Answer; 2]>,
+}
+
+impl Reference {
+    pub(crate) const fn new(
+        day: u8,
+        size: usize,
+        solve: fn(&str, &DayParams) -> [Answer; 2],
+    ) -> Self {
+        Reference {
+            day,
+            size,
+            params: &[],
+            generate: None,
+            solve: Some(solve),
+        }
+    }
+
+    /// A day whose reference solution is not written yet.
+    pub const fn unwritten(day: u8) -> Self {
+        Reference {
+            day,
+            size: 1,
+            params: &[],
+            generate: None,
+            solve: None,
+        }
+    }
+
+    const fn with_params(self, params: &'static [(&'static str, &'static str)]) -> Self {
+        Reference { params, ..self }
+    }
+
+    const fn with_generator(self, generate: fn(&mut Rng, usize) -> String) -> Self {
+        Reference {
+            generate: Some(generate),
+            ..self
+        }
+    }
+
+    /// The generated input for `seed` and `size`.
+    pub fn input(&self, seed: u64, size: usize) -> String {
+        match self.generate {
+            Some(generate) => generate(&mut Rng::new(seed), size),
+            None => gen::generator(self.day)
+                .expect("a generator for every day")
+                .generate(seed, Some(size)),
+        }
+    }
+
+    /// [`Reference::params`] as they are passed to [`crate::solution::Day::params`].
+    pub fn values(&self) -> Vec<(String, String)> {
+        self.params
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    /// Whether the day has a reference solution to compare with.
+    pub fn is_written(&self) -> bool {
+        self.solve.is_some()
+    }
+
+    /// The answers of both parts, or `None` if the day has no reference solution yet. Panics if
+    /// the input is not valid for the puzzle.
+    pub fn solve(&self, input: &str, params: &DayParams) -> Option<[Answer; 2]> {
+        self.solve.map(|solve| solve(input, params))
+    }
+}
+
+/// The reference solutions of all days, ordered by day.
+pub static REFERENCES: &[Reference] = &[
+    Reference::new(1, 20, day01),
+    Reference::new(2, 50, day02),
+    Reference::new(3, 10, day03),
+    Reference::new(4, 50, day04),
+    Reference::new(5, 30, day05),
+    Reference::new(6, 60, day06),
+    Reference::new(7, 20, day07),
+    Reference::new(8, 12, day08),
+    Reference::new(9, 40, day09),
+    Reference::new(10, 240, day10),
+    Reference::new(11, 6, day11),
+    Reference::new(12, 40, day12),
+    Reference::new(13, 20, day13),
+    Reference::new(14, 15, day14),
+    Reference::new(15, 6, day15)
+        .with_params(&[("row", "10"), ("max_coordinate", "20")])
+        .with_generator(day15_input),
+    Reference::new(16, 5, day16),
+    Reference::new(17, 40, day17).with_params(&[("part2_rocks", "10000")]),
+    Reference::new(18, 6, day18),
+    Reference::new(19, 3, day19)
+        .with_params(&[("part1_minutes", "12"), ("part2_minutes", "14")])
+        .with_generator(day19_input),
+    Reference::new(20, 30, day20),
+    Reference::new(21, 80, day21),
+    Reference::new(22, 3, day22),
+    Reference::new(23, 8, day23),
+    Reference::new(24, 12, day24),
+    Reference::new(25, 20, day25),
+];
+
+pub fn reference(day: u8) -> Option<&'static Reference> {
+    REFERENCES.iter().find(|reference| reference.day == day)
+}
+
+/// All integers in `text`, with a `-` in front of them as their sign.
+fn integers(text: &str) -> Vec<i64> {
+    let mut integers = Vec::new();
+    let mut digits = String::new();
+    for char in text.chars().chain([' ']) {
+        if char.is_ascii_digit() || (char == '-' && digits.is_empty()) {
+            digits.push(char);
+        } else {
+            if let Ok(integer) = digits.parse() {
+                integers.push(integer);
+            }
+            digits.clear();
+            if char == '-' {
+                digits.push(char);
+            }
+        }
+    }
+    integers
+}
+
+fn day01(input: &str, _: &DayParams) -> [Answer; 2] {
+    let mut elves: Vec<i64> = input
+        .split("\n\n")
+        .map(|elf| elf.lines().map(|line| line.parse::<i64>().unwrap()).sum())
+        .collect();
+    elves.sort_unstable();
+    elves.reverse();
+    [elves[0].into(), elves.iter().take(3).sum::<i64>().into()]
+}
+
+fn day02(input: &str, _: &DayParams) -> [Answer; 2] {
+    // Rock, paper and scissors are 0, 1 and 2, and each shape beats the one before it.
+    let outcome = |opponent: i64, me: i64| match (me - opponent).rem_euclid(3) {
+        0 => 3,
+        1 => 6,
+        _ => 0,
+    };
+    let (mut part1, mut part2) = (0, 0);
+    for line in input.lines() {
+        let bytes = line.as_bytes();
+        let (opponent, column) = ((bytes[0] - b'A') as i64, (bytes[2] - b'X') as i64);
+        part1 += column + 1 + outcome(opponent, column);
+        // Lose, draw or win.
+        let me = (opponent + column - 1).rem_euclid(3);
+        part2 += me + 1 + outcome(opponent, me);
+    }
+    [part1.into(), part2.into()]
+}
+
+fn day03(input: &str, _: &DayParams) -> [Answer; 2] {
+    let priority = |item: char| match item {
+        'a'..='z' => item as i64 - 'a' as i64 + 1,
+        _ => item as i64 - 'A' as i64 + 27,
+    };
+    let rucksacks: Vec<&str> = input.lines().collect();
+    let part1: i64 = rucksacks
+        .iter()
+        .map(|rucksack| {
+            let (first, second) = rucksack.split_at(rucksack.len() / 2);
+            priority(first.chars().find(|item| second.contains(*item)).unwrap())
+        })
+        .sum();
+    let part2: i64 = rucksacks
+        .chunks_exact(3)
+        .map(|group| {
+            let badge = group[0]
+                .chars()
+                .find(|item| group[1].contains(*item) && group[2].contains(*item));
+            priority(badge.unwrap())
+        })
+        .sum();
+    [part1.into(), part2.into()]
+}
+
+fn day04(input: &str, _: &DayParams) -> [Answer; 2] {
+    let (mut part1, mut part2) = (0_i64, 0_i64);
+    for line in input.lines() {
+        let [a, b, c, d] = integers(&line.replace('-', " ")).try_into().unwrap();
+        let first: HashSet<i64> = (a..=b).collect();
+        let second: HashSet<i64> = (c..=d).collect();
+        if first.is_subset(&second) || second.is_subset(&first) {
+            part1 += 1;
+        }
+        if !first.is_disjoint(&second) {
+            part2 += 1;
+        }
+    }
+    [part1.into(), part2.into()]
+}
+
+fn day05(input: &str, _: &DayParams) -> [Answer; 2] {
+    let (drawing, procedure) = input.split_once("\n\n").unwrap();
+    let mut lines: Vec<&str> = drawing.lines().collect();
+    let count = integers(lines.pop().unwrap()).len();
+    let mut stacks: Vec<Vec<char>> = vec![Vec::new(); count];
+    for line in lines.iter().rev() {
+        for (index, stack) in stacks.iter_mut().enumerate() {
+            match line.chars().nth(4 * index + 1) {
+                Some(' ') | None => {}
+                Some(crate_) => stack.push(crate_),
+            }
+        }
+    }
+
+    let mut crane = [stacks.clone(), stacks];
+    for line in procedure.lines() {
+        let [count, from, to] = integers(line).try_into().unwrap();
+        let (count, from, to) = (count as usize, from as usize - 1, to as usize - 1);
+        // The first crane moves one crate at a time, the second all of them at once.
+        for _ in 0..count {
+            let crate_ = crane[0][from].pop().unwrap();
+            crane[0][to].push(crate_);
+        }
+        let remaining = crane[1][from].len() - count;
+        let crates = crane[1][from].split_off(remaining);
+        crane[1][to].extend(crates);
+    }
+    crane.map(|stacks| {
+        let tops: String = stacks.iter().map(|stack| *stack.last().unwrap()).collect();
+        Answer::Text(tops)
+    })
+}
+
+fn day06(input: &str, _: &DayParams) -> [Answer; 2] {
+    let signal: Vec<char> = input.trim().chars().collect();
+    [4, 14].map(|length| {
+        let end = (length..=signal.len()).find(|end| {
+            let window: HashSet<char> = signal[end - length..*end].iter().copied().collect();
+            window.len() == length
+        });
+        end.into()
+    })
+}
+
+fn day07(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day07::Params = params.get();
+    let mut sizes: HashMap<Vec<&str>, usize> = HashMap::from([(Vec::new(), 0)]);
+    let mut path: Vec<&str> = Vec::new();
+    for line in input.lines() {
+        let words: Vec<&str> = line.split(' ').collect();
+        match words[..] {
+            ["$", "cd", "/"] => path.clear(),
+            ["$", "cd", ".."] => {
+                path.pop();
+            }
+            ["$", "cd", dir] => path.push(dir),
+            ["$", "ls"] => {}
+            ["dir", dir] => {
+                let mut dir_path = path.clone();
+                dir_path.push(dir);
+                sizes.entry(dir_path).or_insert(0);
+            }
+            [size, _] => {
+                // Every file counts towards all directories above it.
+                for depth in 0..=path.len() {
+                    *sizes.entry(path[..depth].to_vec()).or_insert(0) +=
+                        size.parse::<usize>().unwrap();
+                }
+            }
+            _ => panic!("unexpected line `{}`", line),
+        }
+    }
+
+    let part1: usize = sizes
+        .values()
+        .filter(|size| **size <= params.max_size)
+        .sum();
+    let free = params.disk_size as i64 - sizes[&Vec::new()] as i64;
+    let part2 = sizes
+        .values()
+        .filter(|size| free + **size as i64 >= params.required_space as i64)
+        .min()
+        .copied();
+    [part1.into(), part2.into()]
+}
+
+fn day08(input: &str, _: &DayParams) -> [Answer; 2] {
+    let trees: Vec<Vec<u8>> = input.lines().map(|line| line.bytes().collect()).collect();
+    let (rows, cols) = (trees.len() as i64, trees[0].len() as i64);
+    let (mut visible, mut best) = (0_i64, 0_i64);
+    for row in 0..rows {
+        for col in 0..cols {
+            let height = trees[row as usize][col as usize];
+            let mut score = 1;
+            let mut seen = false;
+            for (dr, dc) in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+                let (mut r, mut c, mut distance) = (row + dr, col + dc, 0);
+                let mut blocked = false;
+                while (0..rows).contains(&r) && (0..cols).contains(&c) {
+                    distance += 1;
+                    if trees[r as usize][c as usize] >= height {
+                        blocked = true;
+                        break;
+                    }
+                    (r, c) = (r + dr, c + dc);
+                }
+                seen |= !blocked;
+                score *= distance;
+            }
+            visible += i64::from(seen);
+            best = best.max(score);
+        }
+    }
+    [visible.into(), best.into()]
+}
+
+fn day09(input: &str, _: &DayParams) -> [Answer; 2] {
+    [2, 10].map(|length| {
+        let mut knots = vec![(0_i64, 0_i64); length];
+        let mut visited = HashSet::from([(0, 0)]);
+        for line in input.lines() {
+            let (direction, steps) = line.split_once(' ').unwrap();
+            let (dx, dy) = match direction {
+                "U" => (0, 1),
+                "D" => (0, -1),
+                "L" => (-1, 0),
+                _ => (1, 0),
+            };
+            for _ in 0..steps.parse::<usize>().unwrap() {
+                knots[0] = (knots[0].0 + dx, knots[0].1 + dy);
+                for index in 1..length {
+                    let (ahead, knot) = (knots[index - 1], knots[index]);
+                    if (ahead.0 - knot.0).abs() > 1 || (ahead.1 - knot.1).abs() > 1 {
+                        knots[index] = (
+                            knot.0 + (ahead.0 - knot.0).signum(),
+                            knot.1 + (ahead.1 - knot.1).signum(),
+                        );
+                    }
+                }
+                visited.insert(knots[length - 1]);
+            }
+        }
+        visited.len().into()
+    })
+}
+
+fn day10(input: &str, _: &DayParams) -> [Answer; 2] {
+    // The value of the register during every cycle, from the first.
+    let mut register = vec![1_i64];
+    for line in input.lines() {
+        let x = *register.last().unwrap();
+        register.push(x);
+        if let Some(value) = line.strip_prefix("addx ") {
+            register.push(x + value.parse::<i64>().unwrap());
+        }
+    }
+    let during = |cycle: usize| register[cycle - 1];
+
+    let part1: i64 = [20, 60, 100, 140, 180, 220]
+        .into_iter()
+        .filter(|cycle| *cycle < register.len())
+        .map(|cycle| cycle as i64 * during(cycle))
+        .sum();
+    let mut screen = String::new();
+    for cycle in 1..=240 {
+        let col = (cycle - 1) as i64 % 40;
+        let lit = cycle < register.len() && (col - during(cycle)).abs() <= 1;
+        screen.push(if lit { '#' } else { '.' });
+        if col == 39 {
+            screen.push('\n');
+        }
+    }
+    [part1.into(), Answer::Art(screen)]
+}
+
+fn day11(input: &str, _: &DayParams) -> [Answer; 2] {
+    struct Monkey {
+        items: Vec<u128>,
+        operation: Vec<String>,
+        divisor: u128,
+        targets: [usize; 2],
+    }
+
+    let monkeys: Vec<Monkey> = input
+        .split("\n\n")
+        .map(|block| {
+            let lines: Vec<&str> = block.lines().collect();
+            let (_, operation) = lines[2].split_once("new = ").unwrap();
+            Monkey {
+                items: integers(lines[1])
+                    .iter()
+                    .map(|item| *item as u128)
+                    .collect(),
+                operation: operation.split(' ').map(String::from).collect(),
+                divisor: integers(lines[3])[0] as u128,
+                targets: [
+                    integers(lines[4])[0] as usize,
+                    integers(lines[5])[0] as usize,
+                ],
+            }
+        })
+        .collect();
+    // Any multiple of all divisors keeps the tests the same.
+    let product: u128 = monkeys.iter().map(|monkey| monkey.divisor).product();
+
+    [(20, true), (10_000, false)].map(|(rounds, relief)| {
+        let mut items: Vec<Vec<u128>> = monkeys.iter().map(|monkey| monkey.items.clone()).collect();
+        let mut inspected = vec![0_i64; monkeys.len()];
+        for _ in 0..rounds {
+            for (index, monkey) in monkeys.iter().enumerate() {
+                for old in std::mem::take(&mut items[index]) {
+                    inspected[index] += 1;
+                    let operand = |word: &str| match word {
+                        "old" => old,
+                        _ => word.parse().unwrap(),
+                    };
+                    let (left, right) =
+                        (operand(&monkey.operation[0]), operand(&monkey.operation[2]));
+                    let new = match monkey.operation[1].as_str() {
+                        "+" => left + right,
+                        _ => left * right,
+                    };
+                    let new = if relief { new / 3 } else { new % product };
+                    let target = monkey.targets[usize::from(new % monkey.divisor != 0)];
+                    items[target].push(new);
+                }
+            }
+        }
+        inspected.sort_unstable();
+        inspected.reverse();
+        (inspected[0] * inspected[1]).into()
+    })
+}
+
+fn day12(input: &str, _: &DayParams) -> [Answer; 2] {
+    let squares: Vec<Vec<u8>> = input.lines().map(|line| line.bytes().collect()).collect();
+    let elevation = |square: u8| match square {
+        b'S' => b'a',
+        b'E' => b'z',
+        _ => square,
+    };
+    let steps_to_end = |start: (usize, usize)| {
+        let mut steps = HashMap::from([(start, 0)]);
+        let mut queue = VecDeque::from([start]);
+        while let Some((row, col)) = queue.pop_front() {
+            if squares[row][col] == b'E' {
+                return Some(steps[&(row, col)]);
+            }
+            let neighbours = [
+                (row.wrapping_sub(1), col),
+                (row + 1, col),
+                (row, col.wrapping_sub(1)),
+                (row, col + 1),
+            ];
+            for (next_row, next_col) in neighbours {
+                let Some(next) = squares.get(next_row).and_then(|row| row.get(next_col)) else {
+                    continue;
+                };
+                if elevation(*next) <= elevation(squares[row][col]) + 1
+                    && !steps.contains_key(&(next_row, next_col))
+                {
+                    steps.insert((next_row, next_col), steps[&(row, col)] + 1);
+                    queue.push_back((next_row, next_col));
+                }
+            }
+        }
+        None
+    };
+
+    let mut starts = Vec::new();
+    let mut part1 = None;
+    for (row, line) in squares.iter().enumerate() {
+        for (col, square) in line.iter().enumerate() {
+            if *square == b'S' {
+                part1 = steps_to_end((row, col));
+            }
+            if elevation(*square) == b'a' {
+                starts.push((row, col));
+            }
+        }
+    }
+    let part2 = starts.into_iter().filter_map(steps_to_end).min();
+    [part1.into(), part2.into()]
+}
+
+#[derive(Clone, Debug)]
+enum Packet {
+    Integer(u32),
+    List(Vec<Packet>),
+}
+
+fn parse_packet(bytes: &[u8], index: &mut usize) -> Packet {
+    if bytes[*index] == b'[' {
+        *index += 1;
+        let mut packets = Vec::new();
+        while bytes[*index] != b']' {
+            packets.push(parse_packet(bytes, index));
+            if bytes[*index] == b',' {
+                *index += 1;
+            }
+        }
+        *index += 1;
+        Packet::List(packets)
+    } else {
+        let start = *index;
+        while bytes[*index].is_ascii_digit() {
+            *index += 1;
+        }
+        Packet::Integer(
+            String::from_utf8_lossy(&bytes[start..*index])
+                .parse()
+                .unwrap(),
+        )
+    }
+}
+
+fn compare(left: &Packet, right: &Packet) -> Ordering {
+    match (left, right) {
+        (Packet::Integer(left), Packet::Integer(right)) => left.cmp(right),
+        (Packet::Integer(_), Packet::List(_)) => compare(&Packet::List(vec![left.clone()]), right),
+        (Packet::List(_), Packet::Integer(_)) => compare(left, &Packet::List(vec![right.clone()])),
+        (Packet::List(left), Packet::List(right)) => {
+            for (left, right) in left.iter().zip(right) {
+                match compare(left, right) {
+                    Ordering::Equal => {}
+                    ordering => return ordering,
+                }
+            }
+            left.len().cmp(&right.len())
+        }
+    }
+}
+
+fn day13(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day13::Params = params.get();
+    let parse = |line: &str| parse_packet(line.as_bytes(), &mut 0);
+    let mut packets: Vec<Packet> = input
+        .lines()
+        .filter(|line| !line.is_empty())
+        .map(parse)
+        .collect();
+
+    let part1: usize = packets
+        .chunks(2)
+        .enumerate()
+        .filter(|(_, pair)| compare(&pair[0], &pair[1]) == Ordering::Less)
+        .map(|(index, _)| index + 1)
+        .sum();
+
+    let dividers =
+        [&params.first_divider, &params.second_divider].map(|divider| divider.to_string());
+    packets.extend(dividers.iter().map(|divider| parse(divider)));
+    let mut order: Vec<usize> = (0..packets.len()).collect();
+    order.sort_by(|left, right| compare(&packets[*left], &packets[*right]));
+    // The dividers were added last.
+    let part2: usize = order
+        .iter()
+        .enumerate()
+        .filter(|(_, packet)| **packet >= packets.len() - 2)
+        .map(|(index, _)| index + 1)
+        .product();
+    [part1.into(), part2.into()]
+}
+
+fn day14(input: &str, _: &DayParams) -> [Answer; 2] {
+    let mut rocks = Vec::new();
+    for line in input.lines() {
+        let points: Vec<(i64, i64)> = line
+            .split(" -> ")
+            .map(|point| {
+                let (x, y) = point.split_once(',').unwrap();
+                (x.parse().unwrap(), y.parse().unwrap())
+            })
+            .collect();
+        for pair in points.windows(2) {
+            let ((x1, y1), (x2, y2)) = (pair[0], pair[1]);
+            for x in x1.min(x2)..=x1.max(x2) {
+                for y in y1.min(y2)..=y1.max(y2) {
+                    rocks.push((x, y));
+                }
+            }
+        }
+    }
+    let bottom = rocks.iter().map(|rock| rock.1).max().unwrap();
+
+    // Sand falls at most one tile sideways for every tile down, so this is all of the cave that
+    // it can get to, down to the floor.
+    let (left, width, height) = (500 - bottom - 2, 2 * bottom + 5, bottom + 2);
+    let mut cave = vec![vec![false; width as usize]; height as usize];
+    for (x, y) in rocks {
+        if (left..left + width).contains(&x) && (0..height).contains(&y) {
+            cave[y as usize][(x - left) as usize] = true;
+        }
+    }
+    let blocked = |cave: &[Vec<bool>], (x, y): (i64, i64)| {
+        y >= height || cave[y as usize][(x - left) as usize]
+    };
+
+    [false, true].map(|floor| {
+        let mut cave = cave.clone();
+        let mut resting = 0_i64;
+        loop {
+            let (mut x, mut y) = (500, 0);
+            if blocked(&cave, (x, y)) {
+                assert!(floor, "the sand never falls into the abyss");
+                break;
+            }
+            while let Some(next) = [(x, y + 1), (x - 1, y + 1), (x + 1, y + 1)]
+                .into_iter()
+                .find(|next| !blocked(&cave, *next))
+            {
+                (x, y) = next;
+            }
+            if !floor && y > bottom {
+                break;
+            }
+            cave[y as usize][(x - left) as usize] = true;
+            resting += 1;
+        }
+        resting.into()
+    })
+}
+
+fn day15_input(rng: &mut Rng, size: usize) -> String {
+    gen::sensors(rng, size, 20)
+}
+
+fn day15(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day15::Params = params.get();
+    let sensors: Vec<[i64; 4]> = input
+        .lines()
+        .map(|line| integers(line).try_into().unwrap())
+        .collect();
+    let distance = |x1: i64, y1: i64, x2: i64, y2: i64| (x1 - x2).abs() + (y1 - y2).abs();
+    let covered = |x: i64, y: i64| {
+        sensors
+            .iter()
+            .any(|[sx, sy, bx, by]| distance(x, y, *sx, *sy) <= distance(*sx, *sy, *bx, *by))
+    };
+
+    let reach: i64 = sensors
+        .iter()
+        .map(|[sx, sy, bx, by]| sx.abs() + distance(*sx, *sy, *bx, *by))
+        .max()
+        .unwrap();
+    let row = params.row as i64;
+    let part1 = (-reach..=reach)
+        .filter(|x| covered(*x, row))
+        .filter(|x| !sensors.iter().any(|[_, _, bx, by]| (bx, by) == (x, &row)))
+        .count();
+
+    let max = params.max_coordinate as i64;
+    let part2 = (0..=max)
+        .flat_map(|x| (0..=max).map(move |y| (x, y)))
+        .find(|(x, y)| !covered(*x, *y))
+        .map(|(x, y)| 4_000_000 * x + y);
+    [part1.into(), part2.into()]
+}
+
+fn day16(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day16::Params = params.get();
+    let mut names = Vec::new();
+    let mut rates = Vec::new();
+    let mut neighbours: Vec<Vec<&str>> = Vec::new();
+    for line in input.lines() {
+        names.push(&line[6..8]);
+        rates.push(integers(line)[0]);
+        let (_, valves) = line.split_once("to valve").unwrap();
+        neighbours.push(valves.trim_start_matches('s').trim().split(", ").collect());
+    }
+    let index = |name: &str| names.iter().position(|other| *other == name).unwrap();
+
+    // The minutes from every valve to every other.
+    let distances: Vec<Vec<i64>> = (0..names.len())
+        .map(|start| {
+            let mut distances = vec![i64::MAX; names.len()];
+            distances[start] = 0;
+            let mut queue = VecDeque::from([start]);
+            while let Some(valve) = queue.pop_front() {
+                for next in neighbours[valve].iter().map(|name| index(name)) {
+                    if distances[next] == i64::MAX {
+                        distances[next] = distances[valve] + 1;
+                        queue.push_back(next);
+                    }
+                }
+            }
+            distances
+        })
+        .collect();
+    let working: Vec<usize> = (0..names.len()).filter(|valve| rates[*valve] > 0).collect();
+
+    /// The most pressure released by opening the valves in `closed` in any order.
+    fn release(
+        valve: usize,
+        minutes: i64,
+        closed: &[usize],
+        rates: &[i64],
+        distances: &[Vec<i64>],
+    ) -> i64 {
+        let mut best = 0;
+        for (index, next) in closed.iter().enumerate() {
+            let left = minutes - distances[valve][*next] - 1;
+            if left > 0 {
+                let mut rest = closed.to_vec();
+                rest.remove(index);
+                let released = rates[*next] * left + release(*next, left, &rest, rates, distances);
+                best = best.max(released);
+            }
+        }
+        best
+    }
+
+    let start = index("AA");
+    let part1 = release(
+        start,
+        params.part1_minutes as i64,
+        &working,
+        &rates,
+        &distances,
+    );
+    let part2 = (0..1 << working.len())
+        .map(|mask: usize| {
+            let (mine, elephant): (Vec<usize>, Vec<usize>) =
+                (0..working.len()).partition(|index| mask & (1 << index) != 0);
+            let valves = |indices: Vec<usize>| -> Vec<usize> {
+                indices.into_iter().map(|index| working[index]).collect()
+            };
+            let minutes = params.part2_minutes as i64;
+            release(start, minutes, &valves(mine), &rates, &distances)
+                + release(start, minutes, &valves(elephant), &rates, &distances)
+        })
+        .max()
+        .unwrap();
+    [part1.into(), part2.into()]
+}
+
+fn day17(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day17::Params = params.get();
+    let rocks: [&[(i64, i64)]; 5] = [
+        &[(0, 0), (1, 0), (2, 0), (3, 0)],
+        &[(1, 0), (0, 1), (1, 1), (2, 1), (1, 2)],
+        &[(0, 0), (1, 0), (2, 0), (2, 1), (2, 2)],
+        &[(0, 0), (0, 1), (0, 2), (0, 3)],
+        &[(0, 0), (1, 0), (0, 1), (1, 1)],
+    ];
+    let jets: Vec<i64> = input
+        .trim()
+        .bytes()
+        .map(|jet| if jet == b'<' { -1 } else { 1 })
+        .collect();
+
+    // The height of the tower after every number of rocks.
+    let mut heights = vec![0];
+    let mut stopped: HashSet<(i64, i64)> = HashSet::new();
+    let mut jet = 0;
+    let free = |stopped: &HashSet<(i64, i64)>, rock: &[(i64, i64)], x: i64, y: i64| {
+        rock.iter().all(|(dx, dy)| {
+            (0..7).contains(&(x + dx)) && y + dy >= 0 && !stopped.contains(&(x + dx, y + dy))
+        })
+    };
+    for count in 0..params.part1_rocks.max(params.part2_rocks) {
+        let rock = rocks[count % rocks.len()];
+        let (mut x, mut y) = (2, heights[count] + 3);
+        loop {
+            let pushed = x + jets[jet % jets.len()];
+            jet += 1;
+            if free(&stopped, rock, pushed, y) {
+                x = pushed;
+            }
+            if free(&stopped, rock, x, y - 1) {
+                y -= 1;
+            } else {
+                break;
+            }
+        }
+        stopped.extend(rock.iter().map(|(dx, dy)| (x + dx, y + dy)));
+        let top = rock.iter().map(|(_, dy)| y + dy + 1).max().unwrap();
+        heights.push(heights[count].max(top));
+    }
+    [params.part1_rocks, params.part2_rocks].map(|rocks| heights[rocks].into())
+}
+
+fn day18(input: &str, _: &DayParams) -> [Answer; 2] {
+    let cubes: HashSet<[i64; 3]> = input
+        .lines()
+        .map(|line| integers(line).try_into().unwrap())
+        .collect();
+    let neighbours = |[x, y, z]: [i64; 3]| {
+        [
+            [x - 1, y, z],
+            [x + 1, y, z],
+            [x, y - 1, z],
+            [x, y + 1, z],
+            [x, y, z - 1],
+            [x, y, z + 1],
+        ]
+    };
+
+    let low = cubes.iter().flatten().min().unwrap() - 1;
+    let high = cubes.iter().flatten().max().unwrap() + 1;
+    let mut outside = HashSet::from([[low; 3]]);
+    let mut stack = vec![[low; 3]];
+    while let Some(cube) = stack.pop() {
+        for next in neighbours(cube) {
+            if next
+                .iter()
+                .all(|coordinate| (low..=high).contains(coordinate))
+                && !cubes.contains(&next)
+                && outside.insert(next)
+            {
+                stack.push(next);
+            }
+        }
+    }
+
+    let faces = |exposed: &dyn Fn(&[i64; 3]) -> bool| {
+        cubes
+            .iter()
+            .flat_map(|cube| neighbours(*cube))
+            .filter(|next| exposed(next))
+            .count()
+    };
+    [
+        faces(&|next| !cubes.contains(next)).into(),
+        faces(&|next| outside.contains(next)).into(),
+    ]
+}
+
+/// Blueprints with cheaper robots than in the puzzle, which open geodes in far fewer minutes.
+fn day19_input(rng: &mut Rng, size: usize) -> String {
+    (1..=size.max(1))
+        .map(|id| {
+            format!(
+                "Blueprint {}: Each ore robot costs {} ore. Each clay robot costs {} ore. \
+                 Each obsidian robot costs {} ore and {} clay. \
+                 Each geode robot costs {} ore and {} obsidian.\n",
+                id,
+                rng.range(1..=3),
+                rng.range(1..=3),
+                rng.range(1..=3),
+                rng.range(2..=6),
+                rng.range(1..=3),
+                rng.range(2..=6)
+            )
+        })
+        .collect()
+}
+
+fn day19(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day19::Params = params.get();
+    let blueprints: Vec<[i64; 7]> = input
+        .lines()
+        .map(|line| integers(line).try_into().unwrap())
+        .collect();
+
+    // Every choice in every minute, with the same states merged.
+    let geodes = |[_, ore, clay, obsidian_ore, obsidian_clay, geode_ore, geode_obsidian]: [i64;
+                      7],
+                  minutes: u32| {
+        let costs = [
+            [ore, 0, 0],
+            [clay, 0, 0],
+            [obsidian_ore, obsidian_clay, 0],
+            [geode_ore, 0, geode_obsidian],
+        ];
+        // Amounts of ore, clay, obsidian and geodes, and then the robots collecting them.
+        let mut states: HashSet<[i64; 8]> = HashSet::from([[0, 0, 0, 0, 1, 0, 0, 0]]);
+        for minute in 1..=minutes {
+            let mut next = HashSet::new();
+            for state in states {
+                let mut collected = state;
+                for resource in 0..4 {
+                    collected[resource] += state[resource + 4];
+                }
+                next.insert(collected);
+                // A robot built in the last minute collects nothing.
+                if minute == minutes {
+                    continue;
+                }
+                for (robot, cost) in costs.iter().enumerate() {
+                    if (0..3).all(|resource| state[resource] >= cost[resource]) {
+                        let mut built = collected;
+                        for resource in 0..3 {
+                            built[resource] -= cost[resource];
+                        }
+                        built[robot + 4] += 1;
+                        next.insert(built);
+                    }
+                }
+            }
+            states = next;
+        }
+        states.iter().map(|state| state[3]).max().unwrap()
+    };
+
+    let part1: i64 = blueprints
+        .iter()
+        .map(|blueprint| blueprint[0] * geodes(*blueprint, params.part1_minutes))
+        .sum();
+    let part2: i64 = blueprints
+        .iter()
+        .take(params.part2_blueprints)
+        .map(|blueprint| geodes(*blueprint, params.part2_minutes))
+        .product();
+    [part1.into(), part2.into()]
+}
+
+fn day20(input: &str, params: &DayParams) -> [Answer; 2] {
+    let params: &day20::Params = params.get();
+    let numbers: Vec<i64> = input.lines().map(|line| line.parse().unwrap()).collect();
+    [(1, 1), (params.decryption_key as i64, params.part2_rounds)].map(|(key, rounds)| {
+        // The numbers with their original position, in their current order.
+        let mut mixed: Vec<(usize, i64)> = numbers
+            .iter()
+            .map(|number| number * key)
+            .enumerate()
+            .collect();
+        for _ in 0..rounds {
+            for original in 0..numbers.len() {
+                let index = mixed
+                    .iter()
+                    .position(|(index, _)| *index == original)
+                    .unwrap();
+                let number = mixed.remove(index);
+                let new_index = (index as i64 + number.1).rem_euclid(mixed.len() as i64);
+                mixed.insert(new_index as usize, number);
+            }
+        }
+        let zero = mixed.iter().position(|(_, number)| *number == 0).unwrap();
+        let grove: i64 = [1000, 2000, 3000]
+            .iter()
+            .map(|offset| mixed[(zero + offset) % mixed.len()].1)
+            .sum();
+        grove.into()
+    })
+}
+
+fn day21(input: &str, _: &DayParams) -> [Answer; 2] {
+    enum Job {
+        Number(i128),
+        Operation(usize, char, usize),
+    }
+
+    let names: Vec<&str> = input.lines().map(|line| &line[..4]).collect();
+    let index = |name: &str| names.iter().position(|other| *other == name).unwrap();
+    let jobs: Vec<Job> = input
+        .lines()
+        .map(|line| {
+            let words: Vec<&str> = line[6..].split(' ').collect();
+            match words[..] {
+                [left, operation, right] => {
+                    Job::Operation(index(left), operation.chars().next().unwrap(), index(right))
+                }
+                _ => Job::Number(words[0].parse().unwrap()),
+            }
+        })
+        .collect();
+    let (root, humn) = (index("root"), index("humn"));
+
+    /// The number that `monkey` yells, if every division on the way is exact.
+    fn yell(jobs: &[Job], monkey: usize, humn: usize, number: i128) -> Option<i128> {
+        if monkey == humn {
+            return Some(number);
+        }
+        match jobs[monkey] {
+            Job::Number(number) => Some(number),
+            Job::Operation(left, operation, right) => {
+                let left = yell(jobs, left, humn, number)?;
+                let right = yell(jobs, right, humn, number)?;
+                match operation {
+                    '+' => Some(left + right),
+                    '-' => Some(left - right),
+                    '*' => Some(left * right),
+                    _ if right != 0 && left % right == 0 => Some(left / right),
+                    _ => None,
+                }
+            }
+        }
+    }
+
+    let Job::Number(number) = jobs[humn] else {
+        panic!("`humn` yells a number");
+    };
+    let Job::Operation(left, _, right) = jobs[root] else {
+        panic!("`root` has an operation");
+    };
+    let part1 = yell(&jobs, root, humn, number).unwrap();
+    // Tries 0, 1, -1, 2, -2 and so on.
+    let part2 = (0..2_000_000)
+        .map(|index: i128| {
+            if index % 2 == 0 {
+                -index / 2
+            } else {
+                index / 2 + 1
+            }
+        })
+        .find(|number| {
+            let left = yell(&jobs, left, humn, *number);
+            left.is_some() && left == yell(&jobs, right, humn, *number)
+        });
+    [
+        Answer::Integer(part1),
+        Answer::Integer(part2.expect("a number up to a million")),
+    ]
+}
+
+type Vector = [i64; 3];
+
+fn add(a: Vector, b: Vector) -> Vector {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2]]
+}
+
+fn scale(a: Vector, factor: i64) -> Vector {
+    a.map(|component| component * factor)
+}
+
+/// Where a square of the map is on the cube: the corner of its first tile, the directions of its
+/// rows and columns, and the direction it faces, outwards.
+#[derive(Copy, Clone)]
+struct Side {
+    corner: Vector,
+    right: Vector,
+    down: Vector,
+    normal: Vector,
+}
+
+fn day22(input: &str, _: &DayParams) -> [Answer; 2] {
+    let (map, path) = input.split_once("\n\n").unwrap();
+    let map: Vec<Vec<u8>> = map.lines().map(|line| line.bytes().collect()).collect();
+    let tile = |row: i64, col: i64| {
+        let tile = map
+            .get(row as usize)
+            .and_then(|line| line.get(col as usize))
+            .copied();
+        match tile {
+            Some(b'.' | b'#') if row >= 0 && col >= 0 => tile,
+            _ => None,
+        }
+    };
+    let mut instructions: Vec<String> = Vec::new();
+    for char in path.trim().chars() {
+        match instructions.last_mut() {
+            Some(last)
+                if char.is_ascii_digit() && last.starts_with(|c: char| c.is_ascii_digit()) =>
+            {
+                last.push(char)
+            }
+            _ => instructions.push(char.to_string()),
+        }
+    }
+    let start = (
+        0,
+        map[0].iter().position(|tile| *tile == b'.').unwrap() as i64,
+    );
+    let password =
+        |(row, col): (i64, i64), facing: usize| 1000 * (row + 1) + 4 * (col + 1) + facing as i64;
+
+    // Right, down, left and up, in the order of their facing.
+    let steps = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+    let (mut position, mut facing) = (start, 0);
+    for instruction in &instructions {
+        match instruction.as_str() {
+            "R" => facing = (facing + 1) % 4,
+            "L" => facing = (facing + 3) % 4,
+            count => {
+                for _ in 0..count.parse::<usize>().unwrap() {
+                    let (dr, dc) = steps[facing];
+                    let (mut row, mut col) = (position.0 + dr, position.1 + dc);
+                    if tile(row, col).is_none() {
+                        // Wraps around to the last tile in the other direction.
+                        (row, col) = position;
+                        while tile(row - dr, col - dc).is_some() {
+                            (row, col) = (row - dr, col - dc);
+                        }
+                    }
+                    if tile(row, col) == Some(b'#') {
+                        break;
+                    }
+                    position = (row, col);
+                }
+            }
+        }
+    }
+    let part1 = password(position, facing);
+
+    // Folds the map around a cube from -size to size in every direction, with the centers of the
+    // tiles at odd coordinates on its faces.
+    let tiles = map.iter().flatten().filter(|tile| **tile != b' ').count() as i64;
+    let size = (1..).find(|size| 6 * size * size >= tiles).unwrap();
+    assert_eq!(6 * size * size, tiles, "a map of six squares");
+    let first = (0, start.1 / size);
+    let mut sides: HashMap<(i64, i64), Side> = HashMap::from([(
+        first,
+        Side {
+            corner: [-size; 3],
+            right: [1, 0, 0],
+            down: [0, 1, 0],
+            normal: [0, 0, -1],
+        },
+    )]);
+    let mut queue = VecDeque::from([first]);
+    while let Some((row, col)) = queue.pop_front() {
+        let side = sides[&(row, col)];
+        let edge = 2 * size;
+        let neighbours = [
+            (
+                (row, col + 1),
+                Side {
+                    corner: add(side.corner, scale(side.right, edge)),
+                    right: scale(side.normal, -1),
+                    down: side.down,
+                    normal: side.right,
+                },
+            ),
+            (
+                (row, col - 1),
+                Side {
+                    corner: add(side.corner, scale(side.normal, -edge)),
+                    right: side.normal,
+                    down: side.down,
+                    normal: scale(side.right, -1),
+                },
+            ),
+            (
+                (row + 1, col),
+                Side {
+                    corner: add(side.corner, scale(side.down, edge)),
+                    right: side.right,
+                    down: scale(side.normal, -1),
+                    normal: side.down,
+                },
+            ),
+            (
+                (row - 1, col),
+                Side {
+                    corner: add(side.corner, scale(side.normal, -edge)),
+                    right: side.right,
+                    down: side.normal,
+                    normal: scale(side.down, -1),
+                },
+            ),
+        ];
+        for (square, side) in neighbours {
+            if tile(square.0 * size, square.1 * size).is_some() && !sides.contains_key(&square) {
+                sides.insert(square, side);
+                queue.push_back(square);
+            }
+        }
+    }
+    let normals: HashSet<Vector> = sides.values().map(|side| side.normal).collect();
+    assert!(
+        sides.len() == 6 && normals.len() == 6,
+        "a map that folds into a cube"
+    );
+
+    let mut tiles: HashMap<Vector, (i64, i64)> = HashMap::new();
+    for (&(square_row, square_col), side) in &sides {
+        for row in 0..size {
+            for col in 0..size {
+                let center = add(
+                    side.corner,
+                    add(
+                        scale(side.right, 2 * col + 1),
+                        scale(side.down, 2 * row + 1),
+                    ),
+                );
+                tiles.insert(center, (square_row * size + row, square_col * size + col));
+            }
+        }
+    }
+    let side_of = |(row, col): (i64, i64)| sides[&(row / size, col / size)];
+    // The directions of right, down, left and up on a side.
+    let directions = |side: Side| {
+        [
+            side.right,
+            side.down,
+            scale(side.right, -1),
+            scale(side.down, -1),
+        ]
+    };
+
+    let position = tiles
+        .iter()
+        .find(|(_, tile)| **tile == start)
+        .map(|(center, _)| *center);
+    let (mut center, mut direction) = (position.unwrap(), side_of(start).right);
+    for instruction in &instructions {
+        let side = side_of(tiles[&center]);
+        let facing = directions(side)
+            .iter()
+            .position(|other| *other == direction)
+            .unwrap();
+        match instruction.as_str() {
+            "R" => direction = directions(side)[(facing + 1) % 4],
+            "L" => direction = directions(side)[(facing + 3) % 4],
+            count => {
+                for _ in 0..count.parse::<usize>().unwrap() {
+                    let (mut next, mut next_direction) =
+                        (add(center, scale(direction, 2)), direction);
+                    if !tiles.contains_key(&next) {
+                        // Over the edge and down the next face.
+                        let normal = side_of(tiles[&center]).normal;
+                        next = add(add(center, direction), scale(normal, -1));
+                        next_direction = scale(normal, -1);
+                    }
+                    let (row, col) = tiles[&next];
+                    if tile(row, col) == Some(b'#') {
+                        break;
+                    }
+                    (center, direction) = (next, next_direction);
+                }
+            }
+        }
+    }
+    let side = side_of(tiles[&center]);
+    let facing = directions(side)
+        .iter()
+        .position(|other| *other == direction)
+        .unwrap();
+    let part2 = password(tiles[&center], facing);
+    [part1.into(), part2.into()]
+}
+
+fn day23(input: &str, _: &DayParams) -> [Answer; 2] {
+    let mut elves: HashSet<(i64, i64)> = HashSet::new();
+    for (row, line) in input.lines().enumerate() {
+        for (col, tile) in line.bytes().enumerate() {
+            if tile == b'#' {
+                elves.insert((row as i64, col as i64));
+            }
+        }
+    }
+    // North, south, west and east, with the three positions checked for each.
+    let directions: [[(i64, i64); 3]; 4] = [
+        [(-1, 0), (-1, -1), (-1, 1)],
+        [(1, 0), (1, -1), (1, 1)],
+        [(0, -1), (-1, -1), (1, -1)],
+        [(0, 1), (-1, 1), (1, 1)],
+    ];
+
+    let mut part1 = 0;
+    let mut round = 0;
+    loop {
+        let mut proposals: HashMap<(i64, i64), Vec<(i64, i64)>> = HashMap::new();
+        for &(row, col) in &elves {
+            let occupied = |(dr, dc): (i64, i64)| elves.contains(&(row + dr, col + dc));
+            if !directions.iter().flatten().any(|offset| occupied(*offset)) {
+                continue;
+            }
+            let proposal = (0..4)
+                .map(|index| directions[(round + index) % 4])
+                .find(|offsets| !offsets.iter().any(|offset| occupied(*offset)));
+            if let Some([(dr, dc), _, _]) = proposal {
+                proposals
+                    .entry((row + dr, col + dc))
+                    .or_default()
+                    .push((row, col));
+            }
+        }
+        let mut moved = false;
+        for (target, from) in proposals {
+            if from.len() == 1 {
+                elves.remove(&from[0]);
+                elves.insert(target);
+                moved = true;
+            }
+        }
+        round += 1;
+
+        if round == 10 || !moved && round < 10 {
+            // Nobody moves any more once nobody moved, so that is also the grove of round 10.
+            let rows = elves.iter().map(|elf| elf.0);
+            let cols = elves.iter().map(|elf| elf.1);
+            let height = rows.clone().max().unwrap() - rows.min().unwrap() + 1;
+            let width = cols.clone().max().unwrap() - cols.min().unwrap() + 1;
+            part1 = height * width - elves.len() as i64;
+        }
+        if !moved {
+            return [part1.into(), round.into()];
+        }
+    }
+}
+
+fn day24(input: &str, _: &DayParams) -> [Answer; 2] {
+    let lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    let (height, width) = (lines.len() as i64, lines[0].len() as i64);
+    let (rows, cols) = (height - 2, width - 2);
+    let start = (
+        0,
+        lines[0].iter().position(|tile| *tile == b'.').unwrap() as i64,
+    );
+    let end = (
+        height - 1,
+        lines[lines.len() - 1]
+            .iter()
+            .position(|tile| *tile == b'.')
+            .unwrap() as i64,
+    );
+    // Whether no blizzard is on the tile at `time`, with the valley from 1 to `rows` and `cols`.
+    let free = |(row, col): (i64, i64), time: i64| {
+        if (row, col) == start || (row, col) == end {
+            return true;
+        }
+        if !(1..=rows).contains(&row) || !(1..=cols).contains(&col) {
+            return false;
+        }
+        let blizzard = |row: i64, col: i64| lines[row as usize][col as usize];
+        let wrap = |value: i64, length: i64| (value - 1).rem_euclid(length) + 1;
+        blizzard(row, wrap(col - time, cols)) != b'>'
+            && blizzard(row, wrap(col + time, cols)) != b'<'
+            && blizzard(wrap(row - time, rows), col) != b'v'
+            && blizzard(wrap(row + time, rows), col) != b'^'
+    };
+    // Any way through takes fewer minutes than there are tiles at each time of the blizzards.
+    let limit = (rows * cols + 2) * rows * cols;
+    let cross = |from: (i64, i64), to: (i64, i64), start_time: i64| {
+        let mut positions = HashSet::from([from]);
+        for time in start_time.. {
+            if positions.contains(&to) {
+                return Some(time);
+            }
+            if time >= start_time + limit {
+                return None;
+            }
+            let next: HashSet<(i64, i64)> = positions
+                .iter()
+                .flat_map(|(row, col)| {
+                    [(0, 0), (1, 0), (-1, 0), (0, 1), (0, -1)].map(|(dr, dc)| (row + dr, col + dc))
+                })
+                .filter(|position| free(*position, time + 1))
+                .collect();
+            positions = next;
+        }
+        unreachable!()
+    };
+
+    let there = cross(start, end, 0);
+    let again = there
+        .and_then(|there| cross(end, start, there))
+        .and_then(|back| cross(start, end, back));
+    [there.into(), again.into()]
+}
+
+fn day25(input: &str, _: &DayParams) -> [Answer; 2] {
+    let digits = "=-012";
+    let sum: i128 = input
+        .lines()
+        .map(|line| {
+            line.chars().fold(0, |number, digit| {
+                5 * number + digits.find(digit).unwrap() as i128 - 2
+            })
+        })
+        .sum();
+
+    let mut snafu = String::new();
+    let mut rest = sum;
+    while rest != 0 {
+        let digit = (rest + 2).rem_euclid(5);
+        snafu.insert(0, digits.as_bytes()[digit as usize] as char);
+        rest = (rest + 2).div_euclid(5);
+    }
+    if snafu.is_empty() {
+        snafu.push('0');
+    }
+    [Answer::Text(snafu), Answer::NoPart]
+}
@@ -0,0 +1,384 @@
+use crate::answers::{escape, Verdict};
+use crate::runner::{PartResult, Status};
+use crate::solution::Answer;
+use std::fmt::Write;
+use std::time::Duration;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn from_arg(arg: &str) -> Option<Self> {
+        match arg {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "csv" => Some(Format::Csv),
+            _ => None,
+        }
+    }
+}
+
+/// Formats a single result as human-readable text, or returns `None` for parts that do not exist.
+pub fn text_line(result: &PartResult) -> Option<String> {
+    let label = format!("Day {:02}, part {}", result.day, result.part);
+
+    match (&result.status, &result.answer) {
+        (Status::Ok, Answer::NoPart) => None,
+        (Status::Ok, Answer::Art(art)) => Some(format!("{}:\n{}", label, art.trim_end())),
+        (Status::Ok, answer) => Some(format!("{}: {}", label, answer)),
+        (status, _) => Some(format!(
+            "{}: {}{}",
+            label,
+            status.name(),
+            status
+                .message()
+                .map(|message| format!(" ({})", message))
+                .unwrap_or_default()
+        )),
+    }
+}
+
+/// Summarizes the parts that panicked, timed out or failed with an error, or returns `None` if
+/// all of them were solved.
+pub fn failures(results: &[PartResult]) -> Option<String> {
+    let failed: Vec<String> = results
+        .iter()
+        .filter(|result| result.status != Status::Ok)
+        .map(|result| {
+            format!(
+                "day {}, part {} ({})",
+                result.day,
+                result.part,
+                result.status.name()
+            )
+        })
+        .collect();
+    if failed.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} of {} parts failed: {}",
+        failed.len(),
+        results.len(),
+        failed.join("; ")
+    ))
+}
+
+fn outcome(result: &PartResult) -> String {
+    match result.status.message() {
+        Some(message) => format!("{}: {}", result.status.name(), message),
+        None if result.status == Status::Ok => escape(&result.answer),
+        None => result.status.name().to_string(),
+    }
+}
+
+/// Formats the outcome of checking a result against the expected answer.
+pub fn verify_line(result: &PartResult, verdict: &Verdict) -> String {
+    let label = format!("Day {:02}, part {}", result.day, result.part);
+
+    match verdict {
+        Verdict::Pass => format!("{}: pass ({})", label, outcome(result)),
+        Verdict::Fail(expected) => format!(
+            "{}: FAIL (expected {}, got {})",
+            label,
+            expected,
+            outcome(result)
+        ),
+        Verdict::Missing => format!("{}: missing ({})", label, outcome(result)),
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len() + 2);
+    escaped.push('"');
+    for c in text.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(escaped, "\\u{:04x}", c as u32).unwrap(),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
+fn answer_text(answer: &Answer) -> Option<String> {
+    match answer {
+        Answer::None | Answer::NoPart => None,
+        Answer::Art(art) => Some(art.clone()),
+        answer => Some(answer.to_string()),
+    }
+}
+
+pub fn json(results: &[PartResult]) -> String {
+    let mut out = String::from("[\n");
+
+    for (index, result) in results.iter().enumerate() {
+        let answer = answer_text(&result.answer);
+        write!(
+            out,
+            "  {{\"day\": {}, \"part\": {}, \"answer\": {}, \"type\": {}, \"status\": {}, \
+             \"message\": {}, \"parse_ns\": {}, \"solve_ns\": {}}}",
+            result.day,
+            result.part,
+            answer.as_deref().map_or(String::from("null"), json_string),
+            json_string(result.answer.kind()),
+            json_string(result.status.name()),
+            result
+                .status
+                .message()
+                .map_or(String::from("null"), json_string),
+            result.parse_time.as_nanos(),
+            result.solve_time.as_nanos(),
+        )
+        .unwrap();
+        out.push_str(if index + 1 < results.len() {
+            ",\n"
+        } else {
+            "\n"
+        });
+    }
+
+    out.push(']');
+    out
+}
+
+pub fn csv(results: &[PartResult]) -> String {
+    let mut out = String::from("day,part,answer,type,status,message,parse_ns,solve_ns\n");
+
+    for result in results {
+        writeln!(
+            out,
+            "{},{},{},{},{},{},{},{}",
+            result.day,
+            result.part,
+            csv_field(&answer_text(&result.answer).unwrap_or_default()),
+            result.answer.kind(),
+            result.status.name(),
+            csv_field(result.status.message().unwrap_or_default()),
+            result.parse_time.as_nanos(),
+            result.solve_time.as_nanos(),
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+pub(crate) fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{} ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.1} µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.1} ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.2} s", nanos as f64 / 1e9)
+    }
+}
+
+struct DayTimings {
+    day: u8,
+    parse: Duration,
+    parts: [Option<Duration>; 2],
+}
+
+impl DayTimings {
+    fn total(&self) -> Duration {
+        self.parse + self.parts.iter().flatten().sum::<Duration>()
+    }
+}
+
+/// Summarizes the parse and solve times of each day in a table, slowest day first.
+pub fn timings(results: &[PartResult]) -> String {
+    let mut days: Vec<DayTimings> = Vec::new();
+
+    for result in results {
+        let index = match days.iter().position(|timings| timings.day == result.day) {
+            Some(index) => index,
+            None => {
+                days.push(DayTimings {
+                    day: result.day,
+                    parse: result.parse_time,
+                    parts: [None, None],
+                });
+                days.len() - 1
+            }
+        };
+        days[index].parts[result.part as usize - 1] = Some(result.solve_time);
+    }
+
+    days.sort_by_key(|timings| std::cmp::Reverse(timings.total()));
+
+    let mut out = format!(
+        "{:<3}  {:<28}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+        "Day", "Title", "Parse", "Part 1", "Part 2", "Total"
+    );
+    for timings in &days {
+        let part = |index: usize| timings.parts[index].map_or(String::from("-"), format_duration);
+        writeln!(
+            out,
+            "{:>3}  {:<28}  {:>10}  {:>10}  {:>10}  {:>10}",
+            timings.day,
+            crate::day(timings.day).map_or("", |day| day.title),
+            format_duration(timings.parse),
+            part(0),
+            part(1),
+            format_duration(timings.total()),
+        )
+        .unwrap();
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn results() -> Vec<PartResult> {
+        vec![
+            PartResult {
+                day: 5,
+                part: 1,
+                answer: Answer::Text(String::from("CMZ")),
+                status: Status::Ok,
+                parse_time: Duration::from_nanos(1500),
+                solve_time: Duration::from_nanos(300),
+            },
+            PartResult {
+                day: 10,
+                part: 2,
+                answer: Answer::Art(String::from("#.\n.#\n")),
+                status: Status::Ok,
+                parse_time: Duration::from_nanos(20),
+                solve_time: Duration::from_nanos(10),
+            },
+            PartResult {
+                day: 21,
+                part: 1,
+                answer: Answer::None,
+                status: Status::Panic(String::from("explicit \"panic\"")),
+                parse_time: Duration::from_nanos(7),
+                solve_time: Duration::ZERO,
+            },
+        ]
+    }
+
+    #[test]
+    fn test_text_line() {
+        let lines: Vec<Option<String>> = results().iter().map(text_line).collect();
+        assert_eq!(
+            lines,
+            vec![
+                Some(String::from("Day 05, part 1: CMZ")),
+                Some(String::from("Day 10, part 2:\n#.\n.#")),
+                Some(String::from("Day 21, part 1: panic (explicit \"panic\")")),
+            ]
+        );
+
+        let mut result = results().swap_remove(0);
+        result.answer = Answer::None;
+        assert_eq!(text_line(&result).as_deref(), Some("Day 05, part 1: none"));
+        result.answer = Answer::NoPart;
+        assert_eq!(text_line(&result), None);
+    }
+
+    #[test]
+    fn test_failures() {
+        let mut results = results();
+        assert_eq!(
+            failures(&results).as_deref(),
+            Some("1 of 3 parts failed: day 21, part 1 (panic)")
+        );
+        results[1].status = Status::Timeout;
+        assert_eq!(
+            failures(&results).as_deref(),
+            Some("2 of 3 parts failed: day 10, part 2 (timeout); day 21, part 1 (panic)")
+        );
+        assert_eq!(failures(&results[..1]), None);
+    }
+
+    #[test]
+    fn test_verify_line() {
+        let results = results();
+        assert_eq!(
+            verify_line(&results[0], &Verdict::Pass),
+            "Day 05, part 1: pass (CMZ)"
+        );
+        assert_eq!(
+            verify_line(&results[1], &Verdict::Missing),
+            "Day 10, part 2: missing (#.\\n.#)"
+        );
+        assert_eq!(
+            verify_line(&results[2], &Verdict::Fail(String::from("152"))),
+            "Day 21, part 1: FAIL (expected 152, got panic: explicit \"panic\")"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        assert_eq!(
+            json(&results()),
+            r##"[
+  {"day": 5, "part": 1, "answer": "CMZ", "type": "text", "status": "ok", "message": null, "parse_ns": 1500, "solve_ns": 300},
+  {"day": 10, "part": 2, "answer": "#.\n.#\n", "type": "art", "status": "ok", "message": null, "parse_ns": 20, "solve_ns": 10},
+  {"day": 21, "part": 1, "answer": null, "type": "none", "status": "panic", "message": "explicit \"panic\"", "parse_ns": 7, "solve_ns": 0}
+]"##
+        )
+    }
+
+    #[test]
+    fn test_csv() {
+        assert_eq!(
+            csv(&results()),
+            r##"day,part,answer,type,status,message,parse_ns,solve_ns
+5,1,CMZ,text,ok,,1500,300
+10,2,"#.
+.#
+",art,ok,,20,10
+21,1,,none,panic,"explicit ""panic""",7,0
+"##
+        )
+    }
+
+    #[test]
+    fn test_timings() {
+        let mut results = results();
+        results.push(PartResult {
+            day: 5,
+            part: 2,
+            answer: Answer::Text(String::from("MCD")),
+            status: Status::Ok,
+            parse_time: Duration::from_nanos(1500),
+            solve_time: Duration::from_millis(2),
+        });
+
+        assert_eq!(
+            timings(&results),
+            "\
+Day  Title                              Parse      Part 1      Part 2       Total
+  5  Supply Stacks                     1.5 µs      300 ns      2.0 ms      2.0 ms
+ 10  Cathode-Ray Tube                   20 ns           -       10 ns       30 ns
+ 21  Monkey Math                         7 ns        0 ns           -        7 ns
+"
+        )
+    }
+}
@@ -0,0 +1,486 @@
+use crate::input::{self, Source};
+use crate::params::Overrides;
+use crate::solution::{Answer, Day, DayParams};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Status {
+    Ok,
+    Panic(String),
+    Timeout,
+    Error(String),
+}
+
+impl Status {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Status::Ok => "ok",
+            Status::Panic(_) => "panic",
+            Status::Timeout => "timeout",
+            Status::Error(_) => "error",
+        }
+    }
+
+    pub fn message(&self) -> Option<&str> {
+        match self {
+            Status::Panic(message) | Status::Error(message) => Some(message),
+            Status::Ok | Status::Timeout => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PartResult {
+    pub day: u8,
+    pub part: u8,
+    pub answer: Answer,
+    pub status: Status,
+    /// Time spent parsing the day's input, which is shared by both parts.
+    pub parse_time: Duration,
+    pub solve_time: Duration,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Options {
+    /// Upper limit for parsing and solving all requested parts of a single day. A day that takes
+    /// longer is reported as timed out, but its thread cannot be stopped and keeps running in the
+    /// background until it finishes or the process exits.
+    pub timeout: Option<Duration>,
+    /// Number of days solved at the same time; 1 solves them one after another.
+    pub jobs: usize,
+    /// Parameters that differ from the actual puzzle.
+    pub params: Overrides,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            timeout: None,
+            jobs: 1,
+            params: Overrides::default(),
+        }
+    }
+}
+
+/// Number of jobs to use for solving days in parallel on this machine.
+pub fn available_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |jobs| jobs.get())
+}
+
+enum Event {
+    Parsed(Duration),
+    Invalid(String),
+    Solved(u8, Answer, Duration),
+    Panicked(Option<u8>, String),
+}
+
+pub(crate) fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(message) = payload.downcast_ref::<&str>() {
+        message.to_string()
+    } else if let Some(message) = payload.downcast_ref::<String>() {
+        message.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+/// Stack size of the thread of a day, so that recursive solvers such as the packet parser of
+/// day 13 handle deeply nested input, which would overflow the 2 MiB default.
+const STACK_SIZE: usize = 64 << 20;
+
+fn spawn_day(
+    day: &'static Day,
+    input: Vec<u8>,
+    params: DayParams,
+    parts: Vec<u8>,
+) -> mpsc::Receiver<Event> {
+    let (sender, receiver) = mpsc::channel();
+
+    // Named after the day, so that the message of a panic says which day it was. If the thread
+    // cannot be started, the closed channel reports the day as failed.
+    let builder = thread::Builder::new()
+        .name(format!("day {}", day.number))
+        .stack_size(STACK_SIZE);
+    let _ = builder.spawn(move || {
+        let start = Instant::now();
+        let parsed = match panic::catch_unwind(AssertUnwindSafe(|| day.parse(&input))) {
+            Ok(Ok(parsed)) => parsed,
+            Ok(Err(err)) => {
+                let _ = sender.send(Event::Invalid(err.to_string()));
+                return;
+            }
+            Err(payload) => {
+                let _ = sender.send(Event::Panicked(None, panic_message(payload)));
+                return;
+            }
+        };
+        let _ = sender.send(Event::Parsed(start.elapsed()));
+
+        for part in parts {
+            let start = Instant::now();
+            match panic::catch_unwind(AssertUnwindSafe(|| day.solve(&parsed, &params, part))) {
+                Ok(answer) => {
+                    let _ = sender.send(Event::Solved(part, answer, start.elapsed()));
+                }
+                Err(payload) => {
+                    let _ = sender.send(Event::Panicked(Some(part), panic_message(payload)));
+                }
+            }
+        }
+    });
+
+    receiver
+}
+
+/// Runs the given parts of a single day on a separate thread, so that panics and timeouts
+/// only affect this day. After a timeout, the thread is left running, see [`Options::timeout`].
+pub fn run_isolated(
+    day: &'static Day,
+    input: Vec<u8>,
+    params: DayParams,
+    parts: &[u8],
+    options: &Options,
+) -> Vec<PartResult> {
+    // A timeout too long to add to the current time is as good as none.
+    let deadline = options
+        .timeout
+        .and_then(|timeout| Instant::now().checked_add(timeout));
+    let receiver = spawn_day(day, input, params, parts.to_vec());
+
+    let mut parse_time = Duration::ZERO;
+    let mut results: Vec<PartResult> = Vec::new();
+    let result = |part: u8, answer: Answer, status: Status, parse_time, solve_time| PartResult {
+        day: day.number,
+        part,
+        answer,
+        status,
+        parse_time,
+        solve_time,
+    };
+
+    while results.len() < parts.len() {
+        let event = match deadline {
+            Some(deadline) => receiver
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+                .map_err(|err| err == RecvTimeoutError::Timeout),
+            None => receiver.recv().map_err(|_| false),
+        };
+
+        match event {
+            Ok(Event::Parsed(duration)) => parse_time = duration,
+            Ok(Event::Solved(part, answer, duration)) => {
+                results.push(result(part, answer, Status::Ok, parse_time, duration))
+            }
+            Ok(Event::Panicked(Some(part), message)) => results.push(result(
+                part,
+                Answer::None,
+                Status::Panic(message),
+                parse_time,
+                Duration::ZERO,
+            )),
+            Ok(Event::Invalid(message)) => {
+                for part in &parts[results.len()..] {
+                    results.push(result(
+                        *part,
+                        Answer::None,
+                        Status::Error(format!("invalid input: {}", message)),
+                        parse_time,
+                        Duration::ZERO,
+                    ))
+                }
+            }
+            Ok(Event::Panicked(None, message)) => {
+                for part in &parts[results.len()..] {
+                    results.push(result(
+                        *part,
+                        Answer::None,
+                        Status::Panic(message.clone()),
+                        parse_time,
+                        Duration::ZERO,
+                    ))
+                }
+            }
+            Err(timed_out) => {
+                let status = if timed_out {
+                    Status::Timeout
+                } else {
+                    Status::Panic(String::from("solver thread terminated"))
+                };
+                for part in &parts[results.len()..] {
+                    results.push(result(
+                        *part,
+                        Answer::None,
+                        status.clone(),
+                        parse_time,
+                        Duration::ZERO,
+                    ))
+                }
+            }
+        }
+    }
+
+    results
+}
+
+fn load_and_run_day(
+    day: &'static Day,
+    parts: &[u8],
+    source: &Source,
+    options: &Options,
+) -> Vec<PartResult> {
+    let failed = |message: String| {
+        parts
+            .iter()
+            .map(|part| PartResult {
+                day: day.number,
+                part: *part,
+                answer: Answer::None,
+                status: Status::Error(message.clone()),
+                parse_time: Duration::ZERO,
+                solve_time: Duration::ZERO,
+            })
+            .collect()
+    };
+
+    let values = options.params.get(&source.name(day.number), day.number);
+    let params = match day.params(&values) {
+        Ok(params) => params,
+        Err(message) => return failed(format!("invalid parameters: {}", message)),
+    };
+    match input::load(day.number, source) {
+        Ok(input) => run_isolated(day, input.into_owned(), params, parts, options),
+        Err(err) => failed(format!(
+            "cannot read {}: {}",
+            source.describe(day.number),
+            err
+        )),
+    }
+}
+
+/// Runs the given days and calls `on_result` as soon as a part is done.
+///
+/// With more than one job, the days are distributed over that many threads. Results are still
+/// reported in the order of `days`, so a slow day holds back the output of later days.
+pub fn run_days(
+    days: &[u8],
+    parts: &[u8],
+    source: &Source,
+    options: &Options,
+    mut on_result: impl FnMut(&PartResult),
+) -> Vec<PartResult> {
+    let days: Vec<&'static Day> = days
+        .iter()
+        .filter_map(|number| crate::day(*number))
+        .collect();
+    let mut results = Vec::new();
+    let mut report = |day_results: Vec<PartResult>| {
+        for result in day_results {
+            on_result(&result);
+            results.push(result);
+        }
+    };
+
+    if options.jobs <= 1 {
+        for day in &days {
+            report(load_and_run_day(day, parts, source, options));
+        }
+        return results;
+    }
+
+    let next_day = AtomicUsize::new(0);
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.min(days.len()) {
+            let (sender, next_day, days) = (sender.clone(), &next_day, &days);
+            scope.spawn(move || loop {
+                let index = next_day.fetch_add(1, Ordering::Relaxed);
+                let Some(day) = days.get(index) else {
+                    break;
+                };
+                let _ = sender.send((index, load_and_run_day(day, parts, source, options)));
+            });
+        }
+        drop(sender);
+
+        let mut pending = BTreeMap::new();
+        let mut next_to_report = 0;
+        for (index, day_results) in receiver {
+            pending.insert(index, day_results);
+            while let Some(day_results) = pending.remove(&next_to_report) {
+                report(day_results);
+                next_to_report += 1;
+            }
+        }
+    });
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ParseError;
+    use crate::solution::Solution;
+
+    struct Failing;
+
+    impl Solution for Failing {
+        type Parsed = u8;
+        type Params = ();
+
+        fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+            match input.first() {
+                Some(b'!') => Err(ParseError::at(input, input, "a byte other than `!`")),
+                Some(byte) => Ok(*byte),
+                None => panic!("empty input"),
+            }
+        }
+
+        fn part1(parsed: &Self::Parsed, _: &()) -> Answer {
+            Answer::from(*parsed as u32)
+        }
+
+        fn part2(parsed: &Self::Parsed, _: &()) -> Answer {
+            if *parsed == b'0' {
+                panic!("division by zero")
+            }
+            loop {
+                std::thread::sleep(Duration::from_millis(10))
+            }
+        }
+    }
+
+    static FAILING: Day = Day::new::<Failing>(1, "Failing");
+
+    #[test]
+    fn test_panic_in_part() {
+        let results = run_isolated(
+            &FAILING,
+            vec![b'0'],
+            FAILING.default_params(),
+            &[1, 2],
+            &Options::default(),
+        );
+        assert_eq!(results[0].status, Status::Ok);
+        assert_eq!(results[0].answer, Answer::Integer(48));
+        assert_eq!(
+            results[1].status,
+            Status::Panic(String::from("division by zero"))
+        );
+    }
+
+    #[test]
+    fn test_panic_in_parse() {
+        let results = run_isolated(
+            &FAILING,
+            Vec::new(),
+            FAILING.default_params(),
+            &[1, 2],
+            &Options::default(),
+        );
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|result| result.status.name() == "panic"));
+    }
+
+    #[test]
+    fn test_invalid_input() {
+        let results = run_isolated(
+            &FAILING,
+            vec![b'!'],
+            FAILING.default_params(),
+            &[1, 2],
+            &Options::default(),
+        );
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].status,
+            Status::Error(String::from(
+                "invalid input: line 1, column 1: expected a byte other than `!`, found `!`"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_timeout() {
+        let options = Options {
+            timeout: Some(Duration::from_millis(50)),
+            ..Options::default()
+        };
+        let results = run_isolated(
+            &FAILING,
+            vec![b'1'],
+            FAILING.default_params(),
+            &[1, 2],
+            &options,
+        );
+        assert_eq!(results[0].status, Status::Ok);
+        assert_eq!(results[1].status, Status::Timeout);
+
+        let options = Options {
+            timeout: Some(Duration::from_secs(10_u64.pow(19))),
+            ..Options::default()
+        };
+        let results = run_isolated(
+            &FAILING,
+            vec![b'1'],
+            FAILING.default_params(),
+            &[1],
+            &options,
+        );
+        assert_eq!(results[0].status, Status::Ok);
+    }
+
+    #[test]
+    fn test_stack_size() {
+        // Parsing packets nested this deeply overflows the default stack of a thread.
+        let packet = format!("{}{}", "[".repeat(20_000), "]".repeat(20_000));
+        let input = format!("{}\n[1]\n\n[2]\n[3]\n", packet);
+        let day = crate::day(13).unwrap();
+        let results = run_isolated(
+            day,
+            input.into_bytes(),
+            day.default_params(),
+            &[1, 2],
+            &Options::default(),
+        );
+        assert!(results.iter().all(|result| result.status == Status::Ok));
+    }
+
+    #[test]
+    fn test_invalid_params() {
+        let mut options = Options::default();
+        options.params.set("rounds", "1");
+        let results = run_days(&[1], &[1, 2], &Source::Default, &options, |_| {});
+        assert_eq!(results.len(), 2);
+        assert_eq!(
+            results[0].status,
+            Status::Error(String::from(
+                "invalid parameters: unknown parameter `rounds`, there are none"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_parallel_order() {
+        let days = [6, 2, 5, 1, 4, 3];
+        let options = Options {
+            jobs: 4,
+            ..Options::default()
+        };
+        let mut reported = Vec::new();
+        let results = run_days(&days, &[1, 2], &Source::Default, &options, |result| {
+            reported.push((result.day, result.part))
+        });
+
+        let expected: Vec<(u8, u8)> = days.iter().flat_map(|day| [(*day, 1), (*day, 2)]).collect();
+        assert_eq!(reported, expected);
+        assert!(results.iter().all(|result| result.status == Status::Ok));
+    }
+}
@@ -0,0 +1,394 @@
+use std::path::{Path, PathBuf};
+
+const TEMPLATE: &str = r#"use crate::error::ParseError;
+use crate::parse::integer_at;
+use crate::solution::{Answer, Solution};
+
+pub fn parse(input: &[u8]) -> Result<Vec<u32>, ParseError> {
+    input
+        .trim_ascii_end()
+        .split(|byte| *byte == b'\n')
+        .filter(|line| !line.is_empty())
+        .map(|line| integer_at(input, line))
+        .collect()
+}
+
+pub fn part1(_numbers: &[u32]) -> Option<usize> {
+    None
+}
+
+pub fn part2(_numbers: &[u32]) -> Option<usize> {
+    None
+}
+
+pub struct Day{NN};
+
+impl Solution for Day{NN} {
+    type Parsed = Vec<u32>;
+    type Params = ();
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+        parse(input)
+    }
+
+    fn part1(numbers: &Self::Parsed, _: &()) -> Answer {
+        part1(numbers).into()
+    }
+
+    fn part2(numbers: &Self::Parsed, _: &()) -> Answer {
+        part2(numbers).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bench::bench;
+
+    const INPUT: &[u8] = include_bytes!("../inputs/day{NN}.txt");
+    const EXAMPLE: &[u8] = include_bytes!("../examples/day{NN}.txt");
+
+    #[test]
+    fn test_example() {
+        let numbers = parse(EXAMPLE).unwrap();
+        assert_eq!(part1(&numbers), None);
+        assert_eq!(part2(&numbers), None);
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_parse() {
+        bench("day{NN}::parse", || parse(INPUT))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part1() {
+        let numbers = parse(INPUT).unwrap();
+        bench("day{NN}::part1", || part1(&numbers))
+    }
+
+    #[test]
+    #[ignore]
+    fn bench_part2() {
+        let numbers = parse(INPUT).unwrap();
+        bench("day{NN}::part2", || part2(&numbers))
+    }
+}
+"#;
+
+/// The generator of a new day, for inputs that the parser of [`TEMPLATE`] accepts.
+const GENERATOR_TEMPLATE: &str = r#"
+/// Random numbers, one per line, until the generator is written for the puzzle.
+fn day{NN}(rng: &mut Rng, size: usize) -> String {
+    (0..size.max(1))
+        .map(|_| format!("{}\n", rng.below(1000)))
+        .collect()
+}
+"#;
+
+/// The source of a new day module, to be filled in with the actual solution.
+fn module(day: u8) -> String {
+    TEMPLATE.replace("{NN}", &format!("{:02}", day))
+}
+
+/// Inserts `line` into the block of lines for which `entry` returns a day number, keeping the
+/// block ordered by day.
+fn insert_entry(
+    text: &str,
+    day: u8,
+    line: &str,
+    entry: impl Fn(&str) -> Option<u8>,
+) -> Result<String, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let entries: Vec<(usize, u8)> = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(index, line)| Some((index, entry(line)?)))
+        .collect();
+
+    if entries.iter().any(|(_, number)| *number == day) {
+        return Err(format!("day {} is already registered", day));
+    }
+    let Some(last) = entries.last() else {
+        return Err(String::from("cannot find where to register the day"));
+    };
+    let index = entries
+        .iter()
+        .find(|(_, number)| *number > day)
+        .map_or(last.0 + 1, |(index, _)| *index);
+
+    // Entries may continue on the following lines, e.g. with `.with_params(...)`.
+    let index = if index == last.0 + 1 {
+        index
+            + lines[index..]
+                .iter()
+                .take_while(|line| line.trim_start().starts_with('.'))
+                .count()
+    } else {
+        index
+    };
+
+    let mut result: Vec<&str> = lines[..index].to_vec();
+    result.push(line);
+    result.extend_from_slice(&lines[index..]);
+    Ok(result.join("\n") + "\n")
+}
+
+/// The day of a `dayNN` identifier at the start of `text`.
+fn module_day(text: &str) -> Option<u8> {
+    let digits = text.strip_prefix("day")?.get(..2)?;
+    digits
+        .bytes()
+        .all(|byte| byte.is_ascii_digit())
+        .then(|| digits.parse().ok())?
+}
+
+/// Adds the module declaration and registry entry of `day` to the source of `lib.rs`.
+fn register(lib: &str, day: u8, title: &str) -> Result<String, String> {
+    let lib = insert_entry(lib, day, &format!("pub mod day{:02};", day), |line| {
+        module_day(line.strip_prefix("pub mod ")?.strip_suffix(';')?)
+    })?;
+    let entry = format!(
+        "    Day::new::<day{:02}::Day{:02}>({}, {:?}),",
+        day, day, day, title
+    );
+    insert_entry(&lib, day, &entry, |line| {
+        module_day(line.trim_start().strip_prefix("Day::new::<")?)
+    })
+}
+
+/// Adds the embedded input of `day` to the source of `input.rs`.
+fn register_embedded(input: &str, day: u8) -> Result<String, String> {
+    let entry = format!(
+        "        {} => include_bytes!(\"../inputs/day{:02}.txt\"),",
+        day, day
+    );
+    insert_entry(input, day, &entry, |line| {
+        let (number, include) = line.trim_start().split_once(" => include_bytes!")?;
+        include.contains("/inputs/").then(|| number.parse().ok())?
+    })
+}
+
+/// The day of an entry like `Generator::new(7, ...` or `Reference::unwritten(7)` in a registry of
+/// `kind`.
+fn registry_day(line: &str, kind: &str) -> Option<u8> {
+    let call = line.trim_start().strip_prefix(kind)?.strip_prefix("::")?;
+    let (_, arguments) = call.split_once('(')?;
+    arguments.split([',', ')']).next()?.parse().ok()
+}
+
+/// Inserts `function` before the tests of `source`, or at its end if it has none.
+fn insert_function(source: &str, function: &str) -> String {
+    match source.find("\n#[cfg(test)]\n") {
+        Some(index) => format!("{}{}{}", &source[..index], function, &source[index..]),
+        None => format!("{}{}", source, function),
+    }
+}
+
+/// Adds a stub generator of `day` to the source of `gen.rs`.
+fn register_generator(gen: &str, day: u8) -> Result<String, String> {
+    let entry = format!(
+        "    Generator::new({}, \"lines\", 100, day{:02}),",
+        day, day
+    );
+    let gen = insert_entry(gen, day, &entry, |line| registry_day(line, "Generator"))?;
+    let function = GENERATOR_TEMPLATE.replace("{NN}", &format!("{:02}", day));
+    Ok(insert_function(&gen, &function))
+}
+
+/// Registers `day` in `reference.rs` as not written yet, so that the differential tests skip it.
+fn register_reference(reference: &str, day: u8) -> Result<String, String> {
+    let entry = format!("    Reference::unwritten({}),", day);
+    insert_entry(reference, day, &entry, |line| {
+        registry_day(line, "Reference")
+    })
+}
+
+pub fn module_path(root: &Path, day: u8) -> PathBuf {
+    root.join("src").join(format!("day{:02}.rs", day))
+}
+
+/// Creates the module of `day` below `root` and registers it, together with a stub generator and
+/// a reference solution that is not written yet, and empty example and input files if they do not exist yet.
+pub fn create(root: &Path, day: u8, title: &str) -> Result<(), String> {
+    let read = |path: &Path| {
+        std::fs::read_to_string(path)
+            .map_err(|err| format!("cannot read {}: {}", path.display(), err))
+    };
+    let write = |path: &Path, contents: &str| {
+        std::fs::write(path, contents)
+            .map_err(|err| format!("cannot write {}: {}", path.display(), err))
+    };
+
+    let module_file = module_path(root, day);
+    if module_file.exists() {
+        return Err(format!("{} already exists", module_file.display()));
+    }
+    let (lib_path, input_path) = (root.join("src/lib.rs"), root.join("src/input.rs"));
+    let (gen_path, reference_path) = (root.join("src/gen.rs"), root.join("src/reference.rs"));
+    let lib = register(&read(&lib_path)?, day, title)?;
+    let input = register_embedded(&read(&input_path)?, day)?;
+    let gen = register_generator(&read(&gen_path)?, day)?;
+    let reference = register_reference(&read(&reference_path)?, day)?;
+
+    for path in [
+        root.join(crate::input::default_path(day)),
+        root.join(crate::input::example_path(day)),
+    ] {
+        if !path.exists() {
+            if let Some(parent) = path.parent() {
+                std::fs::create_dir_all(parent)
+                    .map_err(|err| format!("cannot create {}: {}", parent.display(), err))?;
+            }
+            write(&path, "")?;
+        }
+    }
+
+    write(&module_file, &module(day))?;
+    write(&lib_path, &lib)?;
+    write(&input_path, &input)?;
+    write(&gen_path, &gen)?;
+    write(&reference_path, &reference)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::testing::temp_dir;
+
+    const LIB: &str = "\
+pub mod day01;
+pub mod day03;
+
+pub mod answers;
+
+pub static DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1, \"Calorie Counting\"),
+    Day::new::<day03::Day03>(3, \"Rucksack Reorganization\"),
+];
+
+pub const NUM_DAYS: u8 = 3;
+";
+
+    const GEN: &str = "\
+pub static GENERATORS: &[Generator] = &[
+    Generator::new(1, \"elves\", 250, day01),
+    Generator::new(3, \"groups of three rucksacks\", 100, day03),
+];
+
+fn day01(rng: &mut Rng, size: usize) -> String {}
+
+#[cfg(test)]
+mod tests {}
+";
+
+    const REFERENCE: &str = "\
+pub static REFERENCES: &[Reference] = &[
+    Reference::new(1, 20, day01)
+        .with_params(&[(\"elves\", \"3\")]),
+];
+
+fn day01(input: &str, _: &DayParams) -> [Answer; 2] {}
+";
+
+    #[test]
+    fn test_module() {
+        let module = module(7);
+        assert!(module.contains("pub struct Day07;"));
+        assert!(module.contains("include_bytes!(\"../examples/day07.txt\")"));
+        assert!(!module.contains("{NN}"));
+        assert!(!module.contains("todo!"));
+    }
+
+    #[test]
+    fn test_register() {
+        assert_eq!(
+            register(LIB, 2, "Rock \"Paper\" Scissors").unwrap(),
+            "\
+pub mod day01;
+pub mod day02;
+pub mod day03;
+
+pub mod answers;
+
+pub static DAYS: &[Day] = &[
+    Day::new::<day01::Day01>(1, \"Calorie Counting\"),
+    Day::new::<day02::Day02>(2, \"Rock \\\"Paper\\\" Scissors\"),
+    Day::new::<day03::Day03>(3, \"Rucksack Reorganization\"),
+];
+
+pub const NUM_DAYS: u8 = 3;
+"
+        );
+        assert!(register(LIB, 4, "")
+            .unwrap()
+            .contains("pub mod day03;\npub mod day04;\n\npub mod answers;"));
+        assert!(register(LIB, 3, "").is_err());
+    }
+
+    #[test]
+    fn test_register_embedded() {
+        let input = "match day {\n        1 => include_bytes!(\"../inputs/day01.txt\"),\n        _ => &[],\n}\n";
+        assert_eq!(
+            register_embedded(input, 2).unwrap(),
+            "match day {\n        1 => include_bytes!(\"../inputs/day01.txt\"),\n        2 => include_bytes!(\"../inputs/day02.txt\"),\n        _ => &[],\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_register_generator() {
+        let gen = register_generator(GEN, 2).unwrap();
+        assert!(gen.contains(
+            "day01),\n    Generator::new(2, \"lines\", 100, day02),\n    Generator::new(3,"
+        ));
+        let function = gen.find("fn day02(rng: &mut Rng, size: usize)").unwrap();
+        assert!(function < gen.find("#[cfg(test)]").unwrap());
+        assert!(register_generator(GEN, 3).is_err());
+    }
+
+    #[test]
+    fn test_register_reference() {
+        let reference = register_reference(REFERENCE, 2).unwrap();
+        assert!(reference.contains("(\"elves\", \"3\")]),\n    Reference::unwritten(2),\n];"));
+        assert!(!reference.contains("fn day02"));
+        assert!(register_reference(&reference, 2).is_err());
+        assert!(register_reference(&reference, 3)
+            .unwrap()
+            .contains("Reference::unwritten(2),\n    Reference::unwritten(3),\n];"));
+    }
+
+    #[test]
+    fn test_create() {
+        let root = temp_dir("scaffold");
+        std::fs::create_dir_all(root.join("src")).unwrap();
+        std::fs::write(root.join("src/lib.rs"), LIB).unwrap();
+        std::fs::write(
+            root.join("src/input.rs"),
+            "        1 => include_bytes!(\"../inputs/day01.txt\"),\n",
+        )
+        .unwrap();
+        std::fs::write(root.join("src/gen.rs"), GEN).unwrap();
+        std::fs::write(root.join("src/reference.rs"), REFERENCE).unwrap();
+
+        create(&root, 2, "Rock Paper Scissors").unwrap();
+        assert_eq!(
+            std::fs::read_to_string(module_path(&root, 2)).unwrap(),
+            module(2)
+        );
+        assert!(root.join("inputs/day02.txt").exists());
+        assert!(root.join("examples/day02.txt").exists());
+        let read = |path: &str| std::fs::read_to_string(root.join(path)).unwrap();
+        assert!(read("src/lib.rs").contains("pub mod day02;"));
+        assert!(read("src/input.rs").contains("2 => include_bytes!"));
+        assert_eq!(read("src/gen.rs"), register_generator(GEN, 2).unwrap());
+        assert_eq!(
+            read("src/reference.rs"),
+            register_reference(REFERENCE, 2).unwrap()
+        );
+
+        assert!(create(&root, 2, "Rock Paper Scissors").is_err());
+        assert!(create(&root, 1, "Calorie Counting").is_err());
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,547 @@
+use crate::grid::Grid2D;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Keeps track of the nodes a search has reached.
+pub trait Visited<N> {
+    /// Marks `node` and returns whether it was not marked before.
+    fn visit(&mut self, node: &N) -> bool;
+
+    /// The number of nodes, if [`Visited::index`] maps them to distinct numbers below it, so that
+    /// [`Search::dijkstra`] and [`Search::astar`] can keep their costs in a vector.
+    #[inline]
+    fn capacity(&self) -> Option<usize> {
+        None
+    }
+
+    /// The number of `node`, if the nodes are numbered.
+    #[inline]
+    fn index(&self, _node: &N) -> Option<usize> {
+        None
+    }
+}
+
+impl<N: Clone + Eq + Hash> Visited<N> for HashSet<N> {
+    #[inline]
+    fn visit(&mut self, node: &N) -> bool {
+        self.insert(node.clone())
+    }
+}
+
+/// A grid of flags for searches over the positions of a [`Grid2D`].
+impl Visited<(usize, usize)> for Grid2D<bool> {
+    #[inline]
+    fn visit(&mut self, position: &(usize, usize)) -> bool {
+        !std::mem::replace(&mut self[*position], true)
+    }
+
+    #[inline]
+    fn capacity(&self) -> Option<usize> {
+        Some(self.width() * self.height())
+    }
+
+    #[inline]
+    fn index(&self, (x, y): &(usize, usize)) -> Option<usize> {
+        Some(y * self.width() + x)
+    }
+}
+
+/// Flags for nodes that `index` maps to distinct numbers below a known bound, which is much
+/// faster than hashing them.
+pub struct Indexed<F> {
+    flags: Vec<bool>,
+    index: F,
+}
+
+impl<F> Indexed<F> {
+    pub fn new(size: usize, index: F) -> Self {
+        Indexed {
+            flags: vec![false; size],
+            index,
+        }
+    }
+}
+
+impl<N, F: Fn(&N) -> usize> Visited<N> for Indexed<F> {
+    #[inline]
+    fn visit(&mut self, node: &N) -> bool {
+        !std::mem::replace(&mut self.flags[(self.index)(node)], true)
+    }
+
+    #[inline]
+    fn capacity(&self) -> Option<usize> {
+        Some(self.flags.len())
+    }
+
+    #[inline]
+    fn index(&self, node: &N) -> Option<usize> {
+        Some((self.index)(node))
+    }
+}
+
+/// The cheapest cost found so far for every node, in a vector if the nodes are numbered.
+enum Costs<N, C> {
+    Dense(Vec<Option<C>>),
+    Sparse(HashMap<N, C>),
+}
+
+impl<N: Eq + Hash, C: Copy> Costs<N, C> {
+    fn new(visited: &impl Visited<N>) -> Self {
+        match visited.capacity() {
+            Some(capacity) => Costs::Dense(vec![None; capacity]),
+            None => Costs::Sparse(HashMap::new()),
+        }
+    }
+
+    #[inline]
+    fn get(&self, visited: &impl Visited<N>, node: &N) -> Option<C> {
+        match self {
+            Costs::Dense(costs) => costs[Self::index(visited, node)],
+            Costs::Sparse(costs) => costs.get(node).copied(),
+        }
+    }
+
+    #[inline]
+    fn insert(&mut self, visited: &impl Visited<N>, node: N, cost: C) {
+        match self {
+            Costs::Dense(costs) => costs[Self::index(visited, &node)] = Some(cost),
+            Costs::Sparse(costs) => {
+                costs.insert(node, cost);
+            }
+        }
+    }
+
+    #[inline]
+    fn index(visited: &impl Visited<N>, node: &N) -> usize {
+        visited.index(node).expect("numbered nodes have an index")
+    }
+}
+
+/// A breadth-first, Dijkstra or A* search, with the bookkeeping of which nodes were reached.
+///
+/// All searches take a list of start nodes, a function listing the neighbours of a node, and a
+/// function `stop` that is called once for each reached node, in order of cost, with the cost of
+/// reaching it. The search ends at the first node for which `stop` returns true and returns that
+/// node with its cost, or `None` once every reachable node has been seen.
+///
+/// With [`Search::track_paths`], the predecessor of every node is kept so that [`Search::path`]
+/// can rebuild the cheapest path to it.
+pub struct Search<N, V = HashSet<N>> {
+    visited: V,
+    predecessors: Option<HashMap<N, N>>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    pub fn new() -> Self {
+        Self::with_visited(HashSet::new())
+    }
+}
+
+impl<N: Clone + Eq + Hash> Default for Search<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<N: Clone + Eq + Hash, V: Visited<N>> Search<N, V> {
+    /// Creates a search that marks reached nodes in `visited`, for node types with a cheaper
+    /// representation than a hash set. If `visited` numbers the nodes, [`Search::dijkstra`] and
+    /// [`Search::astar`] also keep their costs in a vector rather than a hash map.
+    pub fn with_visited(visited: V) -> Self {
+        Search {
+            visited,
+            predecessors: None,
+        }
+    }
+
+    /// Keeps the predecessor of every node, for [`Search::path`].
+    pub fn track_paths(mut self) -> Self {
+        self.predecessors = Some(HashMap::new());
+        self
+    }
+
+    /// The nodes reached so far. [`Search::bfs`] marks nodes when they are queued, including those
+    /// not yet passed to `stop`; [`Search::dijkstra`] and [`Search::astar`] only mark them when
+    /// they leave the queue.
+    pub fn visited(&self) -> &V {
+        &self.visited
+    }
+
+    /// The nodes from a start node to `goal`, or `None` if paths are not tracked or `goal` was
+    /// not reached.
+    pub fn path(&self, goal: &N) -> Option<Vec<N>> {
+        let predecessors = self.predecessors.as_ref()?;
+        let mut path = vec![goal.clone()];
+        let mut node = goal;
+        loop {
+            // Start nodes are their own predecessors.
+            let previous = predecessors.get(node)?;
+            if previous == node {
+                break;
+            }
+            path.push(previous.clone());
+            node = previous;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    #[inline]
+    fn record(&mut self, node: &N, previous: &N) {
+        if let Some(predecessors) = self.predecessors.as_mut() {
+            predecessors.insert(node.clone(), previous.clone());
+        }
+    }
+
+    /// Searches a graph where every edge costs one.
+    #[inline]
+    pub fn bfs<I: IntoIterator<Item = N>>(
+        &mut self,
+        starts: impl IntoIterator<Item = N>,
+        mut neighbours: impl FnMut(&N) -> I,
+        mut stop: impl FnMut(&N, usize) -> bool,
+    ) -> Option<(N, usize)> {
+        let mut queue = VecDeque::new();
+        for start in starts {
+            if self.visited.visit(&start) {
+                self.record(&start, &start);
+                queue.push_back((start, 0));
+            }
+        }
+
+        while let Some((node, cost)) = queue.pop_front() {
+            if stop(&node, cost) {
+                return Some((node, cost));
+            }
+            for next in neighbours(&node) {
+                if self.visited.visit(&next) {
+                    self.record(&next, &node);
+                    queue.push_back((next, cost + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Searches a graph with non-negative edge costs; `neighbours` lists each neighbour with the
+    /// cost of the edge to it.
+    pub fn dijkstra<C, I>(
+        &mut self,
+        starts: impl IntoIterator<Item = N>,
+        neighbours: impl FnMut(&N) -> I,
+        stop: impl FnMut(&N, C) -> bool,
+    ) -> Option<(N, C)>
+    where
+        C: Copy + Ord + Default + Add<Output = C>,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        self.astar(starts, neighbours, |_| C::default(), stop)
+    }
+
+    /// Like [`Search::dijkstra`], but visits nodes in order of their cost plus `heuristic`, an
+    /// estimate of the remaining cost that must never be too high.
+    ///
+    /// A heuristic that is admissible but not consistent, i.e. drops by more than the cost of
+    /// some edge, can lead to a node being left before its cheapest path is found. Such a node is
+    /// visited again, and passed to `stop` again, once a cheaper path to it turns up.
+    #[inline]
+    pub fn astar<C, I>(
+        &mut self,
+        starts: impl IntoIterator<Item = N>,
+        mut neighbours: impl FnMut(&N) -> I,
+        mut heuristic: impl FnMut(&N) -> C,
+        mut stop: impl FnMut(&N, C) -> bool,
+    ) -> Option<(N, C)>
+    where
+        C: Copy + Ord + Default + Add<Output = C>,
+        I: IntoIterator<Item = (N, C)>,
+    {
+        // Nodes are only marked once they leave the queue, as a cheaper path to a queued node may
+        // still be found. The cheapest cost found so far is kept for every node, so that entries
+        // superseded by a cheaper one are skipped and a node is visited again if it was left
+        // too early.
+        let mut costs = Costs::new(&self.visited);
+        let mut queue = BinaryHeap::new();
+        for start in starts {
+            // A start listed twice is queued once.
+            if costs.get(&self.visited, &start).is_some() {
+                continue;
+            }
+            costs.insert(&self.visited, start.clone(), C::default());
+            queue.push(Entry {
+                estimate: heuristic(&start),
+                cost: C::default(),
+                node: start,
+                previous: None,
+            });
+        }
+
+        while let Some(Entry {
+            cost,
+            node,
+            previous,
+            ..
+        }) = queue.pop()
+        {
+            if costs
+                .get(&self.visited, &node)
+                .is_some_and(|best| best < cost)
+            {
+                continue;
+            }
+            self.visited.visit(&node);
+            self.record(&node, previous.as_ref().unwrap_or(&node));
+            if stop(&node, cost) {
+                return Some((node, cost));
+            }
+            for (next, step) in neighbours(&node) {
+                let cost = cost + step;
+                if costs
+                    .get(&self.visited, &next)
+                    .is_some_and(|best| best <= cost)
+                {
+                    continue;
+                }
+                costs.insert(&self.visited, next.clone(), cost);
+                queue.push(Entry {
+                    estimate: cost + heuristic(&next),
+                    cost,
+                    node: next,
+                    previous: self.predecessors.is_some().then(|| node.clone()),
+                });
+            }
+        }
+
+        None
+    }
+}
+
+/// A queued node, ordered so that the lowest estimate is popped first from the max-heap.
+struct Entry<N, C> {
+    estimate: C,
+    cost: C,
+    node: N,
+    previous: Option<N>,
+}
+
+impl<N, C: Ord> Ord for Entry<N, C> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+impl<N, C: Ord> PartialOrd for Entry<N, C> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N, C: Ord> PartialEq for Entry<N, C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N, C: Ord> Eq for Entry<N, C> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &[u8] = b"\
+S.#.....
+.##.###.
+....#.E.
+.####.#.
+........
+";
+
+    fn maze() -> Grid2D<u8> {
+        Grid2D::parse(MAZE, "squares", "`.`, `#`, `S` or `E`", Some).unwrap()
+    }
+
+    fn open(grid: &Grid2D<u8>, position: &(usize, usize)) -> Vec<(usize, usize)> {
+        grid.neighbours_4(*position)
+            .filter(|next| grid[*next] != b'#')
+            .collect()
+    }
+
+    #[test]
+    fn test_bfs() {
+        let grid = maze();
+        let start = grid.position(|square| *square == b'S').unwrap();
+        let mut search = Search::with_visited(grid.map(|_| false)).track_paths();
+
+        let found = search.bfs(
+            [start],
+            |position| open(&grid, position),
+            |position, _| grid[*position] == b'E',
+        );
+        assert_eq!(found, Some(((6, 2), 12)));
+
+        let path = search.path(&(6, 2)).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!((path[0], path[12]), ((0, 0), (6, 2)));
+        assert!(path
+            .windows(2)
+            .all(|step| open(&grid, &step[0]).contains(&step[1])));
+    }
+
+    #[test]
+    fn test_bfs_multiple_starts() {
+        let grid = maze();
+        let mut distances = HashMap::new();
+        let found = Search::new().bfs(
+            [(0, 0), (7, 4)],
+            |position| open(&grid, position),
+            |position, distance| {
+                distances.insert(*position, distance);
+                false
+            },
+        );
+        assert_eq!(found, None);
+        assert_eq!(distances[&(6, 2)], 3);
+        assert_eq!(distances[&(3, 0)], 7);
+        assert!(!distances.contains_key(&(2, 0)));
+        assert!(Search::new().path(&(0, 0)).is_none());
+
+        let mut search = Search::new().track_paths();
+        search.bfs([(0, 0)], |position| open(&grid, position), |_, _| false);
+        assert_eq!(search.path(&(0, 0)), Some(vec![(0, 0)]));
+        assert_eq!(search.path(&(2, 0)), None);
+    }
+
+    /// A graph where the direct edge is more expensive than the detour.
+    fn weighted(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 7), ('c', 2)],
+            'c' => vec![('d', 2), ('b', 4)],
+            'd' => vec![('b', 1), ('e', 10)],
+            'b' => vec![('e', 3)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let mut search = Search::new().track_paths();
+        let found = search.dijkstra(['a'], weighted, |node, _| *node == 'e');
+        assert_eq!(found, Some(('e', 8)));
+        assert_eq!(search.path(&'e'), Some(vec!['a', 'c', 'd', 'b', 'e']));
+        assert_eq!(
+            Search::new().dijkstra(['e'], weighted, |node, _| *node == 'a'),
+            None
+        );
+    }
+
+    #[test]
+    fn test_duplicate_starts() {
+        let grid = maze();
+        let neighbours =
+            |position: &(usize, usize)| open(&grid, position).into_iter().map(|next| (next, 1));
+        let mut stops = 0;
+        Search::new().dijkstra([(0, 0), (0, 0), (7, 4), (0, 0)], neighbours, |_, _| {
+            stops += 1;
+            false
+        });
+        assert_eq!(stops, open_squares(&grid));
+
+        let mut stops = 0;
+        Search::with_visited(grid.map(|_| false)).astar(
+            [(7, 4), (7, 4)],
+            neighbours,
+            |_| 0,
+            |_, _| {
+                stops += 1;
+                false
+            },
+        );
+        assert_eq!(stops, open_squares(&grid));
+    }
+
+    #[test]
+    fn test_astar() {
+        let grid = maze();
+        let goal = (6, 2);
+        let mut expanded = 0;
+        let found = Search::new().astar(
+            [(0, 0)],
+            |position| open(&grid, position).into_iter().map(|next| (next, 1)),
+            |&(x, y)| x.abs_diff(goal.0) + y.abs_diff(goal.1),
+            |position, _| {
+                expanded += 1;
+                *position == goal
+            },
+        );
+        assert_eq!(found, Some((goal, 12)));
+        assert!(expanded < open_squares(&grid));
+    }
+
+    #[test]
+    fn test_dijkstra_dense() {
+        // The costs of a grid or indexed search are kept in a vector.
+        let grid = maze();
+        let mut search = Search::with_visited(grid.map(|_| false)).track_paths();
+        let found = search.dijkstra(
+            [(0, 0)],
+            |position| open(&grid, position).into_iter().map(|next| (next, 1)),
+            |position, _| grid[*position] == b'E',
+        );
+        assert_eq!(found, Some(((6, 2), 12)));
+        assert_eq!(search.path(&(6, 2)).map(|path| path.len()), Some(13));
+
+        let number = |node: &char| (*node as u8 - b'a') as usize;
+        let mut search = Search::with_visited(Indexed::new(5, number)).track_paths();
+        let found = search.dijkstra(['a'], weighted, |node, _| *node == 'e');
+        assert_eq!(found, Some(('e', 8)));
+        assert_eq!(search.path(&'e'), Some(vec!['a', 'c', 'd', 'b', 'e']));
+    }
+
+    #[test]
+    fn test_astar_inconsistent_heuristic() {
+        // The shortest path is s, b, a, g with cost 5. The estimate of 4 for `b` never exceeds
+        // its remaining cost, but drops by more than the edge from `b` to `a`, so `a` is first
+        // left with the cost 4 of the direct edge.
+        let edges = |node: &char| match node {
+            's' => vec![('a', 4), ('b', 1)],
+            'b' => vec![('a', 1)],
+            'a' => vec![('g', 3)],
+            _ => vec![],
+        };
+        let heuristic = |node: &char| if *node == 'b' { 4 } else { 0 };
+        let mut search = Search::new().track_paths();
+        let mut reached = Vec::new();
+        let found = search.astar(['s'], edges, heuristic, |node, cost| {
+            reached.push((*node, cost));
+            *node == 'g'
+        });
+        assert_eq!(found, Some(('g', 5)));
+        assert_eq!(search.path(&'g'), Some(vec!['s', 'b', 'a', 'g']));
+        assert_eq!(reached, [('s', 0), ('a', 4), ('b', 1), ('a', 2), ('g', 5)]);
+    }
+
+    fn open_squares(grid: &Grid2D<u8>) -> usize {
+        grid.iter().filter(|square| **square != b'#').count()
+    }
+
+    #[test]
+    fn test_indexed() {
+        let mut visited = Indexed::new(10, |node: &usize| *node);
+        assert!(visited.visit(&3));
+        assert!(!visited.visit(&3));
+        let mut search = Search::with_visited(Indexed::new(10, |node: &usize| *node));
+        let mut count = 0;
+        search.bfs(
+            [0],
+            |node| [node + 1, node + 2].into_iter().filter(|next| *next < 10),
+            |_, _| {
+                count += 1;
+                false
+            },
+        );
+        assert_eq!(count, 10);
+    }
+}
@@ -0,0 +1,337 @@
+use crate::error::ParseError;
+use std::any::Any;
+use std::fmt;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Answer {
+    Integer(i128),
+    Text(String),
+    /// Multi-line output such as the CRT image of day 10.
+    Art(String),
+    /// The input has no answer, e.g. when a searched path does not exist.
+    None,
+    /// The part does not exist, e.g. the second part of day 25.
+    NoPart,
+}
+
+impl Answer {
+    pub fn text(bytes: &[u8]) -> Self {
+        Answer::Text(String::from_utf8_lossy(bytes).into_owned())
+    }
+
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "text",
+            Answer::Art(_) => "art",
+            Answer::None => "none",
+            Answer::NoPart => "no_part",
+        }
+    }
+}
+
+macro_rules! answer_from_integer {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(value: $t) -> Self {
+                    Answer::Integer(value as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_integer!(u32, u64, usize, i32, i64, isize);
+
+/// `None` for inputs without an answer, e.g. when a searched path does not exist.
+impl<T: Into<Answer>> From<Option<T>> for Answer {
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Answer::None, Into::into)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Art(art) => write!(f, "{}", art.trim_end()),
+            Answer::None => write!(f, "none"),
+            Answer::NoPart => write!(f, "-"),
+        }
+    }
+}
+
+/// Puzzle parameters of a day, such as the number of rounds, that can be changed by name to
+/// solve variations of the puzzle. The [`Default`] holds the values of the actual puzzle.
+pub trait Params: Default + Send + 'static {
+    /// The name and value of every parameter.
+    fn values(&self) -> Vec<(&'static str, String)>;
+
+    /// Parses `value` as the new value of the parameter `name`.
+    fn set(&mut self, name: &str, value: &str) -> Result<(), String>;
+}
+
+/// For days without parameters.
+impl Params for () {
+    fn values(&self) -> Vec<(&'static str, String)> {
+        Vec::new()
+    }
+
+    fn set(&mut self, name: &str, _value: &str) -> Result<(), String> {
+        Err(format!("unknown parameter `{}`, there are none", name))
+    }
+}
+
+/// Defines a `Params` struct with a default for every field and implements [`Params`] for it.
+/// The fields need to implement [`std::str::FromStr`] and [`fmt::Display`].
+macro_rules! params {
+    (
+        $(#[$meta:meta])*
+        pub struct Params {
+            $($(#[$field_meta:meta])* pub $field:ident: $type:ty = $default:expr,)*
+        }
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct Params {
+            $($(#[$field_meta])* pub $field: $type,)*
+        }
+
+        impl Default for Params {
+            fn default() -> Self {
+                Params {
+                    $($field: $default,)*
+                }
+            }
+        }
+
+        impl $crate::solution::Params for Params {
+            fn values(&self) -> Vec<(&'static str, String)> {
+                vec![$((stringify!($field), self.$field.to_string()),)*]
+            }
+
+            fn set(&mut self, name: &str, value: &str) -> Result<(), String> {
+                match name {
+                    $(stringify!($field) => {
+                        self.$field = value.parse().map_err(|_| {
+                            format!("invalid value `{}` for parameter `{}`", value, name)
+                        })?
+                    })*
+                    _ => {
+                        let names = [$(concat!("`", stringify!($field), "`")),*];
+                        return Err(format!(
+                            "unknown parameter `{}`, expected {}",
+                            name,
+                            names.join(", ")
+                        ));
+                    }
+                }
+                Ok(())
+            }
+        }
+    };
+}
+
+pub(crate) use params;
+
+pub trait Solution {
+    type Parsed: Send + 'static;
+    type Params: Params;
+
+    fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError>;
+    fn part1(parsed: &Self::Parsed, params: &Self::Params) -> Answer;
+    fn part2(parsed: &Self::Parsed, params: &Self::Params) -> Answer;
+}
+
+pub struct Parsed(Box<dyn Any + Send>);
+
+/// The [`Solution::Params`] of a day.
+pub struct DayParams(Box<dyn Any + Send>);
+
+impl DayParams {
+    /// The parameters as the [`Solution::Params`] of their day. Panics for the type of another day.
+    pub fn get<P: Params>(&self) -> &P {
+        self.0.downcast_ref().expect("parameters of another day")
+    }
+}
+
+/// `(name, value)` pairs of parameters, applied in order.
+type Values = [(String, String)];
+
+/// A type-erased [`Solution`], so that all days can be kept in one registry.
+pub struct Day {
+    pub number: u8,
+    pub title: &'static str,
+    parse: fn(&[u8]) -> Result<Parsed, ParseError>,
+    params: fn(&Values) -> Result<DayParams, String>,
+    default_values: fn() -> Vec<(&'static str, String)>,
+    parts: [fn(&Parsed, &DayParams) -> Answer; 2],
+}
+
+fn parse_erased<S: Solution>(input: &[u8]) -> Result<Parsed, ParseError> {
+    S::parse(input).map(|parsed| Parsed(Box::new(parsed)))
+}
+
+fn params_erased<S: Solution>(values: &[(String, String)]) -> Result<DayParams, String> {
+    let mut params = S::Params::default();
+    for (name, value) in values {
+        params.set(name, value)?;
+    }
+    Ok(DayParams(Box::new(params)))
+}
+
+fn default_values<S: Solution>() -> Vec<(&'static str, String)> {
+    S::Params::default().values()
+}
+
+fn downcast<'a, S: Solution>(
+    parsed: &'a Parsed,
+    params: &'a DayParams,
+) -> (&'a S::Parsed, &'a S::Params) {
+    (
+        parsed
+            .0
+            .downcast_ref()
+            .expect("parsed input of another day"),
+        params.0.downcast_ref().expect("parameters of another day"),
+    )
+}
+
+fn part1_erased<S: Solution>(parsed: &Parsed, params: &DayParams) -> Answer {
+    let (parsed, params) = downcast::<S>(parsed, params);
+    S::part1(parsed, params)
+}
+
+fn part2_erased<S: Solution>(parsed: &Parsed, params: &DayParams) -> Answer {
+    let (parsed, params) = downcast::<S>(parsed, params);
+    S::part2(parsed, params)
+}
+
+impl Day {
+    pub const fn new<S: Solution>(number: u8, title: &'static str) -> Self {
+        Day {
+            number,
+            title,
+            parse: parse_erased::<S>,
+            params: params_erased::<S>,
+            default_values: default_values::<S>,
+            parts: [part1_erased::<S>, part2_erased::<S>],
+        }
+    }
+
+    pub fn parse(&self, input: &[u8]) -> Result<Parsed, ParseError> {
+        (self.parse)(input)
+    }
+
+    /// The default parameters with the given `(name, value)` pairs applied in order.
+    pub fn params(&self, values: &[(String, String)]) -> Result<DayParams, String> {
+        (self.params)(values)
+    }
+
+    pub fn default_params(&self) -> DayParams {
+        self.params(&[]).expect("default parameters")
+    }
+
+    /// The name and default value of every parameter.
+    pub fn default_values(&self) -> Vec<(&'static str, String)> {
+        (self.default_values)()
+    }
+
+    /// Panics if `part` is not 1 or 2.
+    pub fn solve(&self, parsed: &Parsed, params: &DayParams, part: u8) -> Answer {
+        self.parts[part as usize - 1](parsed, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum;
+
+    params! {
+        pub struct Params {
+            /// Added to the sum in part 1.
+            pub offset: u32 = 0,
+            pub label: String = String::from("digits"),
+        }
+    }
+
+    impl Solution for Sum {
+        type Parsed = Vec<u32>;
+        type Params = Params;
+
+        fn parse(input: &[u8]) -> Result<Self::Parsed, ParseError> {
+            input
+                .chunks(1)
+                .map(|digit| crate::parse::integer_at(input, digit))
+                .collect()
+        }
+
+        fn part1(parsed: &Self::Parsed, params: &Params) -> Answer {
+            (parsed.iter().sum::<u32>() + params.offset).into()
+        }
+
+        fn part2(parsed: &Self::Parsed, params: &Params) -> Answer {
+            Answer::Text(format!("{} {:?}", params.label, parsed))
+        }
+    }
+
+    #[test]
+    fn test_day() {
+        let day = Day::new::<Sum>(1, "Sum");
+        let parsed = day.parse(b"123").unwrap();
+        let params = day.default_params();
+        assert_eq!(day.solve(&parsed, &params, 1), Answer::Integer(6));
+        assert_eq!(
+            day.solve(&parsed, &params, 2),
+            Answer::Text(String::from("digits [1, 2, 3]"))
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let day = Day::new::<Sum>(1, "Sum");
+        let parsed = day.parse(b"123").unwrap();
+        let value = |name: &str, value: &str| (name.to_string(), value.to_string());
+        let params = day
+            .params(&[value("offset", "4"), value("offset", "10")])
+            .unwrap();
+        assert_eq!(day.solve(&parsed, &params, 1), Answer::Integer(16));
+        assert_eq!(
+            day.default_values(),
+            vec![
+                ("offset", String::from("0")),
+                ("label", String::from("digits"))
+            ]
+        );
+
+        let error = day.params(&[value("offset", "-1")]).err().unwrap();
+        assert_eq!(error, "invalid value `-1` for parameter `offset`");
+        let error = day.params(&[value("rounds", "1")]).err().unwrap();
+        assert_eq!(
+            error,
+            "unknown parameter `rounds`, expected `offset`, `label`"
+        );
+        assert!(super::Params::set(&mut (), "rounds", "1").is_err());
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let day = Day::new::<Sum>(1, "Sum");
+        let error = day.parse(b"12x").err().unwrap();
+        assert_eq!((error.line, error.column), (1, 3));
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(Answer::from(42_usize).to_string(), "42");
+        assert_eq!(Answer::from(-7_i64).to_string(), "-7");
+        assert_eq!(Answer::text(b"CMZ").to_string(), "CMZ");
+        assert_eq!(Answer::Art(String::from("#.\n.#\n")).to_string(), "#.\n.#");
+        assert_eq!(Answer::None.to_string(), "none");
+        assert_eq!(Answer::NoPart.to_string(), "-");
+    }
+}
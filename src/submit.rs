@@ -0,0 +1,352 @@
+use crate::client::{Client, Remote};
+use crate::solution::Answer;
+use regex::Regex;
+use std::fmt;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+pub fn default_path() -> PathBuf {
+    PathBuf::from("submissions.txt")
+}
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Response {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint in which direction.
+    Wrong,
+    /// The part was already solved, so the answer was not checked.
+    AlreadySolved,
+    /// Submitted too soon after the previous answer, so the answer was not checked.
+    Wait(Duration),
+}
+
+impl Response {
+    /// Parses the page returned for a submitted answer.
+    pub fn parse(page: &str) -> Result<Self, String> {
+        let message = message(page);
+        let wait = Regex::new(r"(?:(\d+)m )?(\d+)s left to wait").unwrap();
+
+        if message.contains("That's the right answer") {
+            Ok(Response::Correct)
+        } else if message.contains("That's not the right answer") {
+            Ok(if message.contains("your answer is too high") {
+                Response::TooHigh
+            } else if message.contains("your answer is too low") {
+                Response::TooLow
+            } else {
+                Response::Wrong
+            })
+        } else if let Some(captures) = wait.captures(&message) {
+            let number = |index| {
+                captures
+                    .get(index)
+                    .map_or(0, |number| number.as_str().parse::<u64>().unwrap_or(0))
+            };
+            Ok(Response::Wait(Duration::from_secs(
+                60 * number(1) + number(2),
+            )))
+        } else if message.contains("You don't seem to be solving the right level") {
+            Ok(Response::AlreadySolved)
+        } else {
+            Err(format!("unexpected response: {}", message))
+        }
+    }
+
+    /// The keyword stored in the submissions file, for responses that judge the answer.
+    fn keyword(&self) -> Option<&'static str> {
+        match self {
+            Response::Correct => Some("correct"),
+            Response::TooHigh => Some("too-high"),
+            Response::TooLow => Some("too-low"),
+            Response::Wrong => Some("wrong"),
+            Response::AlreadySolved | Response::Wait(_) => None,
+        }
+    }
+
+    fn from_keyword(keyword: &str) -> Option<Self> {
+        [
+            Response::Correct,
+            Response::TooHigh,
+            Response::TooLow,
+            Response::Wrong,
+        ]
+        .into_iter()
+        .find(|response| response.keyword() == Some(keyword))
+    }
+}
+
+impl fmt::Display for Response {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Response::Correct => write!(f, "correct"),
+            Response::TooHigh => write!(f, "wrong, too high"),
+            Response::TooLow => write!(f, "wrong, too low"),
+            Response::Wrong => write!(f, "wrong"),
+            Response::AlreadySolved => write!(f, "already solved"),
+            Response::Wait(duration) => {
+                write!(f, "submitted too soon, wait {}s", duration.as_secs())
+            }
+        }
+    }
+}
+
+/// The text of the `<article>` element of a page, without markup.
+fn message(page: &str) -> String {
+    let start = page.find("<article").unwrap_or(0);
+    let end = page[start..]
+        .find("</article>")
+        .map_or(page.len(), |end| start + end);
+
+    let mut text = String::new();
+    let mut in_tag = false;
+    for c in page[start..end].chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Submission {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub response: Response,
+}
+
+/// Answers submitted so far, so that known wrong answers are not sent again.
+///
+/// The file has one submission per line, `<day> <part> <response> <answer>`, where the
+/// response is `correct`, `too-high`, `too-low` or `wrong`.
+#[derive(Debug, Default)]
+pub struct Submissions {
+    path: PathBuf,
+    entries: Vec<Submission>,
+}
+
+impl Submissions {
+    /// Reads the submissions file at `path`, which does not have to exist yet.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(format!("cannot read {}: {}", path.display(), err)),
+        };
+
+        let mut entries = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let invalid = || format!("invalid line {} in {}", index + 1, path.display());
+            let mut fields = line.splitn(4, ' ');
+            let (Some(day), Some(part), Some(response), Some(answer)) =
+                (fields.next(), fields.next(), fields.next(), fields.next())
+            else {
+                return Err(invalid());
+            };
+            entries.push(Submission {
+                day: day.parse().map_err(|_| invalid())?,
+                part: part.parse().map_err(|_| invalid())?,
+                answer: answer.to_string(),
+                response: Response::from_keyword(response).ok_or_else(invalid)?,
+            });
+        }
+
+        Ok(Submissions {
+            path: path.to_path_buf(),
+            entries,
+        })
+    }
+
+    pub fn entries(&self) -> &[Submission] {
+        &self.entries
+    }
+
+    /// Returns why `answer` must not be submitted, if earlier submissions rule it out.
+    pub fn check(&self, day: u8, part: u8, answer: &str) -> Result<(), String> {
+        let value = answer.parse::<i128>().ok();
+
+        for entry in self.entries.iter() {
+            if entry.day != day || entry.part != part {
+                continue;
+            }
+            let both = value.zip(entry.answer.parse::<i128>().ok());
+            let ruled_out = match entry.response {
+                Response::Correct => true,
+                Response::TooHigh => {
+                    entry.answer == answer || both.is_some_and(|(value, high)| value >= high)
+                }
+                Response::TooLow => {
+                    entry.answer == answer || both.is_some_and(|(value, low)| value <= low)
+                }
+                Response::Wrong => entry.answer == answer,
+                Response::AlreadySolved | Response::Wait(_) => false,
+            };
+            if ruled_out {
+                return Err(format!(
+                    "day {} part {} already got `{}` for {}",
+                    day, part, entry.response, entry.answer
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Appends a submission to the file, unless the response did not judge the answer.
+    pub fn record(&mut self, submission: Submission) -> Result<(), String> {
+        let Some(keyword) = submission.response.keyword() else {
+            return Ok(());
+        };
+        let error = |err: std::io::Error| format!("cannot write {}: {}", self.path.display(), err);
+
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .map_err(error)?;
+        writeln!(
+            file,
+            "{} {} {} {}",
+            submission.day, submission.part, keyword, submission.answer
+        )
+        .map_err(error)?;
+
+        self.entries.push(submission);
+        Ok(())
+    }
+}
+
+/// Encodes an answer the way it is submitted, or returns `None` for answers that cannot be.
+pub fn encode(answer: &Answer) -> Option<String> {
+    match answer {
+        Answer::Integer(_) | Answer::Text(_) => Some(answer.to_string()),
+        Answer::Art(_) | Answer::None | Answer::NoPart => None,
+    }
+}
+
+/// Submits `answer` for a part, unless earlier submissions show that it is wrong, and records
+/// the response.
+pub fn submit(
+    remote: &Remote,
+    submissions: &mut Submissions,
+    day: u8,
+    part: u8,
+    answer: &str,
+) -> Result<Response, String> {
+    submissions.check(day, part, answer)?;
+
+    let client = Client::connect(remote)?;
+    let url = format!("{}/answer", client.day_url(day));
+    let page = client.post_form(&url, &[("level", &part.to_string()), ("answer", answer)])?;
+    let response = Response::parse(&String::from_utf8_lossy(&page))?;
+
+    submissions.record(Submission {
+        day,
+        part,
+        answer: answer.to_string(),
+        response,
+    })?;
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::testing::{serve, temp_dir, SESSION};
+
+    const CORRECT: &str = "<main>\n<article><p>That's the right answer!  You are <em>one gold \
+        star</em> closer to collecting enough star fruit.</p></article>\n</main>";
+    const TOO_HIGH: &str = "<main>\n<article><p>That's not the right answer; your answer is \
+        too high.  Please wait one minute before trying again.</p></article>\n</main>";
+    const WRONG: &str = "<article><p>That's not the right answer.  If you're stuck, make sure \
+        you're using the full input data.</p></article>";
+    const WAIT: &str = "<article><p>You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>";
+    const SOLVED: &str = "<article><p>You don't seem to be solving the right level.  Did you \
+        already complete it?</p></article>";
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(Response::parse(CORRECT), Ok(Response::Correct));
+        assert_eq!(Response::parse(TOO_HIGH), Ok(Response::TooHigh));
+        assert_eq!(
+            Response::parse(&TOO_HIGH.replace("high", "low")),
+            Ok(Response::TooLow)
+        );
+        assert_eq!(Response::parse(WRONG), Ok(Response::Wrong));
+        assert_eq!(
+            Response::parse(WAIT),
+            Ok(Response::Wait(Duration::from_secs(65)))
+        );
+        assert_eq!(Response::parse(SOLVED), Ok(Response::AlreadySolved));
+        assert!(Response::parse("<article>Something else</article>").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        let dir = temp_dir("submissions");
+        let path = dir.join("submissions.txt");
+        std::fs::write(&path, "1 1 too-high 100\n1 1 too-low 10\n1 2 wrong ABC\n").unwrap();
+        let submissions = Submissions::load(&path).unwrap();
+
+        assert_eq!(submissions.check(1, 1, "50"), Ok(()));
+        assert!(submissions.check(1, 1, "100").is_err());
+        assert!(submissions.check(1, 1, "120").is_err());
+        assert!(submissions.check(1, 1, "10").is_err());
+        assert!(submissions.check(1, 2, "ABC").is_err());
+        assert_eq!(submissions.check(1, 2, "ABD"), Ok(()));
+        assert_eq!(submissions.check(2, 1, "100"), Ok(()));
+
+        std::fs::write(&path, "1 1 maybe 100\n").unwrap();
+        assert!(Submissions::load(&path).is_err());
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_submit() {
+        let dir = temp_dir("submit");
+        let (url, server) = serve(vec![(200, TOO_HIGH), (200, WAIT), (200, CORRECT)]);
+        let remote = Remote {
+            url,
+            session: dir.join(".session"),
+        };
+        std::fs::write(&remote.session, SESSION).unwrap();
+        let path = dir.join("submissions.txt");
+        let mut submissions = Submissions::load(&path).unwrap();
+
+        assert_eq!(
+            submit(&remote, &mut submissions, 5, 2, "1234"),
+            Ok(Response::TooHigh)
+        );
+        assert!(submit(&remote, &mut submissions, 5, 2, "1234").is_err());
+        assert_eq!(
+            submit(&remote, &mut submissions, 5, 2, "1000"),
+            Ok(Response::Wait(Duration::from_secs(65)))
+        );
+        assert_eq!(
+            submit(&remote, &mut submissions, 5, 2, "1000"),
+            Ok(Response::Correct)
+        );
+        assert!(submit(&remote, &mut submissions, 5, 2, "1000").is_err());
+
+        let requests = server.join().unwrap();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0],
+            "POST /2022/day/5/answer HTTP/1.1\nlevel=2&answer=1234"
+        );
+        assert_eq!(
+            std::fs::read_to_string(&path).unwrap(),
+            "5 2 too-high 1234\n5 2 correct 1000\n"
+        );
+        assert_eq!(Submissions::load(&path).unwrap().entries().len(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}